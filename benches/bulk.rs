@@ -0,0 +1,58 @@
+//! Bulk generation into a numeric column versus a column of strings. Run
+//! with `cargo bench --bench bulk`.
+//!
+//! Generating flat out, one thread hits the 4096 IDs/ms the sequence allows
+//! (about 244 ns per ID) whichever path it takes, and waits out the rest of
+//! each millisecond. The string path's extra cost, an encode and an
+//! allocation per ID, is therefore also measured on its own: it is CPU time
+//! `fill_column` frees for the rest of the load, which shows as throughput
+//! once generation shares the thread with other work.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const COUNT: usize = 1_000_000;
+
+fn bench<T>(name: &str, mut f: impl FnMut() -> T) -> Duration {
+    // Warm up the allocator before timing
+    black_box(f());
+    let start = Instant::now();
+    black_box(f());
+    let elapsed = start.elapsed();
+    println!(
+        "{:<18} {:>8.2} ns/ID",
+        name,
+        elapsed.as_nanos() as f64 / COUNT as f64
+    );
+    elapsed
+}
+
+fn main() {
+    let numeric = bench("fill_column", || {
+        let mut column = Vec::new();
+        tiny64::fill_column(&mut column, COUNT);
+        column
+    });
+    let strings = bench("generate_tiny64", || {
+        (0..COUNT)
+            .map(|_| tiny64::generate_tiny64())
+            .collect::<Vec<String>>()
+    });
+    println!(
+        "fill_column speedup: {:.2}x (both capped by the sequence)",
+        strings.as_secs_f64() / numeric.as_secs_f64()
+    );
+
+    let mut column = Vec::new();
+    tiny64::fill_column(&mut column, COUNT);
+    let encoding = bench("encoding alone", || {
+        column
+            .iter()
+            .map(|&value| tiny64::base64_encode_u64(value))
+            .collect::<Vec<String>>()
+    });
+    println!(
+        "encoding costs {:.0}% of a capped fill_column's time per ID",
+        encoding.as_secs_f64() / numeric.as_secs_f64() * 100.0
+    );
+}
@@ -0,0 +1,40 @@
+//! Encoder micro-benchmarks. Run with `cargo bench --bench encode`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u64 = 2_000_000;
+
+fn bench(name: &str, f: impl Fn(u64) -> String) -> Duration {
+    // Warm up caches and the allocator before timing
+    for i in 0..ITERATIONS / 10 {
+        black_box(f(black_box(i)));
+    }
+
+    let start = Instant::now();
+    for i in 0..ITERATIONS {
+        black_box(f(black_box(i.wrapping_mul(0x9E37_79B9_7F4A_7C15))));
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "{:<20} {:>8.2} ns/op",
+        name,
+        elapsed.as_nanos() as f64 / ITERATIONS as f64
+    );
+    elapsed
+}
+
+fn main() {
+    let safe = bench("base64_encode_u64", tiny64::base64_encode_u64);
+    let unchecked = bench("encode_unchecked", tiny64::encode_unchecked);
+    println!(
+        "encode_unchecked speedup: {:.2}x",
+        safe.as_secs_f64() / unchecked.as_secs_f64()
+    );
+    let base62 = bench("encode_base62", tiny64::encode_base62);
+    println!(
+        "encode_base62 slowdown:   {:.2}x",
+        base62.as_secs_f64() / safe.as_secs_f64()
+    );
+}
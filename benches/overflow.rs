@@ -0,0 +1,82 @@
+//! Latency of each sequence-overflow policy when generating faster than
+//! 4096 IDs/ms. Run with `cargo bench --bench overflow`.
+//!
+//! Every call is timed individually so the tail shows the cost of waiting out
+//! a millisecond. The policies trade that tail for something else: `bounded`
+//! with a zero timeout fails instead, and `saturating` reuses the last
+//! sequence number, leaving only the random bits to tell IDs apart.
+
+use std::collections::HashSet;
+use std::hint::black_box;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Far more than the 4096 IDs one millisecond can hold at full speed
+const CALLS: usize = 500_000;
+
+struct Report {
+    latencies: Vec<Duration>,
+    failures: usize,
+    duplicates: usize,
+}
+
+fn run(f: fn() -> Option<String>) -> Report {
+    let mut latencies = Vec::with_capacity(CALLS);
+    let mut ids = Vec::with_capacity(CALLS);
+    for _ in 0..CALLS {
+        let start = Instant::now();
+        let id = black_box(f());
+        latencies.push(start.elapsed());
+        ids.push(id);
+    }
+
+    // Tally outside the timed loop so it does not slow generation down
+    let mut seen = HashSet::with_capacity(CALLS);
+    let mut failures = 0;
+    let mut duplicates = 0;
+    for id in ids {
+        match id {
+            Some(id) => {
+                if !seen.insert(id) {
+                    duplicates += 1;
+                }
+            }
+            None => failures += 1,
+        }
+    }
+
+    Report {
+        latencies,
+        failures,
+        duplicates,
+    }
+}
+
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    sorted[((sorted.len() - 1) as f64 * p) as usize]
+}
+
+fn bench(name: &str, f: fn() -> Option<String>) {
+    // Each policy gets a fresh thread, and with it fresh thread-local state
+    let mut report = thread::spawn(move || run(f)).join().unwrap();
+    report.latencies.sort_unstable();
+
+    println!(
+        "{:<12} p50 {:>8.0?} p99 {:>8.0?} max {:>10.0?} failed {:>7} duplicates {:>7}",
+        name,
+        percentile(&report.latencies, 0.50),
+        percentile(&report.latencies, 0.99),
+        report.latencies[CALLS - 1],
+        report.failures,
+        report.duplicates,
+    );
+}
+
+fn main() {
+    println!("{} calls per policy", CALLS);
+    bench("spin", || Some(tiny64::generate_tiny64()));
+    bench("bounded", || {
+        tiny64::generate_tiny64_bounded(Duration::ZERO).ok()
+    });
+    bench("saturating", || Some(tiny64::generate_saturating()));
+}
@@ -0,0 +1,80 @@
+//! Latency and CPU cost of waiting for the next millisecond under different
+//! spin thresholds. Run with `cargo bench --bench wait`.
+//!
+//! Each run calls `align_to_next_ms` repeatedly, which performs the same wait
+//! as a sequence overflow. Latency is how far into the new millisecond the
+//! wait returns; CPU is the share of the waiting time the thread spent on a
+//! core. Pure spinning gives the lowest latency at the cost of a full core.
+//! Sleeping costs some latency but frees the core, and the saving grows with
+//! the wait, so it matters most on coarse clocks where every wait lasts until
+//! the next clock tick. The default threshold spins for only the first few
+//! microseconds of a wait, so most waits end up sleeping: CPU stays near the
+//! sleeping row, while a wait that ends within the threshold still returns
+//! as fast as pure spinning.
+//!
+//! CPU time comes from `/proc/thread-self/schedstat` and is shown as `n/a`
+//! where that file doesn't exist.
+
+use std::fs;
+use std::hint::black_box;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use tiny64::{DEFAULT_SPIN_THRESHOLD, align_to_next_ms, set_spin_threshold};
+
+const WAITS: usize = 500;
+
+/// Nanoseconds this thread has spent running, if the kernel reports it
+fn thread_cpu_ns() -> Option<u64> {
+    let stat = fs::read_to_string("/proc/thread-self/schedstat").ok()?;
+    stat.split_whitespace().next()?.parse().ok()
+}
+
+/// How far past the last whole millisecond the system clock is
+fn into_ms() -> Duration {
+    let since = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    Duration::from_nanos((since.subsec_nanos() % 1_000_000) as u64)
+}
+
+fn bench(name: &str, threshold: Duration) {
+    let report = thread::spawn(move || {
+        set_spin_threshold(threshold);
+        let mut latencies = Vec::with_capacity(WAITS);
+        let cpu_start = thread_cpu_ns();
+        let start = Instant::now();
+        for _ in 0..WAITS {
+            black_box(align_to_next_ms());
+            latencies.push(into_ms());
+        }
+        let wall = start.elapsed();
+        let cpu = cpu_start
+            .zip(thread_cpu_ns())
+            .map(|(before, after)| Duration::from_nanos(after - before));
+        (latencies, wall, cpu)
+    })
+    .join()
+    .unwrap();
+
+    let (mut latencies, wall, cpu) = report;
+    latencies.sort();
+    let cpu = match cpu {
+        Some(cpu) => format!("{:>5.1}%", cpu.as_secs_f64() / wall.as_secs_f64() * 100.0),
+        None => "   n/a".to_string(),
+    };
+    println!(
+        "{:<14} p50 {:>9.1?}  p99 {:>9.1?}  cpu {}",
+        name,
+        latencies[WAITS / 2],
+        latencies[WAITS * 99 / 100],
+        cpu
+    );
+}
+
+fn main() {
+    println!("{} waits for the next millisecond per threshold", WAITS);
+    bench("spin", Duration::MAX);
+    bench("default (20us)", DEFAULT_SPIN_THRESHOLD);
+    bench("100us", Duration::from_micros(100));
+    bench("sleep", Duration::ZERO);
+    set_spin_threshold(DEFAULT_SPIN_THRESHOLD);
+}
@@ -0,0 +1,69 @@
+//! IDs minted together with an audit log line saying why.
+
+use alloc::string::String;
+use core::fmt::Write;
+
+use crate::datetime::format_iso8601;
+use crate::parts::Tiny64Parts;
+
+/// Generate an ID and a log line recording it with its mint time and
+/// `context`
+///
+/// The line has a fixed shape, with the three fields always in this order:
+///
+/// ```text
+/// id=Obrl8O3--Cw time=2024-01-15T10:30:45.123Z context="user 42 signed up"
+/// ```
+///
+/// The time is the ID's own timestamp, so the two can never disagree. The
+/// context is quoted and escaped as a JSON string (`\"`, `\\`, `\n`, `\r`,
+/// `\t`, and `\u00XX` for other ASCII control characters), so the line stays
+/// on one line whatever the context holds, and everything after `context=`
+/// can be read back with any JSON parser.
+pub fn generate_with_context(context: &str) -> (String, String) {
+    let value = crate::generate_tiny64_u64();
+    let id = crate::base64_encode_u64(value);
+    let timestamp_ms = Tiny64Parts::from_u64(value).timestamp_ms;
+
+    let mut line = String::with_capacity(context.len() + 64);
+    line.push_str("id=");
+    line.push_str(&id);
+    line.push_str(" time=");
+    line.push_str(&format_iso8601(timestamp_ms));
+    line.push_str(" context=\"");
+    for ch in context.chars() {
+        match ch {
+            '"' => line.push_str("\\\""),
+            '\\' => line.push_str("\\\\"),
+            '\n' => line.push_str("\\n"),
+            '\r' => line.push_str("\\r"),
+            '\t' => line.push_str("\\t"),
+            ch if ch < ' ' || ch == '\u{7F}' => {
+                write!(line, "\\u{:04x}", ch as u32).unwrap();
+            }
+            ch => line.push(ch),
+        }
+    }
+    line.push('"');
+    (id, line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_line() {
+        let (id, line) = generate_with_context("user 42 signed up");
+        let time = format_iso8601(crate::timestamp_of(&id).unwrap());
+        assert_eq!(
+            line,
+            format!("id={} time={} context=\"user 42 signed up\"", id, time)
+        );
+
+        let (id, line) = generate_with_context("say \"hi\"\n\\ \u{1}\u{7F} é");
+        let context = line.split_once(" context=").unwrap().1;
+        assert_eq!(context, r#""say \"hi\"\n\\ \u0001\u007f é""#);
+        assert!(line.starts_with(&format!("id={} time=", id)));
+    }
+}
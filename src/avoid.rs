@@ -0,0 +1,96 @@
+//! IDs screened against a blacklist of substrings.
+
+use alloc::string::String;
+
+use crate::error::Tiny64Error;
+
+/// IDs [`generate_avoiding`] draws before giving up
+pub const AVOIDING_ATTEMPTS: usize = 64;
+
+/// Characters of an ID that hold only timestamp bits: 7 characters of 6
+/// bits cover the 42-bit timestamp exactly
+const TIMESTAMP_CHARS: usize = 7;
+
+/// Generate an ID that contains none of the substrings in `blacklist`
+///
+/// Each attempt mints a fresh ID, which takes the next sequence number and
+/// new random bits and so changes the last four characters. The first seven
+/// hold the timestamp and stay the same for the rest of the millisecond, so
+/// when a match reaches into them the next attempt first waits for the clock
+/// to tick. After [`AVOIDING_ATTEMPTS`] rejected IDs this fails with
+/// [`Tiny64Error::RetriesExhausted`]; a word in the leading characters, which
+/// change only every few seconds or more, fails that way until it scrolls
+/// past. Matching is exact and case-sensitive, so list each spelling to be
+/// avoided; empty entries are ignored.
+///
+/// The IDs returned still sort in the order they were returned: retries
+/// only leave gaps between them. Rejected IDs use up sequence numbers,
+/// though, and waits cost up to a millisecond each. Words of four or more
+/// characters are rare in random IDs, so retries are too; very short
+/// entries can reject most IDs, such as `-`, which stands for the low
+/// sequence numbers nearly every millisecond starts with.
+pub fn generate_avoiding(blacklist: &[&str]) -> Result<String, Tiny64Error> {
+    for _ in 0..AVOIDING_ATTEMPTS {
+        let id = crate::generate_tiny64();
+        match first_match(&id, blacklist) {
+            None => return Ok(id),
+            Some(start) if start < TIMESTAMP_CHARS => {
+                crate::align_to_next_ms();
+            }
+            Some(_) => {}
+        }
+    }
+    Err(Tiny64Error::RetriesExhausted(AVOIDING_ATTEMPTS))
+}
+
+/// Where the earliest blacklisted substring in `id` starts
+fn first_match(id: &str, blacklist: &[&str]) -> Option<usize> {
+    blacklist
+        .iter()
+        .filter(|word| !word.is_empty())
+        .filter_map(|word| id.find(word))
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_avoiding() {
+        // Every ID minted now starts with the same few timestamp characters
+        let head: String = crate::generate_tiny64().chars().take(2).collect();
+        assert_eq!(
+            generate_avoiding(&[&head]),
+            Err(Tiny64Error::RetriesExhausted(AVOIDING_ATTEMPTS))
+        );
+
+        // Ban 8 characters that the slowly changing head of the ID won't
+        // reach during the test, so retries can always get past them. They
+        // come from the end of the alphabet: the low sequence numbers of a
+        // quiet millisecond encode as its first characters.
+        let alphabet = crate::encode::BASE64_ALPHABET;
+        let now = crate::generate_tiny64().into_bytes();
+        let tick = alphabet.iter().position(|&b| b == now[5]).unwrap();
+        let banned: Vec<String> = alphabet
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|&(index, b)| !now[..6].contains(b) && !(tick..tick + 3).contains(&index))
+            .take(8)
+            .map(|(_, &b)| (b as char).to_string())
+            .collect();
+        let blacklist: Vec<&str> = banned.iter().map(String::as_str).collect();
+
+        let mut previous = String::new();
+        for _ in 0..100 {
+            let id = generate_avoiding(&blacklist).unwrap();
+            assert!(blacklist.iter().all(|word| !id.contains(word)), "{}", id);
+            assert!(id > previous);
+            previous = id;
+        }
+        assert!(generate_avoiding(&[""]).is_ok());
+        assert_eq!(first_match("abcabc", &["ca", "bc", ""]), Some(1));
+        assert_eq!(first_match("abcabc", &["x"]), None);
+    }
+}
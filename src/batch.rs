@@ -0,0 +1,408 @@
+//! Helpers for producing and inspecting many IDs at once.
+
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::io::{self, BufRead, Write};
+
+#[cfg(feature = "std")]
+use crate::ENCODED_LEN;
+use crate::decode::{ParseError, decode_u64};
+use crate::parts::Tiny64Parts;
+
+/// Aggregates over a batch of IDs, from [`analyze`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BatchStats {
+    /// Number of IDs in the batch
+    pub count: usize,
+    /// Number of distinct IDs among them
+    pub unique_count: usize,
+    /// Earliest timestamp, `None` for an empty batch
+    pub min_time: Option<u64>,
+    /// Latest timestamp, `None` for an empty batch
+    pub max_time: Option<u64>,
+    /// Highest sequence number of any ID
+    pub max_sequence_seen: u16,
+    /// Number of distinct milliseconds the IDs were minted in
+    pub timestamps_spanned: usize,
+}
+
+/// Join IDs with `sep`, with no trailing separator
+///
+/// The output buffer is sized exactly from the strings' lengths before
+/// anything is copied, so it is allocated once however long each element
+/// is: bare IDs, quoted JSON elements or `--format` lines alike.
+pub fn ids_to_string(ids: impl IntoIterator<Item = String>, sep: &str) -> String {
+    let ids: Vec<String> = ids.into_iter().collect();
+    let len = ids.iter().map(String::len).sum::<usize>() + ids.len().saturating_sub(1) * sep.len();
+    let mut out = String::with_capacity(len);
+
+    for (i, id) in ids.iter().enumerate() {
+        if i > 0 {
+            out.push_str(sep);
+        }
+        out.push_str(id);
+    }
+    out
+}
+
+/// Decode a batch of IDs and summarise it
+///
+/// Handy for checking a generated batch: `unique_count < count` means
+/// duplicates, and `max_sequence_seen` against `count / timestamps_spanned`
+/// shows how full the busiest millisecond got. Fails on the first ID that
+/// doesn't decode.
+pub fn analyze(ids: &[String]) -> Result<BatchStats, ParseError> {
+    let mut values = BTreeSet::new();
+    let mut timestamps = BTreeSet::new();
+    let mut max_sequence_seen = 0;
+    for id in ids {
+        let value = decode_u64(id)?;
+        let parts = Tiny64Parts::from_u64(value);
+        values.insert(value);
+        timestamps.insert(parts.timestamp_ms);
+        max_sequence_seen = max_sequence_seen.max(parts.sequence);
+    }
+
+    Ok(BatchStats {
+        count: ids.len(),
+        unique_count: values.len(),
+        min_time: timestamps.first().copied(),
+        max_time: timestamps.last().copied(),
+        max_sequence_seen,
+        timestamps_spanned: timestamps.len(),
+    })
+}
+
+/// The IDs that occur more than once in `ids`, each with its number of
+/// occurrences, in ascending order
+///
+/// Compares the strings as given, so it works on any layout or encoding
+/// without decoding anything.
+pub fn find_duplicates(ids: &[String]) -> Vec<(&str, usize)> {
+    let mut sorted: Vec<&str> = ids.iter().map(String::as_str).collect();
+    sorted.sort_unstable();
+    sorted
+        .chunk_by(|a, b| a == b)
+        .filter(|run| run.len() > 1)
+        .map(|run| (run[0], run.len()))
+        .collect()
+}
+
+/// Generate `count` IDs and write them to `w` as a JSON array of strings
+///
+/// Each ID is encoded into a stack buffer and written straight out, so no
+/// string is allocated per ID or for the array as a whole. Every ID goes out
+/// in its own `write_all`; wrap unbuffered writers such as sockets in a
+/// `BufWriter`. Tiny64 characters never need escaping in JSON.
+#[cfg(feature = "std")]
+pub fn write_json_array<W: Write>(mut w: W, count: usize) -> io::Result<()> {
+    // `,"` + ID + `"`; the first element skips the comma
+    let mut element = [b'"'; ENCODED_LEN + 3];
+    element[0] = b',';
+
+    w.write_all(b"[")?;
+    for i in 0..count {
+        element[2..2 + ENCODED_LEN]
+            .copy_from_slice(&crate::base64_encode_u64_const(crate::generate_tiny64_u64()));
+        let start = if i == 0 { 1 } else { 0 };
+        w.write_all(&element[start..])?;
+    }
+    w.write_all(b"]")
+}
+
+/// Write each ID to `w` followed by `terminator`
+///
+/// Every record is terminated, the last one included, which is what
+/// record-oriented readers expect: `b"\n"` for line-based tools, `b"\0"` for
+/// `xargs -0`, or the ASCII record separator `b"\x1e"`. Nothing is buffered;
+/// wrap unbuffered writers in a `BufWriter`.
+#[cfg(feature = "std")]
+pub fn write_delimited<W: Write>(
+    mut w: W,
+    ids: impl IntoIterator<Item = impl AsRef<str>>,
+    terminator: &[u8],
+) -> io::Result<()> {
+    for id in ids {
+        w.write_all(id.as_ref().as_bytes())?;
+        w.write_all(terminator)?;
+    }
+    Ok(())
+}
+
+/// Append `count` fresh packed IDs to `buffer`, for numeric bulk loads
+///
+/// The fastest way to mint many IDs: capacity is reserved once up front and
+/// nothing is encoded, so each ID costs one call to
+/// [`generate_tiny64_u64`](crate::generate_tiny64_u64). The values are in
+/// increasing order and come after any the same thread minted before. Flat
+/// out, a thread is held to 4096 IDs per millisecond on either path, so the
+/// saving over collecting strings shows up as CPU left for other work rather
+/// than as more IDs per second; `cargo bench --bench bulk` measures both.
+#[cfg(feature = "std")]
+pub fn fill_column(buffer: &mut Vec<u64>, count: usize) {
+    buffer.reserve(count);
+    buffer.extend((0..count).map(|_| crate::generate_tiny64_u64()));
+}
+
+/// Error from [`read_batch_with_header`]
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub enum ReadBatchError {
+    Io(io::Error),
+    /// The first line is not a valid header
+    Header(crate::BatchHeaderError),
+    /// `line` is 1-based, counting the header
+    Parse {
+        line: usize,
+        error: ParseError,
+    },
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Display for ReadBatchError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ReadBatchError::Io(e) => write!(f, "read failed: {}", e),
+            ReadBatchError::Header(e) => write!(f, "line 1: {}", e),
+            ReadBatchError::Parse { line, error } => write!(f, "line {}: {}", line, error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ReadBatchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadBatchError::Io(e) => Some(e),
+            ReadBatchError::Header(e) => Some(e),
+            ReadBatchError::Parse { error, .. } => Some(error),
+        }
+    }
+}
+
+/// Write `header` as the first line of a batch file, then each ID on its
+/// own line
+///
+/// See [`BatchHeader`](crate::BatchHeader) for the header format. Read the
+/// file back with [`read_batch_with_header`], which decodes every ID with
+/// the layout and epoch the header records.
+#[cfg(feature = "std")]
+pub fn write_batch_with_header<W: Write>(
+    mut w: W,
+    header: &crate::BatchHeader,
+    ids: impl IntoIterator<Item = impl AsRef<str>>,
+) -> io::Result<()> {
+    writeln!(w, "{}", header)?;
+    write_delimited(w, ids, b"\n")
+}
+
+/// Read a batch file written by [`write_batch_with_header`] and decode its
+/// IDs as its header says
+///
+/// Each ID is unpacked with the header's layout, and its timestamp is
+/// shifted by the header's epoch, so every `timestamp_ms` comes back in
+/// milliseconds since the Unix epoch whatever the file was minted with.
+/// Surrounding whitespace is trimmed and blank lines are skipped. Fails if
+/// the first line is not a header, so a file without one is never decoded
+/// with a guessed layout, and on the first ID that doesn't decode.
+#[cfg(feature = "std")]
+pub fn read_batch_with_header<R: BufRead>(
+    r: R,
+) -> Result<(crate::BatchHeader, Vec<Tiny64Parts>), ReadBatchError> {
+    let mut lines = r.lines();
+    let first = lines.next().transpose().map_err(ReadBatchError::Io)?;
+    let header: crate::BatchHeader = first
+        .unwrap_or_default()
+        .parse()
+        .map_err(ReadBatchError::Header)?;
+
+    let settings = header.settings;
+    let mut parts = Vec::new();
+    for (index, line) in lines.enumerate() {
+        let line = line.map_err(ReadBatchError::Io)?;
+        let id = line.trim();
+        if id.is_empty() {
+            continue;
+        }
+        let value = decode_u64(id).map_err(|error| ReadBatchError::Parse {
+            line: index + 2,
+            error,
+        })?;
+        let mut id_parts = settings.layout.unpack(value);
+        id_parts.timestamp_ms = id_parts.timestamp_ms.saturating_add(settings.epoch_ms);
+        parts.push(id_parts);
+    }
+    Ok((header, parts))
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::generate_tiny64;
+    use crate::parts::id_from_fields;
+
+    #[test]
+    fn test_ids_to_string() {
+        let ids: Vec<String> = (0..3).map(|_| generate_tiny64()).collect();
+        let joined = ids_to_string(ids.clone(), ", ");
+        assert_eq!(joined, format!("{}, {}, {}", ids[0], ids[1], ids[2]));
+        assert_eq!(joined.capacity(), joined.len());
+
+        assert_eq!(ids_to_string(Vec::new(), ","), "");
+        assert_eq!(ids_to_string(vec![ids[0].clone()], ","), ids[0]);
+
+        // Longer elements than bare IDs are sized exactly too
+        let lines: Vec<String> = ids.iter().map(|id| format!("\"{}\"", id)).collect();
+        let joined = ids_to_string(lines, ",\n");
+        assert_eq!(joined.len(), 3 * 13 + 2 * 2);
+        assert_eq!(joined.capacity(), joined.len());
+    }
+
+    #[test]
+    fn test_find_duplicates() {
+        let ids: Vec<String> = ["b", "a", "c", "b", "a", "b"]
+            .iter()
+            .map(|id| id.to_string())
+            .collect();
+        assert_eq!(find_duplicates(&ids), [("a", 2), ("b", 3)]);
+
+        let unique: Vec<String> = (0..1000).map(|_| generate_tiny64()).collect();
+        assert!(find_duplicates(&unique).is_empty());
+    }
+
+    #[test]
+    fn test_write_delimited() {
+        let mut out = Vec::new();
+        write_delimited(&mut out, ["a", "b"], b"\x1e").unwrap();
+        assert_eq!(out, b"a\x1eb\x1e");
+
+        let ids: Vec<String> = (0..3).map(|_| generate_tiny64()).collect();
+        let mut out = Vec::new();
+        write_delimited(&mut out, &ids, b"\n").unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), ids.join("\n") + "\n");
+    }
+
+    #[test]
+    fn test_batch_with_header() {
+        let settings = crate::Settings {
+            epoch_ms: 1_700_000_000_000,
+            layout: crate::Layout::new(40, 14, 10).unwrap(),
+        };
+        let header = crate::BatchHeader::new(settings, None);
+        let minted = Tiny64Parts {
+            timestamp_ms: 5_000,
+            sequence: 9_000,
+            random: 3,
+        };
+        let id = crate::base64_encode_u64(settings.layout.pack(&minted).unwrap());
+
+        let mut out = Vec::new();
+        write_batch_with_header(&mut out, &header, [&id, &id]).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text, format!("{}\n{}\n{}\n", header, id, id));
+
+        let (read_header, parts) = read_batch_with_header(text.as_bytes()).unwrap();
+        assert_eq!(read_header, header);
+        let expected = Tiny64Parts {
+            timestamp_ms: 1_700_000_005_000,
+            ..minted
+        };
+        assert_eq!(parts, [expected, expected]);
+
+        let (_, parts) = read_batch_with_header(format!("{}\n", header).as_bytes()).unwrap();
+        assert!(parts.is_empty());
+    }
+
+    #[test]
+    fn test_read_batch_with_header_errors() {
+        let id = generate_tiny64();
+        match read_batch_with_header(format!("{}\n", id).as_bytes()) {
+            Err(ReadBatchError::Header(crate::BatchHeaderError::Missing)) => {}
+            other => panic!("expected a missing header, got {:?}", other),
+        }
+        match read_batch_with_header(&b""[..]) {
+            Err(ReadBatchError::Header(crate::BatchHeaderError::Missing)) => {}
+            other => panic!("expected a missing header, got {:?}", other),
+        }
+
+        let header = crate::BatchHeader::new(crate::Settings::default(), None);
+        let text = format!("{}\n{}\n\nbad\n", header, id);
+        match read_batch_with_header(text.as_bytes()) {
+            Err(ReadBatchError::Parse { line, error }) => {
+                assert_eq!(line, 4);
+                assert_eq!(error, ParseError::InvalidLength(3));
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_fill_column() {
+        let mut column = vec![7];
+        fill_column(&mut column, 5000);
+        assert_eq!(column.len(), 5001);
+        assert_eq!(column[0], 7);
+        assert!(column[1..].windows(2).all(|pair| pair[0] < pair[1]));
+        fill_column(&mut column, 0);
+        assert_eq!(column.len(), 5001);
+    }
+
+    #[test]
+    fn test_analyze() {
+        let ids = vec![
+            id_from_fields(1_000, 0, 7),
+            id_from_fields(1_000, 1, 3),
+            id_from_fields(1_002, 0, 9),
+            id_from_fields(1_005, 4, 1),
+            id_from_fields(1_005, 4, 1),
+        ];
+        assert_eq!(
+            analyze(&ids),
+            Ok(BatchStats {
+                count: 5,
+                unique_count: 4,
+                min_time: Some(1_000),
+                max_time: Some(1_005),
+                max_sequence_seen: 4,
+                timestamps_spanned: 3,
+            })
+        );
+
+        assert_eq!(analyze(&[]), Ok(BatchStats::default()));
+        assert_eq!(
+            analyze(&[ids[0].clone(), "short".to_string()]),
+            Err(ParseError::InvalidLength(5))
+        );
+    }
+
+    #[test]
+    fn test_write_json_array() {
+        let json_for = |count| {
+            let mut out = Vec::new();
+            write_json_array(&mut out, count).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+        assert_eq!(json_for(0), "[]");
+
+        let one = json_for(1);
+        assert_eq!(one.len(), ENCODED_LEN + 4);
+        assert!(one.starts_with("[\"") && one.ends_with("\"]"));
+        assert!(crate::decode_u64(&one[2..2 + ENCODED_LEN]).is_ok());
+
+        let many = json_for(50);
+        let ids: Vec<&str> = many[1..many.len() - 1]
+            .split(',')
+            .map(|element| {
+                element
+                    .strip_prefix('"')
+                    .unwrap()
+                    .strip_suffix('"')
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(ids.len(), 50);
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
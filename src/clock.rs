@@ -0,0 +1,505 @@
+//! Time sources, and a generator driven entirely by caller-supplied sources.
+
+use alloc::string::String;
+use core::convert::Infallible;
+
+use crate::encode::base64_encode_u64;
+use crate::layout::Layout;
+use crate::random::RandomSource;
+
+/// A source of millisecond timestamps for ID generation
+///
+/// Implemented for [`SystemClock`] (with the `std` feature) and for any
+/// `FnMut() -> u64` closure, so targets without `std` can read a hardware
+/// timer or RTC instead.
+///
+/// The generators use nothing but `now_ms` for time, so any monotonic source
+/// works: a hardware counter, or a hybrid logical clock (HLC) when wall time
+/// is unreliable. Readings should be milliseconds since the Unix epoch so the
+/// IDs decode to meaningful times, and should never go backwards. If they do,
+/// the generators keep counting from the last timestamp rather than reuse a
+/// slot, but stay stuck on it until the clock catches up.
+///
+/// An HLC only needs its physical component in milliseconds:
+///
+/// ```
+/// use tiny64::{Clock, ClockGenerator, FixedRandom};
+///
+/// /// Stand-in for an HLC shared with the rest of the system
+/// struct Hlc {
+///     physical_ms: u64,
+/// }
+///
+/// impl Clock for Hlc {
+///     fn now_ms(&mut self) -> u64 {
+///         // A real HLC would merge in timestamps seen from peers here
+///         self.physical_ms += 1;
+///         self.physical_ms
+///     }
+/// }
+///
+/// let hlc = Hlc { physical_ms: 1_700_000_000_000 };
+/// let mut generator = ClockGenerator::new(hlc, FixedRandom::new(0));
+/// assert!(generator.generate() < generator.generate());
+/// ```
+pub trait Clock {
+    /// Milliseconds since the Unix epoch
+    fn now_ms(&mut self) -> u64;
+}
+
+impl<F: FnMut() -> u64> Clock for F {
+    fn now_ms(&mut self) -> u64 {
+        self()
+    }
+}
+
+/// The operating system's wall clock, via `SystemTime`
+///
+/// Requires the `std` feature.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now_ms(&mut self) -> u64 {
+        crate::generator::current_time_ms()
+    }
+}
+
+/// A clock that starts at a fixed time and advances 1 ms on every reading
+///
+/// Its readings depend only on the start time and how often it has been read,
+/// so together with a [`SeededRandom`](crate::SeededRandom) it makes a
+/// generator fully reproducible. Once it reaches `u64::MAX` it stays there.
+#[derive(Debug, Clone, Copy)]
+pub struct SteppingClock {
+    next_ms: u64,
+}
+
+impl SteppingClock {
+    pub fn new(start_ms: u64) -> Self {
+        SteppingClock { next_ms: start_ms }
+    }
+}
+
+impl Clock for SteppingClock {
+    fn now_ms(&mut self) -> u64 {
+        let now = self.next_ms;
+        self.next_ms = self.next_ms.saturating_add(1);
+        now
+    }
+}
+
+/// Last minted timestamp/sequence slot of a monotonic generator
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct SequenceState {
+    pub(crate) last_timestamp_ms: u64,
+    pub(crate) sequence: u16,
+}
+
+/// What [`SequenceState::try_reserve`] does while a full millisecond waits
+/// for the clock
+pub(crate) enum Stall {
+    /// Keep spinning
+    Wait,
+    /// Give up waiting and hand out the millisecond's last sequence number
+    /// again
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    Saturate,
+}
+
+impl SequenceState {
+    /// Claim `n` consecutive sequence numbers within one millisecond and
+    /// return the timestamp, the first sequence number, and whether it had
+    /// to wait for the clock
+    ///
+    /// Never goes backwards, even if the clock does. If the current
+    /// millisecond has fewer than `n` slots left, the block starts at sequence
+    /// 0 of the next millisecond. `capacity` is the number of sequence values
+    /// per millisecond, and `n` must not exceed it.
+    #[cfg(feature = "std")]
+    pub(crate) fn reserve(
+        &mut self,
+        n: u16,
+        capacity: usize,
+        clock: &mut impl Clock,
+    ) -> (u64, u16, bool) {
+        match self.try_reserve(n, capacity, clock, || Ok::<_, Infallible>(Stall::Wait)) {
+            Ok(reserved) => reserved,
+        }
+    }
+
+    /// Like [`reserve`](Self::reserve), but asks `on_full` what to do each
+    /// time it would spin waiting for the clock
+    ///
+    /// If `on_full` fails, the state is left as it was. On
+    /// [`Stall::Saturate`] the state is left as it was too and the last
+    /// sequence number of the current millisecond is returned, so `n` must be
+    /// 1.
+    pub(crate) fn try_reserve<E>(
+        &mut self,
+        n: u16,
+        capacity: usize,
+        clock: &mut impl Clock,
+        mut on_full: impl FnMut() -> Result<Stall, E>,
+    ) -> Result<(u64, u16, bool), E> {
+        debug_assert!(n as usize <= capacity);
+        let mut now = clock.now_ms().max(self.last_timestamp_ms);
+        let mut waited = false;
+
+        let start = if now == self.last_timestamp_ms {
+            let next = self.sequence as usize + 1;
+            if next + n as usize > capacity {
+                // Not enough room: wait until the clock passes the last timestamp
+                loop {
+                    now = clock.now_ms();
+                    if now > self.last_timestamp_ms {
+                        break;
+                    }
+                    match on_full()? {
+                        Stall::Wait => core::hint::spin_loop(),
+                        Stall::Saturate => {
+                            debug_assert_eq!(n, 1);
+                            return Ok((self.last_timestamp_ms, self.sequence, false));
+                        }
+                    }
+                }
+                waited = true;
+                0
+            } else {
+                next as u16
+            }
+        } else {
+            0
+        };
+
+        if n > 0 {
+            self.last_timestamp_ms = now;
+            self.sequence = start + n - 1;
+        }
+        Ok((now, start, waited))
+    }
+}
+
+/// A monotonic generator that reads time and randomness only from the
+/// [`Clock`] and [`RandomSource`] it is given
+///
+/// It depends on nothing but `core` and `alloc`, so it is the generator to use
+/// when building without the `std` feature. Like
+/// [`Tiny64Generator`](crate::Tiny64Generator), it never mints an ID less than
+/// or equal to the last one, and spins on the clock when a millisecond's
+/// sequence numbers run out.
+#[derive(Debug, Clone)]
+pub struct ClockGenerator<C, R> {
+    pub(crate) state: SequenceState,
+    stats: GeneratorStats,
+    layout: Layout,
+    time_granularity_ms: u64,
+    epoch_ms: u64,
+    clock: C,
+    random: R,
+}
+
+/// Counters describing how hard a generator has been driven
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GeneratorStats {
+    /// Number of IDs minted
+    pub generated: u64,
+    /// Timestamp of the last ID minted
+    pub last_timestamp_ms: u64,
+    /// Highest sequence number reached in any millisecond
+    pub max_sequence: u16,
+    /// Times a millisecond's sequence numbers ran out and the generator spun
+    /// until the clock advanced
+    pub spins: u64,
+    /// Times the clock read earlier than the last ID's timestamp
+    pub clock_backwards: u64,
+}
+
+impl GeneratorStats {
+    /// Count `n` IDs minted from one [`SequenceState::reserve`] call
+    pub(crate) fn record(
+        &mut self,
+        n: u16,
+        timestamp_ms: u64,
+        start: u16,
+        waited: bool,
+        backwards: bool,
+    ) {
+        if n > 0 {
+            self.generated += n as u64;
+            self.last_timestamp_ms = timestamp_ms;
+            self.max_sequence = self.max_sequence.max(start + n - 1);
+        }
+        self.spins += waited as u64;
+        self.clock_backwards += backwards as u64;
+    }
+}
+
+impl<C: Clock, R: RandomSource> ClockGenerator<C, R> {
+    /// Create a generator with the default layout
+    pub fn new(clock: C, random: R) -> Self {
+        Self::with_layout(clock, random, Layout::DEFAULT)
+    }
+
+    /// Create a generator with a non-default field layout
+    pub fn with_layout(clock: C, random: R, layout: Layout) -> Self {
+        ClockGenerator {
+            state: SequenceState::default(),
+            stats: GeneratorStats::default(),
+            layout,
+            time_granularity_ms: 1,
+            epoch_ms: 0,
+            clock,
+            random,
+        }
+    }
+
+    /// Round every clock reading down to a multiple of `ms` before packing
+    ///
+    /// All IDs minted within one `ms`-wide window share a timestamp, so the
+    /// timestamp field takes fewer distinct values and compresses better, at
+    /// the cost of ordering precision: IDs from one window are ordered only
+    /// by sequence number, not by the millisecond they were minted in. The
+    /// sequence field does not grow, so a window holds as many IDs as one
+    /// millisecond normally would; once they run out the generator spins until
+    /// the next window. A value of 0 is treated as 1.
+    pub fn with_time_granularity_ms(mut self, ms: u64) -> Self {
+        self.time_granularity_ms = ms.max(1);
+        self
+    }
+
+    /// Count timestamps from `epoch_ms` (ms since the Unix epoch) instead of
+    /// from the Unix epoch
+    ///
+    /// A recent epoch stretches how long the timestamp field lasts, which
+    /// matters most for narrow custom layouts. IDs then decode to milliseconds
+    /// since `epoch_ms`; add it back to get wall time. Clock readings before
+    /// the epoch count as 0.
+    pub fn with_epoch_ms(mut self, epoch_ms: u64) -> Self {
+        self.epoch_ms = epoch_ms;
+        self
+    }
+
+    /// Generate the next ID as its packed u64 value
+    ///
+    /// With a layout whose timestamp field is too narrow for the clock's
+    /// reading, the timestamp is truncated to fit.
+    pub fn generate_u64(&mut self) -> u64 {
+        match self.try_generate_u64(|| Ok::<_, Infallible>(Stall::Wait)) {
+            Ok(value) => value,
+        }
+    }
+
+    /// Generate the next packed value, asking `on_full` what to do while a
+    /// full millisecond waits for the clock; see [`SequenceState::try_reserve`]
+    pub(crate) fn try_generate_u64<E>(
+        &mut self,
+        on_full: impl FnMut() -> Result<Stall, E>,
+    ) -> Result<u64, E> {
+        let layout = self.layout;
+        let (granularity, epoch_ms) = (self.time_granularity_ms, self.epoch_ms);
+        let clock = &mut self.clock;
+        let last_ms = self.state.last_timestamp_ms;
+        let mut backwards = false;
+        let mut coarse_clock = || {
+            let now = clock.now_ms().saturating_sub(epoch_ms) / granularity * granularity;
+            backwards |= now < last_ms;
+            now
+        };
+        let (timestamp_ms, sequence, waited) =
+            self.state
+                .try_reserve(1, layout.ids_per_ms(), &mut coarse_clock, on_full)?;
+        self.stats
+            .record(1, timestamp_ms, sequence, waited, backwards);
+
+        let random = self.random.next_bits(layout.random_bits()) as u16;
+        Ok(layout.pack_masked(timestamp_ms, sequence, random))
+    }
+
+    /// Generate the next Tiny64 ID
+    pub fn generate(&mut self) -> String {
+        base64_encode_u64(self.generate_u64())
+    }
+
+    /// Number of unpredictable bits in each ID
+    ///
+    /// The timestamp and sequence can be inferred from the mint time, so only
+    /// the random field counts, and only as far as the random source is
+    /// unpredictable: 10 bits for the default layout and source, 0 with a
+    /// [`FixedRandom`](crate::FixedRandom). Guessing an ID whose mint time is
+    /// known takes about `2^bits` tries; the default's 1024 is far too few for
+    /// an ID to serve as a capability token.
+    pub fn effective_entropy_bits(&self) -> f64 {
+        self.random.entropy_bits(self.layout.random_bits())
+    }
+}
+
+impl<C, R> ClockGenerator<C, R> {
+    /// The field layout this generator packs IDs with
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// Counters accumulated since the generator was created
+    pub fn stats(&self) -> GeneratorStats {
+        self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::FixedRandom;
+    use crate::{MAX_IDS_PER_MS, decode_tiny64};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_clock_generator_uses_supplied_sources() {
+        let mut ticks = 0u64;
+        let clock = move || {
+            // Advance one millisecond every 5000 reads
+            ticks += 1;
+            1_000 + ticks / 5_000
+        };
+        let mut generator = ClockGenerator::new(clock, FixedRandom::new(0x155));
+
+        let ids: Vec<String> = (0..MAX_IDS_PER_MS + 10)
+            .map(|_| generator.generate())
+            .collect();
+        for pair in ids.windows(2) {
+            assert!(pair[0] < pair[1]);
+        }
+
+        let first = decode_tiny64(&ids[0]).unwrap();
+        assert_eq!(
+            (first.timestamp_ms, first.sequence, first.random),
+            (1_000, 0, 0x155)
+        );
+        // The 4097th ID had to wait for the clock to reach the next millisecond
+        let overflow = decode_tiny64(&ids[MAX_IDS_PER_MS]).unwrap();
+        assert_eq!((overflow.timestamp_ms, overflow.sequence), (1_001, 0));
+
+        let stats = generator.stats();
+        assert_eq!(stats.generated, ids.len() as u64);
+        assert_eq!(stats.last_timestamp_ms, 1_001);
+        assert_eq!(stats.max_sequence, crate::MAX_SEQUENCE);
+        assert_eq!(stats.spins, 1);
+        assert_eq!(stats.clock_backwards, 0);
+        assert_eq!(generator.effective_entropy_bits(), 0.0);
+    }
+
+    #[test]
+    fn test_monotonic_across_overflow_rollover() {
+        // The clock sits at the last millisecond before 2_000 for the first
+        // 4096 IDs and 100 spin reads, then crosses the boundary
+        let mut reads = 0;
+        let clock = move || {
+            reads += 1;
+            if reads <= MAX_IDS_PER_MS + 100 {
+                1_999
+            } else {
+                2_000
+            }
+        };
+        let mut generator = ClockGenerator::new(clock, FixedRandom::new(0x3FF));
+        let ids: Vec<String> = (0..MAX_IDS_PER_MS + 50)
+            .map(|_| generator.generate())
+            .collect();
+
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+        let unique: alloc::collections::BTreeSet<&String> = ids.iter().collect();
+        assert_eq!(unique.len(), ids.len());
+
+        let last_before = decode_tiny64(&ids[MAX_IDS_PER_MS - 1]).unwrap();
+        assert_eq!(
+            (last_before.timestamp_ms, last_before.sequence),
+            (1_999, crate::MAX_SEQUENCE)
+        );
+        // The overflowing ID waited for the boundary and restarted the sequence
+        let rolled = decode_tiny64(&ids[MAX_IDS_PER_MS]).unwrap();
+        assert_eq!((rolled.timestamp_ms, rolled.sequence), (2_000, 0));
+        let last = decode_tiny64(ids.last().unwrap()).unwrap();
+        assert_eq!((last.timestamp_ms, last.sequence), (2_000, 49));
+        assert_eq!(generator.stats().spins, 1);
+    }
+
+    #[test]
+    fn test_stats_count_clock_backwards() {
+        let mut readings = [1_000, 990, 995, 1_001].into_iter();
+        let clock = move || readings.next().unwrap();
+        let mut generator = ClockGenerator::new(clock, FixedRandom::new(0));
+        let ids: Vec<String> = (0..4).map(|_| generator.generate()).collect();
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+
+        let stats = generator.stats();
+        assert_eq!(stats.clock_backwards, 2);
+        assert_eq!(stats.max_sequence, 2);
+    }
+
+    #[test]
+    fn test_time_granularity_coalesces_window() {
+        // One millisecond per 1000 reads, so a 10ms window would see ~7000 IDs
+        let mut reads = 0u64;
+        let clock = move || {
+            reads += 1;
+            1_003 + reads / 1_000
+        };
+        let mut generator =
+            ClockGenerator::new(clock, FixedRandom::new(0)).with_time_granularity_ms(10);
+
+        let ids: Vec<String> = (0..MAX_IDS_PER_MS + 10)
+            .map(|_| generator.generate())
+            .collect();
+        for pair in ids.windows(2) {
+            assert!(pair[0] < pair[1]);
+        }
+        let parts: Vec<_> = ids.iter().map(|id| decode_tiny64(id).unwrap()).collect();
+        assert!(parts.iter().all(|p| p.timestamp_ms % 10 == 0));
+        assert_eq!((parts[0].timestamp_ms, parts[0].sequence), (1_000, 0));
+
+        // The window's sequence numbers ran out, so the next ID waited for 1010
+        let last = &parts[MAX_IDS_PER_MS - 1];
+        assert_eq!(
+            (last.timestamp_ms, last.sequence),
+            (1_000, crate::MAX_SEQUENCE)
+        );
+        let overflow = &parts[MAX_IDS_PER_MS];
+        assert_eq!((overflow.timestamp_ms, overflow.sequence), (1_010, 0));
+        assert_eq!(generator.stats().spins, 1);
+    }
+
+    #[test]
+    fn test_custom_epoch() {
+        let mut generator =
+            ClockGenerator::new(|| 1_000_500, FixedRandom::new(0)).with_epoch_ms(1_000_000);
+        assert_eq!(
+            decode_tiny64(&generator.generate()).unwrap().timestamp_ms,
+            500
+        );
+
+        let mut early = ClockGenerator::new(|| 5, FixedRandom::new(0)).with_epoch_ms(1_000);
+        assert_eq!(decode_tiny64(&early.generate()).unwrap().timestamp_ms, 0);
+    }
+
+    #[test]
+    fn test_clock_generator_ignores_backwards_clock() {
+        let mut readings = [5_000u64, 4_000, 4_000].into_iter();
+        let clock = move || readings.next().unwrap_or(4_000);
+        let mut generator = ClockGenerator::new(clock, FixedRandom::new(0));
+
+        let a = decode_tiny64(&generator.generate()).unwrap();
+        let b = decode_tiny64(&generator.generate()).unwrap();
+        assert_eq!((a.timestamp_ms, a.sequence), (5_000, 0));
+        assert_eq!((b.timestamp_ms, b.sequence), (5_000, 1));
+    }
+
+    #[test]
+    fn test_stepping_clock_saturates() {
+        let mut clock = SteppingClock::new(u64::MAX - 1);
+        assert_eq!(clock.now_ms(), u64::MAX - 1);
+        assert_eq!(clock.now_ms(), u64::MAX);
+        assert_eq!(clock.now_ms(), u64::MAX);
+    }
+}
@@ -0,0 +1,338 @@
+// Minimal UTC calendar math so the core stays dependency-free.
+
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+
+use crate::decode::{ParseError, timestamp_of};
+
+// `YYYYMMDD-`
+const DATE_PREFIX_LEN: usize = 9;
+
+/// Convert days since 1970-01-01 to a (year, month, day) civil date
+///
+/// Uses Howard Hinnant's `civil_from_days` algorithm, valid for the full
+/// proleptic Gregorian calendar.
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Format signed milliseconds since the Unix epoch as an ISO-8601 date and
+/// time of day, without a zone designator
+fn format_datetime(timestamp_ms: i64) -> String {
+    let millis = timestamp_ms.rem_euclid(1000);
+    let secs = timestamp_ms.div_euclid(1000);
+    let (year, month, day) = civil_from_days(secs.div_euclid(86_400));
+    let secs_of_day = secs.rem_euclid(86_400);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        (secs_of_day / 60) % 60,
+        secs_of_day % 60,
+        millis
+    )
+}
+
+/// Format milliseconds since the Unix epoch as ISO-8601 UTC
+/// (e.g. `2024-01-15T10:23:45.123Z`)
+///
+/// Times past `i64::MAX` milliseconds, some 292 million years out, format
+/// as that last instant rather than wrapping into the past.
+pub fn format_iso8601(timestamp_ms: u64) -> String {
+    let timestamp_ms = timestamp_ms.min(i64::MAX as u64) as i64;
+    format!("{}Z", format_datetime(timestamp_ms))
+}
+
+/// The exact time an ID was minted, as a `SystemTime`
+#[cfg(feature = "std")]
+pub fn system_time_of(id: &str) -> Result<std::time::SystemTime, ParseError> {
+    let timestamp_ms = timestamp_of(id)?;
+    Ok(std::time::UNIX_EPOCH + core::time::Duration::from_millis(timestamp_ms))
+}
+
+/// Format an ID's mint time as ISO-8601 local time at a fixed UTC offset
+///
+/// `offset_seconds` is added to UTC, so it is negative west of Greenwich:
+/// `-18000` formats as `2024-01-15T05:23:45.123-05:00`. Taking a fixed offset
+/// avoids a timezone database, so daylight saving time is up to the caller.
+/// The offset is written as `+HH:MM`, with `:SS` appended if it isn't a whole
+/// number of minutes, and `+00:00` for UTC.
+pub fn format_local(id: &str, offset_seconds: i32) -> Result<String, ParseError> {
+    let local_ms = timestamp_of(id)? as i64 + offset_seconds as i64 * 1000;
+    let sign = if offset_seconds < 0 { '-' } else { '+' };
+    let offset = offset_seconds.unsigned_abs();
+    let mut text = format!(
+        "{}{}{:02}:{:02}",
+        format_datetime(local_ms),
+        sign,
+        offset / 3600,
+        offset / 60 % 60
+    );
+    if !offset.is_multiple_of(60) {
+        text.push_str(&format!(":{:02}", offset % 60));
+    }
+    Ok(text)
+}
+
+/// The UTC date of a timestamp as `YYYYMMDD`
+fn format_date(timestamp_ms: u64) -> String {
+    let (year, month, day) = civil_from_days((timestamp_ms / 86_400_000) as i64);
+    format!("{:04}{:02}{:02}", year, month, day)
+}
+
+/// How coarse a [`partition_key`] is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Granularity {
+    /// `2024-01-15T10`
+    Hour,
+    /// `2024-01-15`
+    Day,
+    /// `2024-01`
+    Month,
+    /// `2024`
+    Year,
+}
+
+/// The UTC time partition an ID falls in, e.g. `2024-01-15` by day
+///
+/// Each key is a prefix of the ISO-8601 time, so keys sort chronologically
+/// and a day's key is a prefix of its hours'. Meant for routing records to
+/// time-partitioned storage.
+pub fn partition_key(id: &str, granularity: Granularity) -> Result<String, ParseError> {
+    let iso = format_iso8601(timestamp_of(id)?);
+    let len = match granularity {
+        Granularity::Hour => 13,
+        Granularity::Day => 10,
+        Granularity::Month => 7,
+        Granularity::Year => 4,
+    };
+    Ok(iso[..len].into())
+}
+
+/// Error returned by [`strip_date_prefix`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DatePrefixError {
+    /// The input doesn't start with `YYYYMMDD-`
+    MissingPrefix,
+    /// The part after the prefix is not a valid ID
+    Id(ParseError),
+    /// The prefix is not the UTC date of the ID's timestamp
+    Mismatch { prefix: String, expected: String },
+}
+
+impl fmt::Display for DatePrefixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DatePrefixError::MissingPrefix => write!(f, "expected a YYYYMMDD- date prefix"),
+            DatePrefixError::Id(err) => write!(f, "invalid ID after date prefix: {}", err),
+            DatePrefixError::Mismatch { prefix, expected } => write!(
+                f,
+                "date prefix {} does not match the ID's date {}",
+                prefix, expected
+            ),
+        }
+    }
+}
+
+impl core::error::Error for DatePrefixError {}
+
+/// Generate an ID prefixed with its UTC mint date, e.g. `20240115-Obrl8O3--Cw`
+///
+/// The date comes from the ID's own timestamp, so the names sort exactly like
+/// the bare IDs while showing the day at a glance; handy for log and dump
+/// file names. Read the ID back with [`strip_date_prefix`].
+#[cfg(feature = "std")]
+pub fn generate_with_date_prefix() -> String {
+    let value = crate::generate_tiny64_u64();
+    let timestamp_ms = crate::parts::Tiny64Parts::from_u64(value).timestamp_ms;
+    format!(
+        "{}-{}",
+        format_date(timestamp_ms),
+        crate::base64_encode_u64(value)
+    )
+}
+
+/// Strip the date prefix written by [`generate_with_date_prefix`] and
+/// return the bare ID
+///
+/// Fails unless the input is `YYYYMMDD-` followed by a valid ID minted on
+/// that UTC date, so a renamed or hand-edited name is caught.
+pub fn strip_date_prefix(name: &str) -> Result<&str, DatePrefixError> {
+    let bytes = name.as_bytes();
+    let has_prefix = bytes.len() >= DATE_PREFIX_LEN
+        && bytes[..DATE_PREFIX_LEN - 1].iter().all(u8::is_ascii_digit)
+        && bytes[DATE_PREFIX_LEN - 1] == b'-';
+    if !has_prefix {
+        return Err(DatePrefixError::MissingPrefix);
+    }
+
+    // The prefix is ASCII, so these are char boundaries
+    let (prefix, id) = (&name[..DATE_PREFIX_LEN - 1], &name[DATE_PREFIX_LEN..]);
+    let expected = format_date(timestamp_of(id).map_err(DatePrefixError::Id)?);
+    if prefix != expected {
+        return Err(DatePrefixError::Mismatch {
+            prefix: prefix.into(),
+            expected,
+        });
+    }
+    Ok(id)
+}
+
+/// Extracts the time an ID was minted as a `time::OffsetDateTime` in UTC
+///
+/// Requires the `time` feature.
+#[cfg(feature = "time")]
+pub fn timestamp_of_datetime(id: &str) -> Result<time::OffsetDateTime, crate::ParseError> {
+    let timestamp_ms = crate::timestamp_of(id)?;
+    // A 42-bit millisecond timestamp ends in 2109, well inside time's range
+    let datetime =
+        time::OffsetDateTime::from_unix_timestamp_nanos(timestamp_ms as i128 * 1_000_000)
+            .expect("42-bit timestamp is within time's supported range");
+    Ok(datetime)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_iso8601() {
+        assert_eq!(format_iso8601(0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(
+            format_iso8601(1_705_314_225_123),
+            "2024-01-15T10:23:45.123Z"
+        );
+        assert_eq!(format_iso8601(951_782_400_000), "2000-02-29T00:00:00.000Z");
+
+        let last = format_iso8601(i64::MAX as u64);
+        assert_eq!(last, "292278994-08-17T07:12:55.807Z");
+        assert_eq!(format_iso8601(u64::MAX), last);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_format_local() {
+        let id = crate::generate_at(1_705_314_225_123).unwrap();
+        assert_eq!(
+            format_local(&id, 0).unwrap(),
+            "2024-01-15T10:23:45.123+00:00"
+        );
+        assert_eq!(
+            format_local(&id, 9 * 3600).unwrap(),
+            "2024-01-15T19:23:45.123+09:00"
+        );
+        assert_eq!(
+            format_local(&id, -(5 * 3600 + 30 * 60)).unwrap(),
+            "2024-01-15T04:53:45.123-05:30"
+        );
+        // West of UTC near midnight falls back a day
+        assert_eq!(
+            format_local(&id, -11 * 3600).unwrap(),
+            "2024-01-14T23:23:45.123-11:00"
+        );
+        assert_eq!(
+            format_local(&id, 45).unwrap(),
+            "2024-01-15T10:24:30.123+00:00:45"
+        );
+
+        // Before the epoch in local time
+        let nil = "-----------";
+        assert_eq!(
+            format_local(nil, -3600).unwrap(),
+            "1969-12-31T23:00:00.000-01:00"
+        );
+        assert_eq!(format_local("bad", 0), Err(ParseError::InvalidLength(3)));
+
+        let since_epoch = system_time_of(&id)
+            .unwrap()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap();
+        assert_eq!(since_epoch.as_millis(), 1_705_314_225_123);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_partition_key() {
+        let id = crate::generate_at(1_705_314_225_123).unwrap();
+        assert_eq!(
+            partition_key(&id, Granularity::Hour).unwrap(),
+            "2024-01-15T10"
+        );
+        assert_eq!(partition_key(&id, Granularity::Day).unwrap(), "2024-01-15");
+        assert_eq!(partition_key(&id, Granularity::Month).unwrap(), "2024-01");
+        assert_eq!(partition_key(&id, Granularity::Year).unwrap(), "2024");
+
+        let new_year = crate::generate_at(1_704_067_199_999).unwrap();
+        assert_eq!(
+            partition_key(&new_year, Granularity::Day).unwrap(),
+            "2023-12-31"
+        );
+        assert_eq!(
+            partition_key("bad", Granularity::Day),
+            Err(ParseError::InvalidLength(3))
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_date_prefix_round_trip() {
+        let name = generate_with_date_prefix();
+        let id = strip_date_prefix(&name).unwrap();
+        assert_eq!(&name[..8], format_date(timestamp_of(id).unwrap()));
+        assert_eq!(name.len(), 20);
+
+        let id = crate::generate_at(1_705_314_225_123).unwrap();
+        let name = format!("20240115-{}", id);
+        assert_eq!(strip_date_prefix(&name), Ok(id.as_str()));
+        assert_eq!(
+            strip_date_prefix(&format!("20240116-{}", id)),
+            Err(DatePrefixError::Mismatch {
+                prefix: "20240116".into(),
+                expected: "20240115".into()
+            })
+        );
+    }
+
+    #[test]
+    fn test_strip_date_prefix_errors() {
+        assert_eq!(
+            strip_date_prefix("Obrl8O3--Cw"),
+            Err(DatePrefixError::MissingPrefix)
+        );
+        assert_eq!(
+            strip_date_prefix("2024011-Obrl8O3--Cw"),
+            Err(DatePrefixError::MissingPrefix)
+        );
+        assert_eq!(
+            strip_date_prefix("20240115-abc"),
+            Err(DatePrefixError::Id(ParseError::InvalidLength(3)))
+        );
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_timestamp_of_datetime() {
+        let id = crate::generate_at(1_705_314_225_123).unwrap();
+        let datetime = timestamp_of_datetime(&id).unwrap();
+        assert_eq!(datetime.unix_timestamp(), 1_705_314_225);
+        assert_eq!(datetime.millisecond(), 123);
+        assert_eq!(datetime.offset(), time::UtcOffset::UTC);
+
+        assert!(timestamp_of_datetime("abc").is_err());
+        let max = timestamp_of_datetime("zzzzzzzzzzw").unwrap();
+        assert_eq!(max.year(), 2109);
+    }
+}
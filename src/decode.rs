@@ -0,0 +1,300 @@
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+
+use crate::ENCODED_LEN;
+use crate::encode::BASE64_ALPHABET;
+use crate::parts::Tiny64Parts;
+
+/// Error returned when a string is not a valid Tiny64 ID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input does not have exactly 11 characters
+    InvalidLength(usize),
+    /// The input contains a character outside the Tiny64 alphabet
+    InvalidChar { ch: char, position: usize },
+    /// The input decodes to a value that does not fit in 64 bits
+    Overflow,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidLength(len) => {
+                write!(f, "invalid length {}, expected {}", len, ENCODED_LEN)
+            }
+            ParseError::InvalidChar { ch, position } => {
+                write!(f, "invalid character '{}' at position {}", ch, position)
+            }
+            ParseError::Overflow => write!(f, "value does not fit in 64 bits"),
+        }
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+/// Marks bytes outside the alphabet in [`REVERSE_ALPHABET`]
+const INVALID: u8 = 0xFF;
+
+/// The 6-bit value of every byte, or [`INVALID`]
+///
+/// Indexed by any `u8`, so decoding hostile input can't index out of bounds.
+const REVERSE_ALPHABET: [u8; 256] = {
+    let mut table = [INVALID; 256];
+    let mut value = 0;
+    while value < BASE64_ALPHABET.len() {
+        table[BASE64_ALPHABET[value] as usize] = value as u8;
+        value += 1;
+    }
+    table
+};
+
+/// Map an alphabet character back to its 6-bit value
+fn decode_char(ch: char) -> Option<u64> {
+    let byte = u8::try_from(ch).ok()?;
+    match REVERSE_ALPHABET[byte as usize] {
+        INVALID => None,
+        bits => Some(bits as u64),
+    }
+}
+
+/// Decodes an 11-character Tiny64 string back into its u64 value
+///
+/// Up to two trailing `=` padding characters, as added by standard Base64
+/// tooling, are accepted and ignored. Canonical output is always unpadded.
+pub fn decode_u64(id: &str) -> Result<u64, ParseError> {
+    let id = id
+        .strip_suffix("==")
+        .or_else(|| id.strip_suffix('='))
+        .unwrap_or(id);
+    let len = id.chars().count();
+    if len != ENCODED_LEN {
+        return Err(ParseError::InvalidLength(len));
+    }
+
+    // 11 chars carry 66 bits: the first 10 hold the top 60 bits of the value,
+    // the last one holds the remaining 4 bits followed by 2 zero padding bits
+    let mut value = 0u64;
+    for (position, ch) in id.chars().enumerate() {
+        let bits = decode_char(ch).ok_or(ParseError::InvalidChar { ch, position })?;
+        if position < ENCODED_LEN - 1 {
+            value = (value << 6) | bits;
+        } else {
+            value = (value << 4) | (bits >> 2);
+        }
+    }
+
+    Ok(value)
+}
+
+/// Whether `id` is the canonical encoding of the value it decodes to
+///
+/// 11 characters hold 66 bits, so the last character's 2 low bits carry no
+/// data and [`decode_u64`] ignores them: `"-----------"` and `"----------0"`
+/// both decode to 0. Only the encoding with those bits zero, the one the
+/// encoders produce, is canonical; padded input is not canonical either.
+/// Check this before using untrusted IDs as map keys or signed tokens, where
+/// two spellings of one value must not both be accepted. Fails if `id`
+/// doesn't decode at all.
+pub fn is_canonical(id: &str) -> Result<bool, ParseError> {
+    let value = decode_u64(id)?;
+    Ok(id.as_bytes() == crate::encode::base64_encode_u64_const(value))
+}
+
+/// Split a key produced by [`encode_pair`](crate::encode_pair) back into
+/// its two values
+///
+/// Padding is not accepted. Error positions count from the start of the
+/// whole 22-character key.
+pub fn decode_pair(key: &str) -> Result<(u64, u64), ParseError> {
+    let len = key.chars().count();
+    if len != 2 * ENCODED_LEN {
+        return Err(ParseError::InvalidLength(len));
+    }
+    // Reject stray characters (including '=') up front with their real position
+    if let Some((position, ch)) = key
+        .chars()
+        .enumerate()
+        .find(|&(_, ch)| decode_char(ch).is_none())
+    {
+        return Err(ParseError::InvalidChar { ch, position });
+    }
+
+    // All ASCII from here on, so the halves split on a byte boundary
+    let (a, b) = key.split_at(ENCODED_LEN);
+    Ok((decode_u64(a)?, decode_u64(b)?))
+}
+
+/// Decodes a Tiny64 string into its timestamp, sequence and random fields
+pub fn decode_tiny64(id: &str) -> Result<Tiny64Parts, ParseError> {
+    decode_u64(id).map(Tiny64Parts::from_u64)
+}
+
+/// Extracts the timestamp (ms since Unix epoch) at which an ID was minted
+pub fn timestamp_of(id: &str) -> Result<u64, ParseError> {
+    decode_tiny64(id).map(|parts| parts.timestamp_ms)
+}
+
+/// Extracts the sequence field, which orders IDs minted in the same millisecond
+///
+/// Comparing whole IDs already orders by sequence within a millisecond; use
+/// this to sort by generation order explicitly, e.g. once IDs from a layout
+/// with the random bits in a different position have been mixed in.
+pub fn intra_ms_key(id: &str) -> Result<u16, ParseError> {
+    decode_tiny64(id).map(|parts| parts.sequence)
+}
+
+/// An ID's fields on one line, e.g. `ts=1705312345123 seq=42 rnd=768`, for
+/// logs and test output
+pub fn debug_fields(id: &str) -> Result<String, ParseError> {
+    let parts = decode_tiny64(id)?;
+    Ok(format!(
+        "ts={} seq={} rnd={}",
+        parts.timestamp_ms, parts.sequence, parts.random
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base64_encode_u64;
+    #[cfg(feature = "std")]
+    use crate::generate_n_at;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_debug_fields() {
+        let id = Tiny64Parts {
+            timestamp_ms: 1_705_312_345_123,
+            sequence: 42,
+            random: 768,
+        }
+        .to_id()
+        .unwrap();
+        assert_eq!(
+            debug_fields(&id).unwrap(),
+            "ts=1705312345123 seq=42 rnd=768"
+        );
+        assert_eq!(debug_fields(""), Err(ParseError::InvalidLength(0)));
+    }
+
+    #[test]
+    fn test_decode_roundtrip() {
+        for value in [0, 1, 0x123456789ABCDEF0, u64::MAX] {
+            assert_eq!(decode_u64(&base64_encode_u64(value)), Ok(value));
+        }
+    }
+
+    #[test]
+    fn test_decode_accepts_padding() {
+        let value = 0x123456789ABCDEF0;
+        let id = base64_encode_u64(value);
+        assert_eq!(decode_u64(&format!("{}=", id)), Ok(value));
+        assert_eq!(decode_u64(&format!("{}==", id)), Ok(value));
+        assert_eq!(
+            decode_u64(&format!("{}===", id)),
+            Err(ParseError::InvalidLength(12))
+        );
+        assert_eq!(
+            decode_u64(&format!("{}=", &id[..10])),
+            Err(ParseError::InvalidLength(10))
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_malformed() {
+        assert_eq!(decode_u64("abc"), Err(ParseError::InvalidLength(3)));
+        assert_eq!(
+            decode_u64("Obrl8O3-+Cw"),
+            Err(ParseError::InvalidChar {
+                ch: '+',
+                position: 8
+            })
+        );
+    }
+
+    #[test]
+    fn test_is_canonical() {
+        for value in [0, 1, 0x123456789ABCDEF0, u64::MAX] {
+            assert_eq!(is_canonical(&base64_encode_u64(value)), Ok(true));
+        }
+        // '0' is 1 in the alphabet: a stray bit below the last 4 data bits
+        assert_eq!(decode_u64("----------0"), Ok(0));
+        assert_eq!(is_canonical("----------0"), Ok(false));
+        assert_eq!(is_canonical("----------z"), Ok(false));
+        assert_eq!(is_canonical("-----------="), Ok(false));
+        assert_eq!(is_canonical("bad"), Err(ParseError::InvalidLength(3)));
+    }
+
+    #[test]
+    fn test_reverse_alphabet_inverts_alphabet() {
+        for (value, &ch) in BASE64_ALPHABET.iter().enumerate() {
+            assert_eq!(REVERSE_ALPHABET[ch as usize], value as u8);
+        }
+        let valid = REVERSE_ALPHABET.iter().filter(|&&bits| bits != INVALID);
+        assert_eq!(valid.count(), BASE64_ALPHABET.len());
+    }
+
+    #[test]
+    fn test_decode_every_byte_in_every_position() {
+        for position in 0..ENCODED_LEN {
+            for byte in 0..=255u8 {
+                let ch = byte as char;
+                let mut id: Vec<char> = "-----------".chars().collect();
+                id[position] = ch;
+                let id: String = id.into_iter().collect();
+                let result = decode_u64(&id);
+                if BASE64_ALPHABET.contains(&byte) {
+                    assert!(result.is_ok(), "{:?}", id);
+                } else if ch == '=' && position == ENCODED_LEN - 1 {
+                    assert_eq!(result, Err(ParseError::InvalidLength(10)));
+                } else {
+                    assert_eq!(result, Err(ParseError::InvalidChar { ch, position }));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_pair_roundtrip_and_order() {
+        let values = [0, 1, 0x123456789ABCDEF0, u64::MAX];
+        for a in values {
+            for b in values {
+                let key = crate::encode_pair(a, b);
+                assert_eq!(key.len(), 22);
+                assert_eq!(decode_pair(&key), Ok((a, b)));
+            }
+        }
+
+        // Ordered by the first value, then the second
+        let mut pairs = vec![(2, 0), (1, u64::MAX), (1, 5), (0, 7), (2, 1)];
+        let mut keys: Vec<String> = pairs
+            .iter()
+            .map(|&(a, b)| crate::encode_pair(a, b))
+            .collect();
+        pairs.sort_unstable();
+        keys.sort_unstable();
+        let decoded: Vec<(u64, u64)> = keys.iter().map(|k| decode_pair(k).unwrap()).collect();
+        assert_eq!(decoded, pairs);
+
+        let key = crate::encode_pair(1, 2);
+        assert_eq!(decode_pair(&key[..21]), Err(ParseError::InvalidLength(21)));
+        assert_eq!(
+            decode_pair(&format!("{}=", &key[..21])),
+            Err(ParseError::InvalidChar {
+                ch: '=',
+                position: 21
+            })
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_intra_ms_key() {
+        let ids = generate_n_at(1_000, 5).unwrap();
+        let keys: Vec<u16> = ids.iter().map(|id| intra_ms_key(id).unwrap()).collect();
+        assert_eq!(keys, [0, 1, 2, 3, 4]);
+        assert_eq!(intra_ms_key("abc"), Err(ParseError::InvalidLength(3)));
+    }
+}
@@ -0,0 +1,110 @@
+//! IDs that sort newest first.
+//!
+//! Layout: `[ 42 bits: !timestamp_ms ] [ 12 bits: !sequence ] [ 10 bits: random ]`.
+//!
+//! Inverting the timestamp and sequence bits reverses the order: of two
+//! descending IDs, the later-minted one sorts first, so a store that only
+//! scans in ascending key order reads the newest entries first. The random
+//! bits are kept as they are. Inverting twice gives back the original value,
+//! so [`decode_descending`] recovers the fields the ID was minted with.
+//!
+//! Nothing in the ID says it is inverted: decoding one with
+//! [`decode_tiny64`](crate::decode_tiny64) gives meaningless fields.
+
+#[cfg(feature = "std")]
+use alloc::string::String;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+
+use crate::RANDOM_BITS;
+use crate::decode::{ParseError, decode_u64};
+use crate::parts::Tiny64Parts;
+
+/// Flip a packed value between ascending and descending form
+fn invert(value: u64) -> u64 {
+    value ^ (u64::MAX << RANDOM_BITS)
+}
+
+/// Generate an ID that sorts before every ID minted earlier
+///
+/// Minted from the same per-thread sequence as
+/// [`generate_tiny64`](crate::generate_tiny64), then inverted. Decode with
+/// [`decode_descending`].
+#[cfg(feature = "std")]
+pub fn generate_tiny64_descending() -> String {
+    crate::base64_encode_u64(invert(crate::generate_tiny64_u64()))
+}
+
+/// Generate one ID in both ascending and descending form
+///
+/// Both come from the same packed value, for keeping a forward and a reverse
+/// index of the same event. The first decodes with
+/// [`decode_tiny64`](crate::decode_tiny64) and the second with
+/// [`decode_descending`] to the same parts.
+#[cfg(feature = "std")]
+pub fn generate_both() -> (String, String) {
+    let value = crate::generate_tiny64_u64();
+    (
+        crate::base64_encode_u64(value),
+        crate::base64_encode_u64(invert(value)),
+    )
+}
+
+/// Generate `n` ordinary IDs, returned newest first
+///
+/// The IDs are minted in ascending order and reversed, so `ids[i] > ids[i +
+/// 1]` throughout and each one decodes normally. This orders one batch for
+/// stack-like use; to keep IDs newest first across batches and inside a
+/// sorted store, mint them with [`generate_tiny64_descending`] instead.
+#[cfg(feature = "std")]
+pub fn generate_batch_descending(n: usize) -> Vec<String> {
+    let mut ids: Vec<String> = (0..n).map(|_| crate::generate_tiny64()).collect();
+    ids.reverse();
+    ids
+}
+
+/// Decode an ID minted by [`generate_tiny64_descending`]
+pub fn decode_descending(id: &str) -> Result<Tiny64Parts, ParseError> {
+    decode_u64(id).map(|value| Tiny64Parts::from_u64(invert(value)))
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_descending_ids_sort_newest_first() {
+        let before = crate::generator::current_time_ms();
+        let ids: Vec<String> = (0..5000).map(|_| generate_tiny64_descending()).collect();
+        assert!(ids.windows(2).all(|pair| pair[0] > pair[1]));
+
+        let first = decode_descending(&ids[0]).unwrap();
+        let last = decode_descending(&ids[4999]).unwrap();
+        assert!(first.timestamp_ms >= before);
+        assert!((first.timestamp_ms, first.sequence) < (last.timestamp_ms, last.sequence));
+    }
+
+    #[test]
+    fn test_generate_both() {
+        let (ascending, descending) = generate_both();
+        assert_eq!(
+            crate::decode_tiny64(&ascending).unwrap(),
+            decode_descending(&descending).unwrap()
+        );
+
+        let (later_ascending, later_descending) = generate_both();
+        assert!(later_ascending > ascending);
+        assert!(later_descending < descending);
+    }
+
+    #[test]
+    fn test_generate_batch_descending() {
+        let ids = generate_batch_descending(5000);
+        assert_eq!(ids.len(), 5000);
+        for i in 0..ids.len() - 1 {
+            assert!(ids[i] > ids[i + 1]);
+        }
+        assert!(crate::decode_tiny64(&ids[0]).is_ok());
+        assert!(generate_batch_descending(0).is_empty());
+    }
+}
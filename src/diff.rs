@@ -0,0 +1,88 @@
+//! Field-by-field comparison of two IDs, for debugging how they sort.
+
+use core::fmt;
+
+use crate::decode::{ParseError, decode_u64};
+use crate::parts::Tiny64Parts;
+
+/// How the fields of `b` differ from those of `a`, from [`bit_diff`]
+///
+/// Each delta is `b - a`. IDs sort by the first field that differs, in the
+/// order timestamp, sequence, random. Displays as e.g. `same timestamp,
+/// sequence differs by 3, random differs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitDiff {
+    pub timestamp_ms: i64,
+    pub sequence: i32,
+    pub random: i32,
+    /// The two packed values XORed: the exact bits that differ
+    pub xor: u64,
+}
+
+impl BitDiff {
+    /// Whether the two IDs are the same
+    pub fn is_identical(&self) -> bool {
+        self.xor == 0
+    }
+}
+
+impl fmt::Display for BitDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.timestamp_ms {
+            0 => write!(f, "same timestamp")?,
+            ms => write!(f, "timestamp differs by {} ms", ms.unsigned_abs())?,
+        }
+        match self.sequence {
+            0 => write!(f, ", same sequence")?,
+            n => write!(f, ", sequence differs by {}", n.unsigned_abs())?,
+        }
+        match self.random {
+            0 => write!(f, ", same random"),
+            _ => write!(f, ", random differs"),
+        }
+    }
+}
+
+/// Decode two IDs and compare them field by field
+pub fn bit_diff(a: &str, b: &str) -> Result<BitDiff, ParseError> {
+    let (a_value, b_value) = (decode_u64(a)?, decode_u64(b)?);
+    let (a, b) = (
+        Tiny64Parts::from_u64(a_value),
+        Tiny64Parts::from_u64(b_value),
+    );
+    Ok(BitDiff {
+        timestamp_ms: b.timestamp_ms as i64 - a.timestamp_ms as i64,
+        sequence: b.sequence as i32 - a.sequence as i32,
+        random: b.random as i32 - a.random as i32,
+        xor: a_value ^ b_value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parts::id_from_fields;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_bit_diff() {
+        let diff = bit_diff(&id_from_fields(1_000, 2, 7), &id_from_fields(1_000, 5, 9)).unwrap();
+        assert_eq!((diff.timestamp_ms, diff.sequence, diff.random), (0, 3, 2));
+        assert_eq!(diff.xor, (2 ^ 5) << 10 | (7 ^ 9));
+        assert_eq!(
+            diff.to_string(),
+            "same timestamp, sequence differs by 3, random differs"
+        );
+
+        let diff = bit_diff(&id_from_fields(1_250, 0, 7), &id_from_fields(1_000, 4, 7)).unwrap();
+        assert_eq!(diff.timestamp_ms, -250);
+        assert_eq!(
+            diff.to_string(),
+            "timestamp differs by 250 ms, sequence differs by 4, same random"
+        );
+
+        let same = id_from_fields(5, 5, 5);
+        assert!(bit_diff(&same, &same).unwrap().is_identical());
+        assert_eq!(bit_diff(&same, "x"), Err(ParseError::InvalidLength(1)));
+    }
+}
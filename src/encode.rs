@@ -0,0 +1,257 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::ENCODED_LEN;
+
+// Base64 URL-safe alphabet ordered by ASCII value for lexical sorting
+// This ensures that encoded strings maintain chronological order
+// Every character is RFC 3986 unreserved, so percent-encoding never alters an ID
+pub(crate) const BASE64_ALPHABET: &[u8; 64] =
+    b"-0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ_abcdefghijklmnopqrstuvwxyz";
+
+/// The collation IDs need in a database to sort in time order: plain byte
+/// order, which SQL databases call `"C"`
+///
+/// The alphabet is in ASCII order, so IDs sort correctly by raw bytes, but
+/// locale collations such as Postgres' default `en_US.UTF-8` compare case and
+/// punctuation differently: they interleave `a` with `A` and may skip `-` and
+/// `_` altogether, reordering IDs. Declare the column `text COLLATE "C"` (or
+/// compare with `COLLATE "C"`, or store IDs as `bytea`) so `ORDER BY` and
+/// range queries follow byte order.
+pub const SORT_COLLATION: &str = "C";
+
+/// Encodes a u64 value as Base64 URL-safe string (11 characters, no padding)
+pub fn base64_encode_u64(value: u64) -> String {
+    String::from_utf8(encode_to_vec(value)).unwrap()
+}
+
+/// Same as [`base64_encode_u64`] but skips UTF-8 validation of the output
+///
+/// This is safe to call: every output byte comes from `BASE64_ALPHABET`, which
+/// is pure ASCII. It only saves the re-validation pass on hot paths.
+pub fn encode_unchecked(value: u64) -> String {
+    // SAFETY: encode_to_vec only emits bytes from BASE64_ALPHABET, all ASCII
+    unsafe { String::from_utf8_unchecked(encode_to_vec(value)) }
+}
+
+/// Encode two values as one 22-character key that sorts by `a`, then `b`
+///
+/// Both halves are fixed-width and sort like their values, so comparing the
+/// concatenation compares `a` first and only falls through to `b` on a tie,
+/// as a composite key like `(parent_id, child_id)` should.
+pub fn encode_pair(a: u64, b: u64) -> String {
+    let mut out = String::with_capacity(2 * ENCODED_LEN);
+    out.push_str(&base64_encode_u64(a));
+    out.push_str(&base64_encode_u64(b));
+    out
+}
+
+fn encode_to_vec(value: u64) -> Vec<u8> {
+    let mut out = [0u8; ENCODED_LEN];
+    encode_into_bytes(value, &mut out);
+    out.to_vec()
+}
+
+/// Encode into a caller-provided buffer, without allocating
+///
+/// Fills `out` with the same ASCII bytes [`base64_encode_u64`] would return,
+/// e.g. straight into a fixed-size record field.
+pub fn encode_into_bytes(value: u64, out: &mut [u8; ENCODED_LEN]) {
+    *out = base64_encode_u64_const(value);
+}
+
+/// `const` version of [`base64_encode_u64`], returning the raw ASCII bytes
+///
+/// Usable in `const` contexts to embed fixed IDs:
+///
+/// ```
+/// use tiny64::base64_encode_u64_const;
+///
+/// const ID_BYTES: [u8; 11] = base64_encode_u64_const(0x123456789ABCDEF0);
+/// const ID: &str = match std::str::from_utf8(&ID_BYTES) {
+///     Ok(id) => id,
+///     Err(_) => panic!("alphabet is ASCII"),
+/// };
+/// assert_eq!(ID, tiny64::base64_encode_u64(0x123456789ABCDEF0));
+/// ```
+pub const fn base64_encode_u64_const(value: u64) -> [u8; ENCODED_LEN] {
+    let mut out = [0u8; ENCODED_LEN];
+
+    // The first 10 characters each take 6 bits, most significant first
+    let mut i = 0;
+    while i < ENCODED_LEN - 1 {
+        let shift = 58 - 6 * i;
+        out[i] = BASE64_ALPHABET[((value >> shift) & 0x3F) as usize];
+        i += 1;
+    }
+
+    // The last character holds the remaining 4 bits followed by 2 zero bits
+    out[ENCODED_LEN - 1] = BASE64_ALPHABET[((value & 0x0F) << 2) as usize];
+    out
+}
+
+/// Encodes the low 48 bits of a u64 as Base64 URL-safe string (8 characters, no padding)
+pub fn base64_encode_u48(value: u64) -> String {
+    let bytes = value.to_be_bytes();
+    let mut result = Vec::with_capacity(8);
+
+    // The low 6 bytes split evenly into two 3-byte groups -> 8 base64 chars
+    for group in bytes[2..].chunks(3) {
+        let b1 = group[0] as usize;
+        let b2 = group[1] as usize;
+        let b3 = group[2] as usize;
+
+        result.push(BASE64_ALPHABET[(b1 >> 2) & 0x3F]);
+        result.push(BASE64_ALPHABET[((b1 & 0x03) << 4) | ((b2 >> 4) & 0x0F)]);
+        result.push(BASE64_ALPHABET[((b2 & 0x0F) << 2) | ((b3 >> 6) & 0x03)]);
+        result.push(BASE64_ALPHABET[b3 & 0x3F]);
+    }
+
+    String::from_utf8(result).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_base64_encode_length() {
+        let id = base64_encode_u64(0x123456789ABCDEF0);
+        assert_eq!(id.len(), 11);
+    }
+
+    #[test]
+    fn test_encode_extremes() {
+        // '-' and 'z' are the lowest and highest characters of the sorted
+        // alphabet; the last character only carries 4 bits, so all bits set
+        // ends in 'w' (0b111100) rather than 'z'
+        assert_eq!(base64_encode_u64(0), "-----------");
+        assert_eq!(base64_encode_u64(u64::MAX), "zzzzzzzzzzw");
+        assert_eq!(crate::decode_u64("-----------"), Ok(0));
+        assert_eq!(crate::decode_u64("zzzzzzzzzzw"), Ok(u64::MAX));
+    }
+
+    #[test]
+    fn test_last_two_bytes_exhaustively() {
+        // The low 16 bits span the last three characters, including the
+        // short 4-bit final one, so every packing of that tail is covered
+        let mut previous = None;
+        for tail in 0..=0xFFFFu64 {
+            let encoded = base64_encode_u64(tail);
+            assert_eq!(&encoded[..8], "--------");
+            assert_eq!(crate::decode_u64(&encoded), Ok(tail));
+            if let Some(previous) = previous {
+                assert!(previous < encoded);
+            }
+            previous = Some(encoded);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_byte_order_matches_numeric_order() {
+        // What a `COLLATE "C"` column compares: raw bytes
+        assert!(BASE64_ALPHABET.windows(2).all(|pair| pair[0] < pair[1]));
+
+        let mut values: Vec<u64> = (0..2000)
+            .map(|i: u64| i.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+            .collect();
+        values.extend((0..2000).map(|_| crate::generate_tiny64_u64()));
+        values.extend([0, 1, u64::MAX - 1, u64::MAX]);
+
+        let mut by_bytes: Vec<String> = values.iter().map(|&v| base64_encode_u64(v)).collect();
+        by_bytes.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+        values.sort_unstable();
+        let by_value: Vec<String> = values.iter().map(|&v| base64_encode_u64(v)).collect();
+        assert_eq!(by_bytes, by_value);
+    }
+
+    #[test]
+    fn test_percent_encoding_leaves_ids_unchanged() {
+        // RFC 3986 section 2.3: the characters no conforming encoder escapes
+        let unreserved = |b: u8| b.is_ascii_alphanumeric() || b"-._~".contains(&b);
+        assert!(BASE64_ALPHABET.iter().all(|&b| unreserved(b)));
+
+        // So a percent-encoded ID is the ID itself, and sorts the same
+        let percent_encode = |id: &str| -> String {
+            id.bytes()
+                .map(|b| {
+                    if unreserved(b) {
+                        (b as char).to_string()
+                    } else {
+                        format!("%{:02X}", b)
+                    }
+                })
+                .collect()
+        };
+        for value in [0, 0x0123_4567_89AB_CDEF, u64::MAX] {
+            let id = base64_encode_u64(value);
+            assert_eq!(percent_encode(&id), id);
+        }
+    }
+
+    #[test]
+    fn test_every_alphabet_position() {
+        // Only the first 10 characters carry a full 6 bits; the 11th holds the
+        // low 4 bits shifted left by 2, so it can only be every 4th character
+        for (index, &ch) in BASE64_ALPHABET.iter().enumerate() {
+            for position in 0..ENCODED_LEN - 1 {
+                let value = (index as u64) << (58 - 6 * position);
+                let encoded = base64_encode_u64(value);
+                assert_eq!(encoded.as_bytes()[position], ch);
+                assert_eq!(crate::decode_u64(&encoded), Ok(value));
+            }
+        }
+
+        for low in 0..16u64 {
+            let encoded = base64_encode_u64(low);
+            assert_eq!(
+                encoded.as_bytes()[ENCODED_LEN - 1],
+                BASE64_ALPHABET[(low << 2) as usize]
+            );
+            assert_eq!(crate::decode_u64(&encoded), Ok(low));
+        }
+    }
+
+    #[test]
+    fn test_const_encoder_matches_runtime() {
+        const ENCODED: [u8; 11] = base64_encode_u64_const(0x123456789ABCDEF0);
+        assert_eq!(&ENCODED, base64_encode_u64(0x123456789ABCDEF0).as_bytes());
+
+        for value in [0, 1, 0x3F, 0x123456789ABCDEF0, 1 << 63, u64::MAX] {
+            assert_eq!(
+                &base64_encode_u64_const(value),
+                base64_encode_u64(value).as_bytes()
+            );
+        }
+    }
+
+    #[test]
+    fn test_encode_into_bytes() {
+        let mut out = [0u8; ENCODED_LEN];
+        for (value, expected) in [
+            (0, "-----------"),
+            (1, "----------3"),
+            (u64::MAX, "zzzzzzzzzzw"),
+        ] {
+            encode_into_bytes(value, &mut out);
+            assert_eq!(&out, expected.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_encode_unchecked_matches_safe() {
+        for value in [0, 1, 0x123456789ABCDEF0, u64::MAX] {
+            assert_eq!(encode_unchecked(value), base64_encode_u64(value));
+        }
+    }
+
+    #[test]
+    fn test_base64_encode_u48() {
+        assert_eq!(base64_encode_u48(0), "--------");
+        assert_eq!(base64_encode_u48(0xFFFF_FFFF_FFFF), "zzzzzzzz");
+        assert!(base64_encode_u48(0x1234) < base64_encode_u48(0x1235));
+    }
+}
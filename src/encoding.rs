@@ -0,0 +1,143 @@
+//! Selecting among the string encodings at runtime.
+//!
+//! Every encoding maps the same 64-bit value to a fixed-width string, but the
+//! widths differ, so validation has to know which encoding to expect.
+
+use alloc::string::String;
+
+use crate::decode::{ParseError, decode_u64};
+use crate::encode::base64_encode_u64;
+use crate::parts::Tiny64Parts;
+use crate::radix;
+
+/// A string encoding of a 64-bit Tiny64 value
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// The standard 11-character Base64 encoding
+    #[default]
+    Base64,
+    /// 13 characters of Crockford's Base32
+    Crockford,
+    /// 16 lowercase hex digits
+    Hex,
+    /// 20 decimal digits
+    Decimal,
+    /// 13 characters that survive uppercasing
+    UppercaseSafe,
+    /// 13-character DNS label
+    DnsSafe,
+    /// 13 characters without easily confused glyphs
+    Unambiguous,
+    /// 12 characters of the QR alphanumeric charset; does not sort
+    Qr,
+    /// 11 ASCII letters and digits
+    Base62,
+}
+
+impl Encoding {
+    /// Length in characters of every string in this encoding
+    pub const fn encoded_len(self) -> usize {
+        match self {
+            Encoding::Base64 => crate::ENCODED_LEN,
+            Encoding::Crockford => radix::width_for_radix(radix::CROCKFORD_ALPHABET.len() as u64),
+            Encoding::Hex => radix::width_for_radix(radix::HEX_ALPHABET.len() as u64),
+            Encoding::Decimal => radix::width_for_radix(10),
+            Encoding::UppercaseSafe => radix::UPPERCASE_ENCODED_LEN,
+            Encoding::DnsSafe => radix::DNS_ENCODED_LEN,
+            Encoding::Unambiguous => radix::UNAMBIGUOUS_ENCODED_LEN,
+            Encoding::Qr => radix::QR_ENCODED_LEN,
+            Encoding::Base62 => radix::BASE62_ENCODED_LEN,
+        }
+    }
+
+    /// Encode a value
+    pub fn encode(self, value: u64) -> String {
+        match self {
+            Encoding::Base64 => base64_encode_u64(value),
+            Encoding::Crockford => radix::encode_crockford(value),
+            Encoding::Hex => radix::encode_hex(value),
+            Encoding::Decimal => radix::encode_decimal(value),
+            Encoding::UppercaseSafe => radix::encode_uppercase_safe(value),
+            Encoding::DnsSafe => radix::encode_dns_safe(value),
+            Encoding::Unambiguous => radix::encode_unambiguous(value),
+            Encoding::Qr => radix::encode_qr(value),
+            Encoding::Base62 => radix::encode_base62(value),
+        }
+    }
+
+    /// Decode a string in this encoding back into its value
+    pub fn decode(self, id: &str) -> Result<u64, ParseError> {
+        match self {
+            Encoding::Base64 => decode_u64(id),
+            Encoding::Crockford => radix::decode_crockford(id),
+            Encoding::Hex => radix::decode_hex(id),
+            Encoding::Decimal => radix::decode_decimal(id),
+            Encoding::UppercaseSafe => radix::decode_uppercase_safe(id),
+            Encoding::DnsSafe => radix::decode_dns_safe(id),
+            Encoding::Unambiguous => radix::decode_unambiguous(id),
+            Encoding::Qr => radix::decode_qr(id),
+            Encoding::Base62 => radix::decode_base62(id),
+        }
+    }
+}
+
+/// Whether `id` is a well-formed ID in the standard Base64 encoding
+pub fn is_valid_tiny64(id: &str) -> bool {
+    decode_u64(id).is_ok()
+}
+
+/// Whether `id` is a well-formed ID in `encoding`, including its length
+pub fn is_valid_with(id: &str, encoding: Encoding) -> bool {
+    encoding.decode(id).is_ok()
+}
+
+/// Decode an ID in `encoding` into its timestamp, sequence and random fields
+pub fn decode_tiny64_with(id: &str, encoding: Encoding) -> Result<Tiny64Parts, ParseError> {
+    encoding.decode(id).map(Tiny64Parts::from_u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Encoding; 9] = [
+        Encoding::Base64,
+        Encoding::Crockford,
+        Encoding::Hex,
+        Encoding::Decimal,
+        Encoding::UppercaseSafe,
+        Encoding::DnsSafe,
+        Encoding::Unambiguous,
+        Encoding::Qr,
+        Encoding::Base62,
+    ];
+
+    #[test]
+    fn test_encoded_len_matches_output() {
+        for encoding in ALL {
+            for value in [0, 0x123456789ABCDEF0, u64::MAX] {
+                let id = encoding.encode(value);
+                assert_eq!(id.len(), encoding.encoded_len(), "{:?}", encoding);
+                assert_eq!(encoding.decode(&id), Ok(value));
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_validate_crockford_id() {
+        let value = crate::generate_tiny64_u64();
+        let id = Encoding::Crockford.encode(value);
+        assert_eq!(id.len(), 13);
+
+        // The default validator rejects it on length alone
+        assert!(!is_valid_tiny64(&id));
+        assert!(is_valid_with(&id, Encoding::Crockford));
+        assert_eq!(
+            decode_tiny64_with(&id, Encoding::Crockford),
+            Ok(Tiny64Parts::from_u64(value))
+        );
+        assert!(is_valid_tiny64(&base64_encode_u64(value)));
+        assert!(!is_valid_with(&id[..12], Encoding::Crockford));
+    }
+}
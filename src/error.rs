@@ -0,0 +1,71 @@
+use core::fmt;
+
+use crate::{MAX_FLAG_BITS, MAX_IDS_PER_MS, MAX_MACHINE_ID, MAX_TIMESTAMP_MS, RANDOM_BITS};
+
+/// Error returned when an ID cannot be generated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Tiny64Error {
+    /// The requested time is before the Unix epoch
+    BeforeEpoch,
+    /// The timestamp does not fit in the 42-bit timestamp field
+    TimestampOutOfRange(u64),
+    /// More IDs were requested for one millisecond than the sequence can hold
+    TooManyIds(usize),
+    /// The clock did not advance within the configured spin timeout
+    ClockStalled,
+    /// The millisecond's sequence numbers are used up and the overflow policy
+    /// doesn't wait for the next one
+    SequenceExhausted,
+    /// The counter does not fit in the 54 bits above the random field
+    CounterOutOfRange(u64),
+    /// More flag bits were requested than a `u8` flag value can carry
+    TooManyFlagBits(u32),
+    /// The flag value does not fit in the requested number of flag bits
+    FlagsOutOfRange { flags: u8, flag_bits: u32 },
+    /// The machine ID does not fit in the 10-bit Snowflake machine field
+    MachineIdOutOfRange(u16),
+    /// The hour or minute is out of range for a time of day
+    InvalidTimeOfDay { hour: u8, minute: u8 },
+    /// Every ID drawn in the allowed number of attempts was rejected
+    RetriesExhausted(usize),
+}
+
+impl fmt::Display for Tiny64Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tiny64Error::BeforeEpoch => write!(f, "time is before the Unix epoch"),
+            Tiny64Error::TimestampOutOfRange(v) => {
+                write!(f, "timestamp {} exceeds maximum {}", v, MAX_TIMESTAMP_MS)
+            }
+            Tiny64Error::TooManyIds(n) => write!(
+                f,
+                "cannot mint {} IDs in one millisecond, at most {}",
+                n, MAX_IDS_PER_MS
+            ),
+            Tiny64Error::ClockStalled => write!(f, "clock did not advance before the timeout"),
+            Tiny64Error::SequenceExhausted => {
+                write!(f, "sequence numbers for this millisecond are used up")
+            }
+            Tiny64Error::CounterOutOfRange(v) => {
+                write!(f, "counter {} does not fit in {} bits", v, 64 - RANDOM_BITS)
+            }
+            Tiny64Error::TooManyFlagBits(n) => {
+                write!(f, "{} flag bits requested, at most {}", n, MAX_FLAG_BITS)
+            }
+            Tiny64Error::FlagsOutOfRange { flags, flag_bits } => {
+                write!(f, "flags {} do not fit in {} bits", flags, flag_bits)
+            }
+            Tiny64Error::MachineIdOutOfRange(id) => {
+                write!(f, "machine ID {} exceeds maximum {}", id, MAX_MACHINE_ID)
+            }
+            Tiny64Error::InvalidTimeOfDay { hour, minute } => {
+                write!(f, "{:02}:{:02} is not a valid time of day", hour, minute)
+            }
+            Tiny64Error::RetriesExhausted(attempts) => {
+                write!(f, "no acceptable ID in {} attempts", attempts)
+            }
+        }
+    }
+}
+
+impl core::error::Error for Tiny64Error {}
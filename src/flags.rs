@@ -0,0 +1,127 @@
+//! Application flags packed into the top of the random field.
+//!
+//! Layout: `[ 42 bits: timestamp_ms ] [ 12 bits: sequence ] [ flag_bits: flags ] [ 10 - flag_bits: random ]`.
+//!
+//! Like the versioned variant, this trades randomness for a small payload
+//! such as an environment marker. Every flag bit halves the random space: with
+//! `flag_bits` flags, two generators minting in the same millisecond slot with
+//! the same flags collide with probability `1 / 2^(10 - flag_bits)`, so 8 flag
+//! bits leave only 4 random values. IDs with different flags never collide.
+//!
+//! The flag width is not recorded in the ID. [`flags_of`] must be called with
+//! the same `flag_bits` used to mint, and on a plain ID it returns whatever the
+//! top random bits happen to be.
+
+#[cfg(feature = "std")]
+use alloc::string::String;
+
+use core::fmt;
+
+use crate::RANDOM_BITS;
+use crate::decode::{ParseError, decode_u64};
+#[cfg(feature = "std")]
+use crate::error::Tiny64Error;
+
+/// Largest number of flag bits, the width of the `u8` flag value
+pub const MAX_FLAG_BITS: u32 = 8;
+
+/// Error returned by [`flags_of`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FlagsError {
+    /// More flag bits were requested than a `u8` flag value can carry
+    TooManyFlagBits(u32),
+    /// The input is not a valid ID
+    Id(ParseError),
+}
+
+impl fmt::Display for FlagsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlagsError::TooManyFlagBits(n) => {
+                write!(f, "{} flag bits requested, at most {}", n, MAX_FLAG_BITS)
+            }
+            FlagsError::Id(err) => write!(f, "invalid ID: {}", err),
+        }
+    }
+}
+
+impl core::error::Error for FlagsError {}
+
+/// Generate an ID carrying `flags` in the top `flag_bits` of the random field
+///
+/// The remaining `10 - flag_bits` random bits are drawn as usual. Fails if
+/// `flag_bits` exceeds [`MAX_FLAG_BITS`] or `flags` needs more than
+/// `flag_bits` bits.
+#[cfg(feature = "std")]
+pub fn generate_with_flags(flags: u8, flag_bits: u32) -> Result<String, Tiny64Error> {
+    if flag_bits > MAX_FLAG_BITS {
+        return Err(Tiny64Error::TooManyFlagBits(flag_bits));
+    }
+    if (flags as u64) >> flag_bits != 0 {
+        return Err(Tiny64Error::FlagsOutOfRange { flags, flag_bits });
+    }
+
+    let shift = RANDOM_BITS - flag_bits;
+    let flag_mask = ((1u64 << flag_bits) - 1) << shift;
+    let value = crate::generate_tiny64_u64() & !flag_mask;
+    Ok(crate::base64_encode_u64(value | (flags as u64) << shift))
+}
+
+/// Read the top `flag_bits` of the random field of an ID minted with
+/// [`generate_with_flags`]
+///
+/// Like [`generate_with_flags`], fails rather than panics if `flag_bits`
+/// exceeds [`MAX_FLAG_BITS`], since the width often comes from configuration
+/// shared by both sides. Also fails if `id` doesn't parse.
+pub fn flags_of(id: &str, flag_bits: u32) -> Result<u8, FlagsError> {
+    if flag_bits > MAX_FLAG_BITS {
+        return Err(FlagsError::TooManyFlagBits(flag_bits));
+    }
+    let value = decode_u64(id).map_err(FlagsError::Id)?;
+    let shift = RANDOM_BITS - flag_bits;
+    Ok(((value >> shift) & ((1 << flag_bits) - 1)) as u8)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::timestamp_of;
+
+    #[test]
+    fn test_flags_round_trip() {
+        for flag_bits in 0..=MAX_FLAG_BITS {
+            let flags = ((1u16 << flag_bits) - 1) as u8;
+            let id = generate_with_flags(flags, flag_bits).unwrap();
+            assert_eq!(flags_of(&id, flag_bits), Ok(flags));
+            assert!(timestamp_of(&id).is_ok());
+        }
+
+        let ids: Vec<String> = (0..100)
+            .map(|_| generate_with_flags(0b10, 2).unwrap())
+            .collect();
+        for pair in ids.windows(2) {
+            assert!(pair[0] < pair[1]);
+        }
+        assert_eq!(
+            flags_of("abc", 2),
+            Err(FlagsError::Id(ParseError::InvalidLength(3)))
+        );
+    }
+
+    #[test]
+    fn test_flags_out_of_range() {
+        assert_eq!(
+            generate_with_flags(0b100, 2),
+            Err(Tiny64Error::FlagsOutOfRange {
+                flags: 0b100,
+                flag_bits: 2
+            })
+        );
+        assert_eq!(
+            generate_with_flags(0, 9),
+            Err(Tiny64Error::TooManyFlagBits(9))
+        );
+        let id = generate_with_flags(0, 8).unwrap();
+        assert_eq!(flags_of(&id, 9), Err(FlagsError::TooManyFlagBits(9)));
+    }
+}
@@ -0,0 +1,1373 @@
+use std::cell::Cell;
+use std::convert::Infallible;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::iter::FusedIterator;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::clock::{
+    Clock, ClockGenerator, GeneratorStats, SequenceState, Stall, SteppingClock, SystemClock,
+};
+use crate::encode::base64_encode_u64;
+use crate::error::Tiny64Error;
+use crate::layout::Layout;
+use crate::node::MAX_NODE_ID;
+use crate::parts::{Tiny64Parts, pack_masked};
+use crate::random::{
+    FixedRandom, HashRandom, RandomSource, SaltedRandom, SeededRandom, generate_random_10bit,
+};
+use crate::settings::Settings;
+use crate::{MAX_SEQUENCE, MAX_TIMESTAMP_MS};
+
+// Thread-local state for sequence tracking
+thread_local! {
+    static LAST_TIMESTAMP_MS: Cell<u64> = const { Cell::new(0) };
+    static SEQUENCE: Cell<u16> = const { Cell::new(0) };
+}
+
+/// Get current Unix timestamp in milliseconds
+pub(crate) fn current_time_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System time is before Unix epoch")
+        .as_millis() as u64
+}
+
+/// Default for [`set_spin_threshold`]
+///
+/// Long enough to catch a millisecond that is about to tick over with no
+/// wakeup latency at all, short enough that a longer wait costs only a few
+/// percent of a core: `cargo bench --bench wait` measures about 3% CPU
+/// against pure spinning's 100%, for tens of microseconds of extra latency
+/// on waits that outlast it.
+pub const DEFAULT_SPIN_THRESHOLD: Duration = Duration::from_micros(20);
+
+static SPIN_THRESHOLD_NS: AtomicU64 = AtomicU64::new(DEFAULT_SPIN_THRESHOLD.as_nanos() as u64);
+
+/// Set how long the free generation functions spin for the next millisecond
+/// before they start sleeping instead
+///
+/// When a millisecond's sequence numbers run out, [`generate_tiny64`] and
+/// its variants that wait have to wait for the clock. Spinning wakes up the
+/// moment the clock ticks, which is best for waits well under a millisecond,
+/// but it burns a core the whole time. Past the threshold the wait sleeps
+/// until the next millisecond boundary and checks again, which costs up to a
+/// scheduler quantum of extra latency but almost no CPU; that matters with a
+/// coarse clock that only ticks every few milliseconds. `Duration::MAX`
+/// always spins and `Duration::ZERO` sleeps straight away. The setting is
+/// process-wide; [`Tiny64Generator`] always spins.
+pub fn set_spin_threshold(threshold: Duration) {
+    let nanos = u64::try_from(threshold.as_nanos()).unwrap_or(u64::MAX);
+    SPIN_THRESHOLD_NS.store(nanos, Ordering::Relaxed);
+}
+
+/// The threshold set by [`set_spin_threshold`]
+pub fn spin_threshold() -> Duration {
+    Duration::from_nanos(SPIN_THRESHOLD_NS.load(Ordering::Relaxed))
+}
+
+/// Wait until the next millisecond, spinning at first and then sleeping
+fn wait_next_millisecond(current: u64) {
+    let threshold = spin_threshold();
+    let start = Instant::now();
+    while current_time_ms() == current {
+        if start.elapsed() < threshold {
+            std::hint::spin_loop();
+        } else {
+            std::thread::sleep(until_next_ms_boundary());
+        }
+    }
+}
+
+/// Time left until the system clock reaches the next whole millisecond
+fn until_next_ms_boundary() -> Duration {
+    let subsec_nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.subsec_nanos());
+    Duration::from_nanos(1_000_000 - (subsec_nanos % 1_000_000) as u64)
+}
+
+/// Wait until the clock ticks over to a fresh millisecond and return it
+///
+/// Call this before a burst so the first batch gets the full
+/// [`MAX_IDS_PER_MS`](crate::MAX_IDS_PER_MS) sequence window, which makes
+/// throughput measurements reproducible.
+pub fn align_to_next_ms() -> u64 {
+    wait_next_millisecond(current_time_ms());
+    current_time_ms()
+}
+
+/// The millisecond at which the next fresh sequence window begins
+///
+/// Unlike [`align_to_next_ms`], this doesn't wait: a scheduler pacing its own
+/// generation can sleep until this time instead of letting the generator spin.
+pub fn next_window_start_ms() -> u64 {
+    current_time_ms() + 1
+}
+
+/// How long until milliseconds counted from `epoch_ms` no longer fit in the
+/// 42-bit timestamp field
+///
+/// From the Unix epoch (`0`) the field lasts until 2109, but counting from a
+/// recent custom epoch only buys the same ~139 years from that point. Returns
+/// [`Duration::ZERO`] once the limit has passed. The limit itself is
+/// [`max_representable_date`].
+pub fn time_until_overflow(epoch_ms: u64) -> Duration {
+    overflow_remaining(current_time_ms(), epoch_ms)
+}
+
+/// The last millisecond the 42-bit timestamp field can hold when counting
+/// from `epoch_ms`
+///
+/// From the Unix epoch that is 2109-05-15T07:35:11.103Z. Unlike
+/// [`time_until_overflow`] this doesn't read the clock, so it also suits
+/// bounds checks and lifespans printed in docs.
+pub fn max_representable_date(epoch_ms: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(epoch_ms.saturating_add(MAX_TIMESTAMP_MS))
+}
+
+fn overflow_remaining(now_ms: u64, epoch_ms: u64) -> Duration {
+    // The first millisecond whose offset from the epoch needs 43 bits
+    let overflow_ms = epoch_ms.saturating_add(MAX_TIMESTAMP_MS + 1);
+    Duration::from_millis(overflow_ms.saturating_sub(now_ms))
+}
+
+/// Generate a Tiny64 ID
+pub fn generate_tiny64() -> String {
+    base64_encode_u64(generate_tiny64_u64())
+}
+
+/// Generate a Tiny64 ID as its packed u64 value
+pub fn generate_tiny64_u64() -> u64 {
+    let result: Result<u64, Infallible> = generate_thread_local(|now| {
+        // Sequence overflow: wait for next millisecond
+        wait_next_millisecond(now);
+        Ok((current_time_ms(), 0))
+    });
+    match result {
+        Ok(value) => value,
+    }
+}
+
+/// Like [`generate_tiny64`], but gives up if the clock doesn't advance
+///
+/// When a millisecond's 4096 sequence numbers are used up, `generate_tiny64`
+/// spins until the clock ticks, which never happens on a frozen or badly
+/// virtualized clock. This variant stops spinning after `timeout` (measured on
+/// the monotonic clock) and returns [`Tiny64Error::ClockStalled`]. The
+/// sequence state is left untouched, so a later call can retry.
+pub fn generate_tiny64_bounded(timeout: Duration) -> Result<String, Tiny64Error> {
+    generate_thread_local(|now| {
+        wait_next_millisecond_bounded(now, timeout, current_time_ms).map(|next| (next, 0))
+    })
+    .map(base64_encode_u64)
+}
+
+/// Like [`generate_tiny64`], but never waits for the clock
+///
+/// Once a millisecond's 4096 sequence numbers are used up, further IDs in
+/// that millisecond keep the sequence at its maximum and differ only in
+/// their freshly drawn random bits. Those overflow IDs are not ordered among
+/// themselves, and with only 10 random bits they can collide with each other,
+/// but every call returns immediately. This is the third overflow policy next
+/// to spinning ([`generate_tiny64`]) and failing
+/// ([`generate_tiny64_bounded`]); a [`Tiny64Generator`] picks among them with
+/// [`OverflowPolicy`].
+pub fn generate_saturating() -> String {
+    let result: Result<u64, Infallible> = generate_thread_local(|now| Ok((now, MAX_SEQUENCE)));
+    match result {
+        Ok(value) => base64_encode_u64(value),
+    }
+}
+
+/// Like [`generate_tiny64`], but also returns how long the call spun
+///
+/// The duration only covers waiting for the clock after a sequence overflow,
+/// so it is [`Duration::ZERO`] for all but the first ID of each millisecond
+/// past the 4096th. Histogram it to see what spinning costs; a
+/// [`Tiny64Generator`] counts the same events in
+/// [`GeneratorStats::spins`].
+pub fn generate_timed() -> (String, Duration) {
+    let mut waited = Duration::ZERO;
+    let result: Result<u64, Infallible> = generate_thread_local(|now| {
+        let start = Instant::now();
+        wait_next_millisecond(now);
+        waited = start.elapsed();
+        Ok((current_time_ms(), 0))
+    });
+    match result {
+        Ok(value) => (base64_encode_u64(value), waited),
+    }
+}
+
+/// Spin until `clock` leaves `current`, or fail once `timeout` has elapsed
+fn wait_next_millisecond_bounded(
+    current: u64,
+    timeout: Duration,
+    clock: impl Fn() -> u64,
+) -> Result<u64, Tiny64Error> {
+    let start = Instant::now();
+    loop {
+        let now = clock();
+        if now != current {
+            return Ok(now);
+        }
+        if start.elapsed() >= timeout {
+            return Err(Tiny64Error::ClockStalled);
+        }
+        std::hint::spin_loop();
+    }
+}
+
+/// Mint the next ID from the thread-local state
+///
+/// `on_overflow` is called with the current millisecond once its sequence
+/// numbers are used up, and returns the millisecond and sequence number to
+/// continue with. If it fails, the state is left as it was.
+fn generate_thread_local<E>(
+    on_overflow: impl FnOnce(u64) -> Result<(u64, u16), E>,
+) -> Result<u64, E> {
+    let (timestamp_ms, sequence, random) = LAST_TIMESTAMP_MS.with(|last_time| {
+        SEQUENCE.with(|seq| {
+            let mut now = current_time_ms();
+            let last = last_time.get();
+            let mut current_seq = seq.get();
+
+            if now == last {
+                // Same millisecond: increment sequence
+                if current_seq == MAX_SEQUENCE {
+                    (now, current_seq) = on_overflow(now)?;
+                } else {
+                    current_seq += 1;
+                }
+            } else {
+                // New millisecond: reset sequence
+                current_seq = 0;
+            }
+
+            // Update state
+            last_time.set(now);
+            seq.set(current_seq);
+
+            // Generate random 10-bit value
+            let random = generate_random_10bit();
+
+            Ok((now, current_seq, random))
+        })
+    })?;
+
+    // Construct 64-bit value:
+    // [ 42 bits: timestamp_ms ] [ 12 bits: sequence ] [ 10 bits: random ]
+    Ok(pack_masked(timestamp_ms, sequence, random))
+}
+
+/// Generate `n` IDs, each in a millisecond of its own
+///
+/// After every ID this spins until the clock has moved past its timestamp, so
+/// no two IDs in the batch share a timestamp. That makes the call take about
+/// `n` milliseconds; use it only where something keys on the timestamp field
+/// alone.
+pub fn generate_distinct_timestamps(n: usize) -> Vec<String> {
+    let mut ids = Vec::with_capacity(n);
+    for i in 0..n {
+        let value = generate_tiny64_u64();
+        ids.push(base64_encode_u64(value));
+        if i + 1 < n {
+            let timestamp_ms = Tiny64Parts::from_u64(value).timestamp_ms;
+            while current_time_ms() <= timestamp_ms {
+                std::hint::spin_loop();
+            }
+        }
+    }
+    ids
+}
+
+/// Generate a Tiny64 ID, returning both the packed value and its encoding
+pub fn generate_pair() -> (u64, String) {
+    let value = generate_tiny64_u64();
+    (value, base64_encode_u64(value))
+}
+
+/// What a [`Tiny64Generator`] does when a millisecond's sequence numbers run
+/// out, set with [`Tiny64GeneratorBuilder::overflow`]
+///
+/// Under every policy the generator's state only moves forward, so IDs never
+/// repeat a timestamp/sequence slot except where [`OverflowPolicy::Saturate`]
+/// says so.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Spin until the clock ticks
+    ///
+    /// Never fails and IDs stay strictly increasing, but the call blocks for
+    /// as long as the clock takes, forever on a frozen clock. The default,
+    /// and what [`generate_tiny64`] does.
+    #[default]
+    Spin,
+    /// Spin for at most the given time, then fail with
+    /// [`Tiny64Error::ClockStalled`]
+    ///
+    /// Bounds the blocking a stalled clock can cause, like
+    /// [`generate_tiny64_bounded`]. A failed call leaves the state untouched,
+    /// so the next one can retry.
+    Timeout(Duration),
+    /// Fail at once with [`Tiny64Error::SequenceExhausted`]
+    ///
+    /// Never blocks or spins, for callers that would rather shed load or
+    /// retry at [`next_window_start_ms`]. A failed call leaves the state
+    /// untouched.
+    Error,
+    /// Reuse the millisecond's last sequence number
+    ///
+    /// Never blocks or fails, like [`generate_saturating`]. IDs past the
+    /// millisecond's capacity differ only in their random bits, so they are
+    /// not ordered among themselves and can collide.
+    Saturate,
+}
+
+/// An owned Tiny64 generator
+///
+/// Unlike [`generate_tiny64`], which keeps its state per thread, a
+/// `Tiny64Generator` owns its timestamp/sequence state. It never mints an ID
+/// less than or equal to the last one it minted: if the clock reads earlier
+/// than the last timestamp, it keeps counting from that timestamp instead.
+///
+/// Nothing in an ID records which of the two produced it: both use the same
+/// layout and decode identically, so the monotonic guarantee can't be
+/// recovered or checked from the ID alone. Track provenance alongside the ID
+/// if mixed sources need to be told apart.
+///
+/// With a state file configured, the last timestamp and sequence are loaded on
+/// build and written back on drop, so that guarantee survives process
+/// restarts. The file is replaced atomically (written to a temporary file,
+/// fsynced, then renamed over the old one), but the directory itself is not
+/// fsynced and nothing is written if the process dies without dropping the
+/// generator. Call [`Tiny64Generator::persist`] to checkpoint explicitly.
+///
+/// Random bits come from the generator's [`RandomSource`], [`HashRandom`]
+/// unless another one is configured on the builder. Time likewise comes only
+/// from its [`Clock`], [`SystemClock`] unless the builder is given another; see
+/// [`Clock`] for plugging in a hybrid logical or hardware clock.
+#[derive(Debug)]
+pub struct Tiny64Generator<R = HashRandom, C = SystemClock> {
+    inner: ClockGenerator<C, R>,
+    overflow: OverflowPolicy,
+    state_file: Option<PathBuf>,
+    log_stats_on_drop: bool,
+}
+
+/// Where a generator has got to: the last timestamp it minted at and the
+/// sequence number it last used there
+///
+/// Take it from a retiring generator with [`Tiny64Generator::state`] and
+/// start its replacement with [`Tiny64Generator::resume_from`], e.g. across
+/// a rolling restart. The replacement carries on from the next sequence
+/// number instead of restarting the millisecond at 0, so the two never hand
+/// out the same slot, provided the old one stops minting first.
+///
+/// Serialised with `Display` and parsed with `FromStr` as the timestamp and
+/// sequence in decimal, separated by a space: `1705312345123 41`. State
+/// files hold the same line. Parsing ignores surrounding whitespace, such as
+/// a trailing newline, and rejects a sequence above [`MAX_SEQUENCE`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GeneratorState {
+    pub last_timestamp_ms: u64,
+    pub sequence: u16,
+}
+
+/// Error returned for text that is not a serialised [`GeneratorState`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidGeneratorState;
+
+impl fmt::Display for InvalidGeneratorState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "malformed tiny64 generator state")
+    }
+}
+
+impl std::error::Error for InvalidGeneratorState {}
+
+impl fmt::Display for GeneratorState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.last_timestamp_ms, self.sequence)
+    }
+}
+
+impl FromStr for GeneratorState {
+    type Err = InvalidGeneratorState;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (timestamp_ms, sequence) = text.trim().split_once(' ').ok_or(InvalidGeneratorState)?;
+        let last_timestamp_ms = timestamp_ms.parse().map_err(|_| InvalidGeneratorState)?;
+        let sequence = sequence
+            .parse()
+            .ok()
+            .filter(|&sequence| sequence <= MAX_SEQUENCE)
+            .ok_or(InvalidGeneratorState)?;
+        Ok(GeneratorState {
+            last_timestamp_ms,
+            sequence,
+        })
+    }
+}
+
+impl GeneratorState {
+    fn from_sequence(state: SequenceState) -> Self {
+        GeneratorState {
+            last_timestamp_ms: state.last_timestamp_ms,
+            sequence: state.sequence,
+        }
+    }
+
+    fn to_sequence(self) -> SequenceState {
+        SequenceState {
+            last_timestamp_ms: self.last_timestamp_ms,
+            sequence: self.sequence,
+        }
+    }
+}
+
+/// Builder for [`Tiny64Generator`]
+#[derive(Debug, Clone)]
+pub struct Tiny64GeneratorBuilder<R = HashRandom, C = SystemClock> {
+    layout: Layout,
+    random: R,
+    clock: C,
+    time_granularity_ms: u64,
+    epoch_ms: u64,
+    overflow: OverflowPolicy,
+    state_file: Option<PathBuf>,
+    log_stats_on_drop: bool,
+    /// Set by [`node_id`](Self::node_id) and checked against the layout in
+    /// [`build`](Self::build)
+    node_id: Option<u16>,
+}
+
+impl Default for Tiny64GeneratorBuilder {
+    fn default() -> Self {
+        Tiny64GeneratorBuilder {
+            layout: Layout::DEFAULT,
+            random: HashRandom,
+            clock: SystemClock,
+            time_granularity_ms: 1,
+            epoch_ms: 0,
+            overflow: OverflowPolicy::Spin,
+            state_file: None,
+            log_stats_on_drop: false,
+            node_id: None,
+        }
+    }
+}
+
+impl<R: RandomSource, C: Clock> Tiny64GeneratorBuilder<R, C> {
+    /// Draw random bits from `random` instead of the default source
+    pub fn random_source<S: RandomSource>(self, random: S) -> Tiny64GeneratorBuilder<S, C> {
+        Tiny64GeneratorBuilder {
+            layout: self.layout,
+            random,
+            clock: self.clock,
+            time_granularity_ms: self.time_granularity_ms,
+            epoch_ms: self.epoch_ms,
+            overflow: self.overflow,
+            state_file: self.state_file,
+            log_stats_on_drop: self.log_stats_on_drop,
+            node_id: None,
+        }
+    }
+
+    /// Read time from `clock` instead of the system clock
+    pub fn clock<D: Clock>(self, clock: D) -> Tiny64GeneratorBuilder<R, D> {
+        Tiny64GeneratorBuilder {
+            layout: self.layout,
+            random: self.random,
+            clock,
+            time_granularity_ms: self.time_granularity_ms,
+            epoch_ms: self.epoch_ms,
+            overflow: self.overflow,
+            state_file: self.state_file,
+            log_stats_on_drop: self.log_stats_on_drop,
+            node_id: self.node_id,
+        }
+    }
+
+    /// Mix a per-node `salt` into the random bits of every ID
+    ///
+    /// Wraps the configured source in a [`SaltedRandom`]. This reduces
+    /// cross-node collisions only if every node is given a different salt.
+    pub fn with_random_salt(self, salt: u64) -> Tiny64GeneratorBuilder<SaltedRandom<R>, C> {
+        Tiny64GeneratorBuilder {
+            layout: self.layout,
+            random: SaltedRandom::new(self.random, salt),
+            clock: self.clock,
+            time_granularity_ms: self.time_granularity_ms,
+            epoch_ms: self.epoch_ms,
+            overflow: self.overflow,
+            state_file: self.state_file,
+            log_stats_on_drop: self.log_stats_on_drop,
+            node_id: None,
+        }
+    }
+
+    /// Write `node_id` into every ID in place of random bits
+    ///
+    /// Generators with distinct node IDs can never mint the same ID. Read the
+    /// node back with [`node_of`](crate::node_of) or
+    /// [`is_from_node`](crate::is_from_node). Meant for the default layout,
+    /// where the node field is 10 bits wide. With a narrower
+    /// [`layout`](Self::layout), [`build`](Self::build) fails if the node ID
+    /// doesn't fit in its random field, rather than truncating it into
+    /// another node's ID.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node_id` exceeds [`MAX_NODE_ID`].
+    pub fn node_id(self, node_id: u16) -> Tiny64GeneratorBuilder<FixedRandom, C> {
+        assert!(node_id <= MAX_NODE_ID, "node ID at most {}", MAX_NODE_ID);
+        Tiny64GeneratorBuilder {
+            layout: self.layout,
+            random: FixedRandom::new(node_id as u64),
+            clock: self.clock,
+            time_granularity_ms: self.time_granularity_ms,
+            epoch_ms: self.epoch_ms,
+            overflow: self.overflow,
+            state_file: self.state_file,
+            log_stats_on_drop: self.log_stats_on_drop,
+            node_id: Some(node_id),
+        }
+    }
+
+    /// Use a non-default field layout
+    pub fn layout(mut self, layout: Layout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Round timestamps down to multiples of `ms`; see
+    /// [`ClockGenerator::with_time_granularity_ms`]
+    pub fn time_granularity_ms(mut self, ms: u64) -> Self {
+        self.time_granularity_ms = ms;
+        self
+    }
+
+    /// Count timestamps from a custom epoch; see
+    /// [`ClockGenerator::with_epoch_ms`]
+    pub fn epoch_ms(mut self, epoch_ms: u64) -> Self {
+        self.epoch_ms = epoch_ms;
+        self
+    }
+
+    /// Apply the epoch and layout from parsed [`Settings`]
+    pub fn settings(self, settings: &Settings) -> Self {
+        self.epoch_ms(settings.epoch_ms).layout(settings.layout)
+    }
+
+    /// Choose what happens when a millisecond's sequence numbers run out
+    ///
+    /// With [`OverflowPolicy::Timeout`] or [`OverflowPolicy::Error`], mint
+    /// with [`Tiny64Generator::try_generate`], since
+    /// [`generate`](Tiny64Generator::generate) panics when the policy fails.
+    pub fn overflow(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow = policy;
+        self
+    }
+
+    /// Persist generator state to `path` across restarts
+    pub fn state_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.state_file = Some(path.into());
+        self
+    }
+
+    /// Print the generator's [`GeneratorStats`] to stderr when it is dropped
+    ///
+    /// Off by default. Useful for checking whether a long-running generator
+    /// fills its millisecond windows without wiring up metrics.
+    pub fn log_stats_on_drop(mut self, enabled: bool) -> Self {
+        self.log_stats_on_drop = enabled;
+        self
+    }
+
+    /// Build the generator, loading any previously persisted state
+    ///
+    /// Fails with [`io::ErrorKind::InvalidInput`] if a
+    /// [`node_id`](Self::node_id) doesn't fit in the layout's random field.
+    pub fn build(self) -> io::Result<Tiny64Generator<R, C>> {
+        if let Some(node_id) = self.node_id
+            && node_id > self.layout.max_random()
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "node ID {} does not fit in a {}-bit random field",
+                    node_id,
+                    self.layout.random_bits()
+                ),
+            ));
+        }
+        let mut inner = ClockGenerator::with_layout(self.clock, self.random, self.layout)
+            .with_time_granularity_ms(self.time_granularity_ms)
+            .with_epoch_ms(self.epoch_ms);
+        if let Some(path) = &self.state_file {
+            inner.state = load_state(path)?.unwrap_or_default();
+        }
+
+        Ok(Tiny64Generator {
+            inner,
+            overflow: self.overflow,
+            state_file: self.state_file,
+            log_stats_on_drop: self.log_stats_on_drop,
+        })
+    }
+}
+
+impl Default for Tiny64Generator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tiny64Generator {
+    /// Create a generator with no persisted state
+    pub fn new() -> Self {
+        Tiny64Generator {
+            inner: ClockGenerator::new(SystemClock, HashRandom),
+            overflow: OverflowPolicy::Spin,
+            state_file: None,
+            log_stats_on_drop: false,
+        }
+    }
+
+    pub fn builder() -> Tiny64GeneratorBuilder {
+        Tiny64GeneratorBuilder::default()
+    }
+
+    /// Create a generator that carries on from where another one stopped
+    ///
+    /// Its first ID uses the sequence number after `state`'s in the same
+    /// millisecond, or the clock's current millisecond if that is later. A
+    /// state ahead of the clock is kept counting from, as after a restart.
+    pub fn resume_from(state: GeneratorState) -> Self {
+        let mut generator = Self::new();
+        generator.inner.state = state.to_sequence();
+        generator
+    }
+
+    /// A fully deterministic generator: the same arguments always yield the
+    /// same sequence of IDs
+    ///
+    /// Time comes from a [`SteppingClock`] starting at `start_ms`, so each ID
+    /// lands in the next millisecond, and random bits from a
+    /// [`SeededRandom`] seeded with `rng_seed`. Meant for golden tests and
+    /// for replaying a sequence whose seed is known; its IDs are trivially
+    /// predictable.
+    pub fn seeded(start_ms: u64, rng_seed: u64) -> Tiny64Generator<SeededRandom, SteppingClock> {
+        Tiny64Generator {
+            inner: ClockGenerator::new(SteppingClock::new(start_ms), SeededRandom::new(rng_seed)),
+            overflow: OverflowPolicy::Spin,
+            state_file: None,
+            log_stats_on_drop: false,
+        }
+    }
+}
+
+impl<R, C> Tiny64Generator<R, C> {
+    /// The field layout this generator packs IDs with
+    pub fn layout(&self) -> Layout {
+        self.inner.layout()
+    }
+
+    /// Counters accumulated since the generator was built
+    pub fn stats(&self) -> GeneratorStats {
+        self.inner.stats()
+    }
+
+    /// The last timestamp and sequence this generator used, to hand over to
+    /// [`Tiny64Generator::resume_from`]
+    pub fn state(&self) -> GeneratorState {
+        GeneratorState::from_sequence(self.inner.state)
+    }
+
+    /// Write the current state to the state file, if one is configured
+    pub fn persist(&self) -> io::Result<()> {
+        match &self.state_file {
+            Some(path) => save_state(path, self.inner.state),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<R: RandomSource, C: Clock> Tiny64Generator<R, C> {
+    /// Generate the next Tiny64 ID
+    ///
+    /// With a custom layout whose timestamp field is too narrow for the
+    /// clock's reading, the timestamp is truncated to fit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [`OverflowPolicy`] fails; use
+    /// [`try_generate`](Self::try_generate) with a policy that can.
+    pub fn generate(&mut self) -> String {
+        base64_encode_u64(self.generate_u64())
+    }
+
+    /// Generate the next ID as its packed u64 value
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [`OverflowPolicy`] fails.
+    pub fn generate_u64(&mut self) -> u64 {
+        self.try_generate_u64()
+            .unwrap_or_else(|e| panic!("tiny64: {}", e))
+    }
+
+    /// Generate the next Tiny64 ID, failing as the [`OverflowPolicy`] says
+    ///
+    /// Never fails under [`OverflowPolicy::Spin`] or
+    /// [`OverflowPolicy::Saturate`].
+    pub fn try_generate(&mut self) -> Result<String, Tiny64Error> {
+        self.try_generate_u64().map(base64_encode_u64)
+    }
+
+    /// Like [`try_generate`](Self::try_generate), as the packed u64 value
+    pub fn try_generate_u64(&mut self) -> Result<u64, Tiny64Error> {
+        let policy = self.overflow;
+        let mut spin_start = None;
+        self.inner.try_generate_u64(|| match policy {
+            OverflowPolicy::Spin => Ok(Stall::Wait),
+            OverflowPolicy::Timeout(timeout) => {
+                let start = *spin_start.get_or_insert_with(Instant::now);
+                if start.elapsed() >= timeout {
+                    Err(Tiny64Error::ClockStalled)
+                } else {
+                    Ok(Stall::Wait)
+                }
+            }
+            OverflowPolicy::Error => Err(Tiny64Error::SequenceExhausted),
+            OverflowPolicy::Saturate => Ok(Stall::Saturate),
+        })
+    }
+
+    /// Number of unpredictable bits in each ID; see
+    /// [`ClockGenerator::effective_entropy_bits`]
+    pub fn effective_entropy_bits(&self) -> f64 {
+        self.inner.effective_entropy_bits()
+    }
+
+    /// An endless iterator of IDs minted by this generator
+    ///
+    /// Bound it with `take` or `by_ref` before collecting.
+    pub fn iter(&mut self) -> Tiny64Iter<'_, R, C> {
+        Tiny64Iter { generator: self }
+    }
+}
+
+/// Iterator returned by [`Tiny64Generator::iter`]
+///
+/// It never returns `None`, so it is fused and its `size_hint` is
+/// `(usize::MAX, None)`.
+pub struct Tiny64Iter<'a, R = HashRandom, C = SystemClock> {
+    generator: &'a mut Tiny64Generator<R, C>,
+}
+
+impl<R: RandomSource, C: Clock> Iterator for Tiny64Iter<'_, R, C> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        Some(self.generator.generate())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl<R: RandomSource, C: Clock> FusedIterator for Tiny64Iter<'_, R, C> {}
+
+/// Cloning snapshots the timestamp/sequence state so the copy continues
+/// independently from the same point.
+///
+/// The two generators will then hand out the same timestamp/sequence slots,
+/// so their IDs differ only in their random bits and can collide. The clone
+/// is detached from any state file so the two
+/// don't overwrite each other's persisted state on drop.
+impl<R: Clone, C: Clone> Clone for Tiny64Generator<R, C> {
+    fn clone(&self) -> Self {
+        Tiny64Generator {
+            inner: self.inner.clone(),
+            overflow: self.overflow,
+            state_file: None,
+            log_stats_on_drop: self.log_stats_on_drop,
+        }
+    }
+}
+
+impl<R, C> Drop for Tiny64Generator<R, C> {
+    fn drop(&mut self) {
+        if let Err(e) = self.persist() {
+            eprintln!("tiny64: failed to persist generator state: {}", e);
+        }
+        if self.log_stats_on_drop {
+            let stats = self.stats();
+            eprintln!(
+                "tiny64: generator stats: generated={} last_timestamp_ms={} max_sequence={} spins={}",
+                stats.generated, stats.last_timestamp_ms, stats.max_sequence, stats.spins
+            );
+        }
+    }
+}
+
+// State file format: a single serialised `GeneratorState` line
+fn load_state(path: &Path) -> io::Result<Option<SequenceState>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let state: GeneratorState = contents
+        .parse()
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "malformed tiny64 state file"))?;
+    Ok(Some(state.to_sequence()))
+}
+
+fn save_state(path: &Path, state: SequenceState) -> io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+
+    let mut file = fs::File::create(&tmp)?;
+    writeln!(file, "{}", GeneratorState::from_sequence(state))?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::FixedRandom;
+    use crate::{MAX_RANDOM, decode_tiny64, decode_u64, timestamp_of};
+
+    #[test]
+    fn test_generate_tiny64_format() {
+        let id = generate_tiny64();
+        assert_eq!(id.len(), 11);
+
+        // Check all characters are Base64 URL-safe
+        for ch in id.chars() {
+            assert!(ch.is_ascii_alphanumeric() || ch == '-' || ch == '_');
+        }
+    }
+
+    #[test]
+    fn test_multiple_ids_different() {
+        let id1 = generate_tiny64();
+        let id2 = generate_tiny64();
+        let id3 = generate_tiny64();
+
+        // IDs should be different (very high probability)
+        assert_ne!(id1, id2);
+        assert_ne!(id2, id3);
+    }
+
+    #[test]
+    fn test_time_ordering() {
+        let mut ids = vec![];
+        for _ in 0..100 {
+            ids.push(generate_tiny64());
+        }
+
+        // IDs should be in lexical order (or equal if same millisecond)
+        for i in 0..ids.len() - 1 {
+            if ids[i] > ids[i + 1] {
+                eprintln!(
+                    "Order violation at index {}: '{}' > '{}'",
+                    i,
+                    ids[i],
+                    ids[i + 1]
+                );
+            }
+            assert!(ids[i] <= ids[i + 1]);
+        }
+    }
+
+    #[test]
+    fn test_align_to_next_ms() {
+        let before = current_time_ms();
+        assert!(align_to_next_ms() > before);
+    }
+
+    #[test]
+    fn test_until_next_ms_boundary() {
+        assert!(until_next_ms_boundary() <= Duration::from_millis(1));
+    }
+
+    #[test]
+    fn test_next_window_start_ms() {
+        let before = current_time_ms();
+        let next = next_window_start_ms();
+        assert!(next > before);
+        assert!(next <= current_time_ms() + 1);
+    }
+
+    #[test]
+    fn test_time_until_overflow() {
+        assert_eq!(
+            overflow_remaining(MAX_TIMESTAMP_MS, 0),
+            Duration::from_millis(1)
+        );
+        assert_eq!(overflow_remaining(MAX_TIMESTAMP_MS + 1, 0), Duration::ZERO);
+        assert_eq!(overflow_remaining(u64::MAX, 0), Duration::ZERO);
+        // An epoch in the future extends the deadline by the gap
+        assert_eq!(
+            overflow_remaining(0, 1_000),
+            Duration::from_millis(MAX_TIMESTAMP_MS + 1_001)
+        );
+
+        // From the Unix epoch, the limit is decades away
+        let years = time_until_overflow(0).as_secs() / (365 * 24 * 3600);
+        assert!((50..90).contains(&years), "{}", years);
+        assert!(time_until_overflow(current_time_ms()) > time_until_overflow(0));
+    }
+
+    #[test]
+    fn test_max_representable_date() {
+        let last_ms = |epoch_ms| {
+            let date = max_representable_date(epoch_ms);
+            date.duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
+        };
+        assert_eq!(last_ms(0), MAX_TIMESTAMP_MS);
+        assert_eq!(
+            crate::format_iso8601(last_ms(0)),
+            "2109-05-15T07:35:11.103Z"
+        );
+        assert_eq!(last_ms(1_000), MAX_TIMESTAMP_MS + 1_000);
+
+        let epoch_ms = current_time_ms();
+        let now = SystemTime::now();
+        let remaining = max_representable_date(epoch_ms)
+            .duration_since(now)
+            .unwrap();
+        let diff = remaining.abs_diff(time_until_overflow(epoch_ms));
+        assert!(diff < Duration::from_secs(1), "{:?}", diff);
+    }
+
+    #[test]
+    fn test_bounded_wait_reports_stall() {
+        let frozen = || 1_000;
+        assert_eq!(
+            wait_next_millisecond_bounded(1_000, Duration::from_millis(5), frozen),
+            Err(Tiny64Error::ClockStalled)
+        );
+        assert_eq!(
+            wait_next_millisecond_bounded(999, Duration::from_millis(5), frozen),
+            Ok(1_000)
+        );
+        assert!(generate_tiny64_bounded(Duration::from_millis(5)).is_ok());
+    }
+
+    #[test]
+    fn test_generate_saturating_never_blocks() {
+        let parts: Vec<_> = (0..3 * crate::MAX_IDS_PER_MS)
+            .map(|_| decode_tiny64(&generate_saturating()).unwrap())
+            .collect();
+
+        // Within each millisecond the sequence counts up from 0 and then
+        // sticks at its maximum instead of moving on to the next millisecond
+        let mut nth_in_ms = 0;
+        for (i, p) in parts.iter().enumerate() {
+            if i > 0 && p.timestamp_ms == parts[i - 1].timestamp_ms {
+                nth_in_ms += 1;
+            } else {
+                nth_in_ms = 0;
+            }
+            assert_eq!(p.sequence, nth_in_ms.min(MAX_SEQUENCE as usize) as u16);
+        }
+    }
+
+    #[test]
+    fn test_generate_timed() {
+        align_to_next_ms();
+        let (id, waited) = generate_timed();
+        assert_eq!(waited, Duration::ZERO);
+        assert!(decode_tiny64(&id).is_ok());
+
+        // Whenever a call did wait, it is the first ID of a new millisecond
+        let mut last = decode_tiny64(&id).unwrap();
+        for _ in 0..3 * crate::MAX_IDS_PER_MS {
+            let (id, waited) = generate_timed();
+            let parts = decode_tiny64(&id).unwrap();
+            if waited > Duration::ZERO {
+                assert_eq!(parts.sequence, 0);
+                assert!(parts.timestamp_ms > last.timestamp_ms);
+            }
+            last = parts;
+        }
+    }
+
+    #[test]
+    fn test_generate_distinct_timestamps() {
+        let start = Instant::now();
+        let ids = generate_distinct_timestamps(5);
+        // Five distinct milliseconds span at least three whole ones
+        assert!(start.elapsed() >= Duration::from_millis(3));
+
+        let timestamps: Vec<u64> = ids.iter().map(|id| timestamp_of(id).unwrap()).collect();
+        for pair in timestamps.windows(2) {
+            assert!(pair[0] < pair[1]);
+        }
+        assert!(generate_distinct_timestamps(0).is_empty());
+    }
+
+    #[test]
+    fn test_generate_pair_matches() {
+        let (value, id) = generate_pair();
+        assert_eq!(decode_u64(&id), Ok(value));
+    }
+
+    #[test]
+    fn test_generator_custom_layout() {
+        let layout = Layout::new(42, 6, 16).unwrap();
+        let mut generator = Tiny64Generator::builder().layout(layout).build().unwrap();
+
+        let ids: Vec<String> = (0..200).map(|_| generator.generate()).collect();
+        for pair in ids.windows(2) {
+            assert!(pair[0] < pair[1]);
+        }
+        for id in &ids {
+            let parts = layout.unpack(decode_u64(id).unwrap());
+            assert!(parts.sequence <= layout.max_sequence());
+            assert!(parts.timestamp_ms.abs_diff(current_time_ms()) < 1000);
+        }
+    }
+
+    #[test]
+    fn test_iter_protocol() {
+        let mut generator = Tiny64Generator::new();
+        let mut iter = generator.iter();
+        assert_eq!(iter.size_hint(), (usize::MAX, None));
+
+        let first: Vec<(usize, String)> = iter.by_ref().enumerate().take(3).collect();
+        assert_eq!(first.iter().map(|(i, _)| *i).collect::<Vec<_>>(), [0, 1, 2]);
+        assert!(first.windows(2).all(|pair| pair[0].1 < pair[1].1));
+
+        // by_ref left the iterator usable, and it carries on from the same state
+        let next = iter.next().unwrap();
+        assert!(first[2].1 < next);
+        assert_eq!(generator.stats().generated, 4);
+    }
+
+    #[test]
+    fn test_time_granularity() {
+        let mut generator = Tiny64Generator::builder()
+            .time_granularity_ms(10)
+            .build()
+            .unwrap();
+        let ids: Vec<String> = (0..100).map(|_| generator.generate()).collect();
+        for pair in ids.windows(2) {
+            assert!(pair[0] < pair[1]);
+        }
+        for id in &ids {
+            assert_eq!(timestamp_of(id).unwrap() % 10, 0);
+        }
+    }
+
+    #[test]
+    fn test_overflow_policies() {
+        let frozen = |policy| {
+            Tiny64Generator::builder()
+                .clock(|| 1_000)
+                .overflow(policy)
+                .build()
+                .unwrap()
+        };
+        let fill = |generator: &mut Tiny64Generator<HashRandom, _>| {
+            for _ in 0..=MAX_SEQUENCE {
+                generator.try_generate().unwrap();
+            }
+        };
+
+        let mut generator = frozen(OverflowPolicy::Error);
+        fill(&mut generator);
+        assert_eq!(
+            generator.try_generate(),
+            Err(Tiny64Error::SequenceExhausted)
+        );
+        assert_eq!(generator.stats().generated, MAX_SEQUENCE as u64 + 1);
+
+        let mut generator = frozen(OverflowPolicy::Timeout(Duration::from_millis(2)));
+        fill(&mut generator);
+        let start = Instant::now();
+        assert_eq!(generator.try_generate(), Err(Tiny64Error::ClockStalled));
+        assert!(start.elapsed() >= Duration::from_millis(2));
+
+        let mut generator = frozen(OverflowPolicy::Saturate);
+        fill(&mut generator);
+        let parts = decode_tiny64(&generator.generate()).unwrap();
+        assert_eq!((parts.timestamp_ms, parts.sequence), (1_000, MAX_SEQUENCE));
+
+        // Spinning waits for the clock instead
+        let mut reads = 0u64;
+        let clock = move || {
+            reads += 1;
+            1_000 + reads / 5_000
+        };
+        let mut generator = Tiny64Generator::builder()
+            .clock(clock)
+            .overflow(OverflowPolicy::Spin)
+            .build()
+            .unwrap();
+        let ids: Vec<String> = (0..=MAX_SEQUENCE as usize + 1)
+            .map(|_| generator.try_generate().unwrap())
+            .collect();
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(timestamp_of(ids.last().unwrap()), Ok(1_001));
+    }
+
+    #[test]
+    #[should_panic(expected = "sequence numbers for this millisecond are used up")]
+    fn test_generate_panics_when_policy_fails() {
+        let mut generator = Tiny64Generator::builder()
+            .clock(|| 1_000)
+            .overflow(OverflowPolicy::Error)
+            .build()
+            .unwrap();
+        for _ in 0..=MAX_SEQUENCE as usize + 1 {
+            generator.generate();
+        }
+    }
+
+    #[test]
+    fn test_seeded_is_reproducible() {
+        let run = |seed| {
+            let mut generator = Tiny64Generator::seeded(1_700_000_000_000, seed);
+            generator.iter().take(50).collect::<Vec<String>>()
+        };
+        let ids = run(7);
+        assert_eq!(ids, run(7));
+        assert_ne!(ids, run(8));
+
+        let first = decode_tiny64(&ids[0]).unwrap();
+        assert_eq!((first.timestamp_ms, first.sequence), (1_700_000_000_000, 0));
+        assert_eq!(
+            decode_tiny64(&ids[49]).unwrap().timestamp_ms,
+            1_700_000_000_049
+        );
+        assert_eq!(Tiny64Generator::seeded(0, 7).effective_entropy_bits(), 0.0);
+    }
+
+    #[test]
+    fn test_effective_entropy_bits() {
+        assert_eq!(Tiny64Generator::new().effective_entropy_bits(), 10.0);
+
+        let layout = Layout::new(42, 6, 16).unwrap();
+        let generator = Tiny64Generator::builder().layout(layout).build().unwrap();
+        assert_eq!(generator.effective_entropy_bits(), 16.0);
+
+        let generator = Tiny64Generator::builder()
+            .random_source(FixedRandom::new(0x2AA))
+            .build()
+            .unwrap();
+        assert_eq!(generator.effective_entropy_bits(), 0.0);
+    }
+
+    #[test]
+    fn test_custom_random_source() {
+        let mut generator = Tiny64Generator::builder()
+            .random_source(FixedRandom::new(0x2AA))
+            .build()
+            .unwrap();
+        for _ in 0..10 {
+            let parts = decode_tiny64(&generator.generate()).unwrap();
+            assert_eq!(parts.random, 0x2AA);
+        }
+    }
+
+    #[test]
+    fn test_random_salt_changes_random_bits() {
+        let random_bits = |salt| {
+            let mut generator = Tiny64Generator::builder()
+                .random_source(FixedRandom::new(7))
+                .with_random_salt(salt)
+                .build()
+                .unwrap();
+            decode_tiny64(&generator.generate()).unwrap().random
+        };
+        assert_ne!(random_bits(0xA), random_bits(0xB));
+        assert_eq!(random_bits(0xA), random_bits(0xA));
+    }
+
+    #[test]
+    fn test_node_id_generators_never_collide() {
+        let mut a = Tiny64Generator::builder().node_id(1).build().unwrap();
+        let mut b = Tiny64Generator::builder().node_id(2).build().unwrap();
+        for _ in 0..2000 {
+            let (id_a, id_b) = (a.generate(), b.generate());
+            assert_ne!(id_a, id_b);
+            assert_eq!(crate::is_from_node(&id_a, 1), Ok(true));
+            assert_eq!(crate::node_of(&id_b), Ok(2));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "node ID at most 1023")]
+    fn test_node_id_out_of_range() {
+        let _ = Tiny64Generator::builder().node_id(MAX_NODE_ID + 1);
+    }
+
+    #[test]
+    fn test_node_id_must_fit_layout() {
+        let narrow = Layout::new(46, 10, 8).unwrap();
+        let err = Tiny64Generator::builder()
+            .layout(narrow)
+            .node_id(300)
+            .build()
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        let mut generator = Tiny64Generator::builder()
+            .node_id(255)
+            .layout(narrow)
+            .build()
+            .unwrap();
+        assert_eq!(generator.generate_u64() & 0xFF, 255);
+    }
+
+    #[test]
+    fn test_custom_clock() {
+        // A slow clock that only ticks every 10000 reads: the generator must
+        // take all its time from it and wait on it when a millisecond fills up
+        let mut reads = 0u64;
+        let clock = move || {
+            reads += 1;
+            1_000_000 + reads / 10_000
+        };
+        let mut generator = Tiny64Generator::builder().clock(clock).build().unwrap();
+
+        let parts: Vec<_> = (0..5_000)
+            .map(|_| decode_tiny64(&generator.generate()).unwrap())
+            .collect();
+        assert_eq!((parts[0].timestamp_ms, parts[0].sequence), (1_000_000, 0));
+        assert_eq!(
+            (parts[4096].timestamp_ms, parts[4096].sequence),
+            (1_000_001, 0)
+        );
+        assert_eq!(parts[4999].timestamp_ms, 1_000_001);
+    }
+
+    #[test]
+    fn test_generator_stats() {
+        let mut generator = Tiny64Generator::builder()
+            .log_stats_on_drop(true)
+            .build()
+            .unwrap();
+        assert_eq!(generator.stats(), GeneratorStats::default());
+
+        let last = decode_tiny64(&generator.generate()).unwrap();
+        let stats = generator.stats();
+        assert_eq!(stats.generated, 1);
+        assert_eq!(stats.last_timestamp_ms, last.timestamp_ms);
+        assert_eq!(stats.spins, 0);
+    }
+
+    #[test]
+    fn test_clone_forks_state() {
+        let mut generator = Tiny64Generator::new();
+        let before = generator.generate();
+
+        let mut fork = generator.clone();
+        assert_eq!(fork.inner.state, generator.inner.state);
+
+        // Advancing the fork leaves the original where it was
+        let snapshot = generator.inner.state;
+        for _ in 0..10 {
+            assert!(fork.generate() > before);
+        }
+        assert_eq!(generator.inner.state, snapshot);
+        assert!(generator.generate() > before);
+    }
+
+    #[test]
+    fn test_state_file_survives_restart() {
+        let path = std::env::temp_dir().join(format!("tiny64-state-{}", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        // Pretend a previous run minted IDs far in the future
+        let future_ms = current_time_ms() + 60_000;
+        let state = SequenceState {
+            last_timestamp_ms: future_ms,
+            sequence: 7,
+        };
+        save_state(&path, state).unwrap();
+        let persisted = pack_masked(future_ms, 7, MAX_RANDOM);
+
+        let last = {
+            let mut generator = Tiny64Generator::builder()
+                .state_file(&path)
+                .build()
+                .unwrap();
+            let id = generator.generate();
+            assert!(decode_u64(&id).unwrap() > persisted);
+            id
+        };
+
+        // Dropping the generator persisted its state; a new one continues after it
+        let mut generator = Tiny64Generator::builder()
+            .state_file(&path)
+            .build()
+            .unwrap();
+        assert!(generator.generate() > last);
+
+        drop(generator);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_resume_from_continues_sequence() {
+        let future_ms = current_time_ms() + 60_000;
+        let mut old = Tiny64Generator::resume_from(GeneratorState {
+            last_timestamp_ms: future_ms,
+            sequence: 40,
+        });
+        let last = old.generate();
+        let handoff = old.state();
+        assert_eq!(handoff.to_string(), format!("{} 41", future_ms));
+        drop(old);
+
+        let mut new = Tiny64Generator::resume_from(handoff.to_string().parse().unwrap());
+        let next = decode_tiny64(&new.generate()).unwrap();
+        assert_eq!((next.timestamp_ms, next.sequence), (future_ms, 42));
+        assert!(new.generate() > last);
+
+        assert_eq!(
+            "12 7\n".parse(),
+            Ok(GeneratorState {
+                last_timestamp_ms: 12,
+                sequence: 7,
+            })
+        );
+        for bad in ["", "12", "12 x", "x 7", "12 4096", "12 7 9"] {
+            assert_eq!(
+                bad.parse::<GeneratorState>(),
+                Err(InvalidGeneratorState),
+                "{:?}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn test_debug_values() {
+        // Generate a few IDs and print raw values
+        for _ in 0..5 {
+            let id = generate_tiny64();
+            println!(
+                "Generated ID: {} ({})",
+                id,
+                crate::debug_fields(&id).unwrap()
+            );
+        }
+    }
+}
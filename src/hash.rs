@@ -0,0 +1,66 @@
+// Small, stable hash helpers. std's hashers are not guaranteed to produce the
+// same output across Rust releases, so anything persisted or compared across
+// processes goes through these instead.
+
+use crate::decode::{ParseError, decode_u64};
+
+/// FNV-1a 64-bit hash of a byte string
+pub(crate) fn fnv1a_64(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for &b in bytes {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+/// SplitMix64 finalizer: spreads every input bit across the whole output
+pub(crate) fn mix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// A well-spread 64-bit hash of an ID, for Bloom filters and hash tables
+///
+/// The ID's own value is a poor key: IDs minted close together share their
+/// high timestamp bits and differ only in a few low ones, so a filter that
+/// derives bit positions from the value concentrates a batch of recent IDs in
+/// a small stretch of its bit array. This runs the decoded value through the
+/// SplitMix64 finalizer, which spreads every input bit across the whole
+/// output. The mix is a bijection, so distinct IDs never share a hash, and it
+/// is stable across processes and platforms. It is not a keyed hash and
+/// offers no protection against deliberately chosen IDs.
+pub fn id_hash(id: &str) -> Result<u64, ParseError> {
+    decode_u64(id).map(mix64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fnv1a_known_vectors() {
+        assert_eq!(fnv1a_64(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a_64(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn test_id_hash_spreads_consecutive_ids() {
+        // 4096 IDs from one millisecond differ only in their low 22 bits
+        let mut buckets = [0u32; 64];
+        for sequence in 0..4096u64 {
+            let id = crate::base64_encode_u64(1_700_000_000_000 << 22 | sequence << 10);
+            buckets[(id_hash(&id).unwrap() >> 58) as usize] += 1;
+        }
+        // 64 expected per bucket
+        assert!(
+            buckets.iter().all(|&count| (30..110).contains(&count)),
+            "{:?}",
+            buckets
+        );
+
+        assert_eq!(id_hash("-----------"), Ok(mix64(0)));
+        assert_eq!(id_hash("bad"), Err(ParseError::InvalidLength(3)));
+    }
+}
@@ -0,0 +1,233 @@
+//! Provenance headers that make batch files self-describing.
+//!
+//! A header is one line recording how the IDs after it were minted:
+//!
+//! ```text
+//! #tiny64 version=0.1.0 epoch_ms=1700000000000 timestamp_bits=42 sequence_bits=12 random_bits=10 node_id=7
+//! ```
+//!
+//! The line starts with `#tiny64` and is followed by space-separated
+//! `key=value` fields in this order: `version` is the version of the crate
+//! that wrote the file, `epoch_ms` and the three bit widths are the
+//! generator's [`Settings`], and `node_id`, present only for node-ID
+//! generators, is the value every ID carries in its random field. Since
+//! nothing in an ID says which layout minted it, a reader must decode with
+//! the header's layout and epoch rather than its own defaults, and must
+//! reject a header it doesn't fully understand: parsing fails on unknown,
+//! repeated or missing keys rather than guessing. The header does not record a
+//! timestamp resolution, so seconds-resolution files aren't described by it.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::layout::{Layout, LayoutError};
+use crate::node::MAX_NODE_ID;
+use crate::settings::Settings;
+
+/// Marker that opens every header line
+const HEADER_MARKER: &str = "#tiny64";
+
+/// How the IDs in a batch file were minted, written as its first line
+///
+/// Displays as the header line, without a trailing newline, and parses back
+/// from it with [`str::parse`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchHeader {
+    /// Version of the crate that wrote the batch
+    pub version: String,
+    /// Epoch and layout the IDs were minted with
+    pub settings: Settings,
+    /// Node ID written in place of random bits, for node-ID generators
+    pub node_id: Option<u16>,
+}
+
+/// Error returned for a line that isn't a valid [`BatchHeader`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchHeaderError {
+    /// The line doesn't start with `#tiny64`
+    Missing,
+    /// The field is not `key=value`, its value doesn't parse, or its key
+    /// already appeared
+    InvalidField(String),
+    /// The key is not one this version understands
+    UnknownKey(String),
+    /// A required key is absent
+    MissingKey(&'static str),
+    /// The bit widths don't form a valid layout
+    Layout(LayoutError),
+}
+
+impl fmt::Display for BatchHeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchHeaderError::Missing => write!(f, "expected a `{}` header", HEADER_MARKER),
+            BatchHeaderError::InvalidField(field) => write!(f, "invalid header field `{}`", field),
+            BatchHeaderError::UnknownKey(key) => write!(f, "unknown header key `{}`", key),
+            BatchHeaderError::MissingKey(key) => write!(f, "header is missing `{}`", key),
+            BatchHeaderError::Layout(err) => write!(f, "invalid layout: {}", err),
+        }
+    }
+}
+
+impl core::error::Error for BatchHeaderError {}
+
+impl BatchHeader {
+    /// A header for IDs minted with `settings` by this version of the crate
+    pub fn new(settings: Settings, node_id: Option<u16>) -> Self {
+        BatchHeader {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            settings,
+            node_id,
+        }
+    }
+}
+
+impl fmt::Display for BatchHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let layout = self.settings.layout;
+        write!(
+            f,
+            "{} version={} epoch_ms={} timestamp_bits={} sequence_bits={} random_bits={}",
+            HEADER_MARKER,
+            self.version,
+            self.settings.epoch_ms,
+            layout.timestamp_bits(),
+            layout.sequence_bits(),
+            layout.random_bits()
+        )?;
+        if let Some(node_id) = self.node_id {
+            write!(f, " node_id={}", node_id)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for BatchHeader {
+    type Err = BatchHeaderError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut fields = line.split_whitespace();
+        if fields.next() != Some(HEADER_MARKER) {
+            return Err(BatchHeaderError::Missing);
+        }
+
+        let mut version = None;
+        let mut epoch_ms = None;
+        let mut bits = [None; 3];
+        let mut node_id = None;
+        let mut seen = Vec::new();
+        for field in fields {
+            let invalid = || BatchHeaderError::InvalidField(field.to_string());
+            let (key, value) = field.split_once('=').ok_or_else(invalid)?;
+            // A repeated key would otherwise silently override the first
+            if seen.contains(&key) {
+                return Err(invalid());
+            }
+            seen.push(key);
+            match key {
+                "version" if !value.is_empty() => version = Some(value.to_string()),
+                "version" => return Err(invalid()),
+                "epoch_ms" => epoch_ms = Some(value.parse().map_err(|_| invalid())?),
+                "timestamp_bits" => bits[0] = Some(value.parse().map_err(|_| invalid())?),
+                "sequence_bits" => bits[1] = Some(value.parse().map_err(|_| invalid())?),
+                "random_bits" => bits[2] = Some(value.parse().map_err(|_| invalid())?),
+                "node_id" => {
+                    let id = value.parse().ok().filter(|&id| id <= MAX_NODE_ID);
+                    node_id = Some(id.ok_or_else(invalid)?);
+                }
+                _ => return Err(BatchHeaderError::UnknownKey(key.to_string())),
+            }
+        }
+
+        let [timestamp_bits, sequence_bits, random_bits] = bits;
+        let required = |value: Option<u32>, key| value.ok_or(BatchHeaderError::MissingKey(key));
+        let layout = Layout::new(
+            required(timestamp_bits, "timestamp_bits")?,
+            required(sequence_bits, "sequence_bits")?,
+            required(random_bits, "random_bits")?,
+        )
+        .map_err(BatchHeaderError::Layout)?;
+        // A node ID wider than the random field can't have minted these IDs
+        if let Some(node_id) = node_id.filter(|&id| id > layout.max_random()) {
+            return Err(BatchHeaderError::InvalidField(format!(
+                "node_id={}",
+                node_id
+            )));
+        }
+        Ok(BatchHeader {
+            version: version.ok_or(BatchHeaderError::MissingKey("version"))?,
+            settings: Settings {
+                epoch_ms: epoch_ms.ok_or(BatchHeaderError::MissingKey("epoch_ms"))?,
+                layout,
+            },
+            node_id,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_round_trip() {
+        let settings = Settings {
+            epoch_ms: 1_700_000_000_000,
+            layout: Layout::new(40, 14, 10).unwrap(),
+        };
+        let header = BatchHeader::new(settings, Some(7));
+        let line = header.to_string();
+        assert_eq!(
+            line,
+            format!(
+                "#tiny64 version={} epoch_ms=1700000000000 timestamp_bits=40 sequence_bits=14 random_bits=10 node_id=7",
+                env!("CARGO_PKG_VERSION")
+            )
+        );
+        assert_eq!(line.parse(), Ok(header));
+
+        let plain = BatchHeader::new(Settings::default(), None);
+        assert!(!plain.to_string().contains("node_id"));
+        assert_eq!(plain.to_string().parse(), Ok(plain));
+    }
+
+    #[test]
+    fn test_header_errors() {
+        let parse = |line: &str| line.parse::<BatchHeader>();
+        let base = "#tiny64 version=0.1.0 epoch_ms=0 timestamp_bits=42 sequence_bits=12";
+        assert_eq!(parse("-----------"), Err(BatchHeaderError::Missing));
+        assert_eq!(
+            parse(base),
+            Err(BatchHeaderError::MissingKey("random_bits"))
+        );
+        assert_eq!(
+            parse(&format!("{} random_bits=10 resolution_ms=1000", base)),
+            Err(BatchHeaderError::UnknownKey("resolution_ms".to_string()))
+        );
+        assert_eq!(
+            parse(&format!("{} random_bits=10 node_id=1024", base)),
+            Err(BatchHeaderError::InvalidField("node_id=1024".to_string()))
+        );
+        assert_eq!(
+            parse(
+                "#tiny64 version=0.1.0 epoch_ms=0 timestamp_bits=46 sequence_bits=10 random_bits=8 node_id=300"
+            ),
+            Err(BatchHeaderError::InvalidField("node_id=300".to_string()))
+        );
+        assert_eq!(
+            parse(&format!("{} random_bits=10 epoch_ms=5", base)),
+            Err(BatchHeaderError::InvalidField("epoch_ms=5".to_string()))
+        );
+        assert_eq!(
+            parse(&format!("{} random_bits=10 random_bits=10", base)),
+            Err(BatchHeaderError::InvalidField("random_bits=10".to_string()))
+        );
+        assert_eq!(
+            parse(&format!("{} random_bits=8", base)),
+            Err(BatchHeaderError::Layout(LayoutError::InvalidTotal(62)))
+        );
+    }
+}
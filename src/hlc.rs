@@ -0,0 +1,187 @@
+//! IDs carrying a hybrid logical clock, for causal order across nodes.
+//!
+//! Layout: `[ 42 bits: physical_ms ] [ 12 bits: logical ] [ 10 bits: random ]`.
+//!
+//! A hybrid logical clock (HLC) pairs the latest physical time a node has
+//! seen, from its own clock or from a message, with a logical counter that
+//! orders events within that millisecond. The clock value handed around is
+//! the top 54 bits of the ID, `physical_ms << 12 | logical`, so the ID's
+//! timestamp and sequence fields are the clock and IDs still sort, encode
+//! and decode like any other. Read a received ID's clock with
+//! `decode_u64(id)? >> 10`.
+
+use alloc::string::String;
+use core::fmt;
+use std::sync::Mutex;
+
+use crate::random::generate_random_10bit;
+use crate::{MAX_TIMESTAMP_MS, RANDOM_BITS, SEQUENCE_BITS};
+
+/// A reasonable bound on how far a peer's clock may run ahead of ours, for
+/// [`generate_hlc`]'s `max_drift_ms`
+pub const DEFAULT_MAX_DRIFT_MS: u64 = 60_000;
+
+/// Largest clock value: the last logical tick of the last millisecond
+const MAX_CLOCK: u64 = (MAX_TIMESTAMP_MS << SEQUENCE_BITS) | ((1 << SEQUENCE_BITS) - 1);
+
+/// This process's clock, shared by every thread
+static CLOCK: Mutex<u64> = Mutex::new(0);
+
+/// Error returned by [`generate_hlc`] for a received clock too far ahead of
+/// the wall clock
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockDriftError {
+    /// Physical part of the received clock
+    pub received_ms: u64,
+    /// The wall clock when it was refused
+    pub now_ms: u64,
+    /// The drift that was allowed
+    pub max_drift_ms: u64,
+}
+
+impl fmt::Display for ClockDriftError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "received clock is {} ms ahead of the wall clock, at most {} allowed",
+            self.received_ms.saturating_sub(self.now_ms),
+            self.max_drift_ms
+        )
+    }
+}
+
+impl core::error::Error for ClockDriftError {}
+
+/// Generate an ID from this process's hybrid logical clock after merging in
+/// `received_clock`, and return it with the updated clock
+///
+/// Pass the clock of the message being handled, or 0 for a local event, and
+/// send the returned clock along with whatever the ID labels. The merge is the
+/// standard HLC rule: the physical part becomes the largest of the local
+/// physical part, the received one and the wall clock; the logical counter
+/// restarts at 0 if the wall clock alone is largest, and otherwise becomes one
+/// more than the largest counter among the clocks sharing that physical part.
+/// With the two parts packed into one integer that is simply
+/// `max(local + 1, received + 1, now_ms << 12)`, so a counter past 4095
+/// carries into the physical part instead of waiting for the wall clock.
+///
+/// The result exceeds both clocks merged in, so an ID minted after receiving
+/// a message sorts after the ID that labelled it, however skewed the senders'
+/// wall clocks are, within `max_drift_ms`. The clock is shared by the whole
+/// process and never goes back, so one wildly wrong clock merged in would
+/// drag every later ID with it; as HLC prescribes, a received clock whose
+/// physical part is more than `max_drift_ms` ahead of the wall clock is
+/// refused with a [`ClockDriftError`] and the local clock is left as it was.
+/// [`DEFAULT_MAX_DRIFT_MS`] suits most deployments. Clocks past the end of the
+/// timestamp field in 2109 are clamped to it.
+pub fn generate_hlc(
+    received_clock: u64,
+    max_drift_ms: u64,
+) -> Result<(String, u64), ClockDriftError> {
+    let now_ms = crate::generator::current_time_ms();
+    check_drift(received_clock, now_ms, max_drift_ms)?;
+
+    let mut local = CLOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    *local = merge(*local, received_clock, now_ms);
+    let clock = *local;
+    drop(local);
+
+    let value = clock << RANDOM_BITS | generate_random_10bit() as u64;
+    Ok((crate::base64_encode_u64(value), clock))
+}
+
+/// Refuse a received clock more than `max_drift_ms` ahead of `now_ms`
+fn check_drift(received_clock: u64, now_ms: u64, max_drift_ms: u64) -> Result<(), ClockDriftError> {
+    let received_ms = received_clock >> SEQUENCE_BITS;
+    if received_ms > now_ms.saturating_add(max_drift_ms) {
+        return Err(ClockDriftError {
+            received_ms,
+            now_ms,
+            max_drift_ms,
+        });
+    }
+    Ok(())
+}
+
+/// The HLC send-or-receive rule on packed clocks
+fn merge(local: u64, received: u64, now_ms: u64) -> u64 {
+    let physical = now_ms.min(MAX_TIMESTAMP_MS) << SEQUENCE_BITS;
+    (local.min(MAX_CLOCK) + 1)
+        .max(received.min(MAX_CLOCK) + 1)
+        .max(physical)
+        .min(MAX_CLOCK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clock(physical_ms: u64, logical: u64) -> u64 {
+        physical_ms << SEQUENCE_BITS | logical
+    }
+
+    #[test]
+    fn test_merge_rule() {
+        // Wall clock ahead of both: counter restarts
+        assert_eq!(merge(clock(10, 3), clock(9, 8), 12), clock(12, 0));
+        // Local ahead: its counter advances
+        assert_eq!(merge(clock(12, 3), clock(9, 8), 11), clock(12, 4));
+        // Received ahead: its counter advances
+        assert_eq!(merge(clock(10, 3), clock(15, 8), 11), clock(15, 9));
+        // Same physical part: one past the larger counter
+        assert_eq!(merge(clock(15, 3), clock(15, 8), 11), clock(15, 9));
+        assert_eq!(merge(clock(15, 8), clock(15, 3), 15), clock(15, 9));
+        // A full counter carries into the physical part
+        assert_eq!(merge(clock(15, 4095), 0, 15), clock(16, 0));
+        assert_eq!(merge(u64::MAX, 0, u64::MAX), MAX_CLOCK);
+    }
+
+    #[test]
+    fn test_check_drift() {
+        assert_eq!(check_drift(clock(1_500, 9), 1_000, 500), Ok(()));
+        assert_eq!(check_drift(0, 1_000, 0), Ok(()));
+        let err = check_drift(clock(1_501, 0), 1_000, 500).unwrap_err();
+        assert_eq!(
+            err,
+            ClockDriftError {
+                received_ms: 1_501,
+                now_ms: 1_000,
+                max_drift_ms: 500
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "received clock is 501 ms ahead of the wall clock, at most 500 allowed"
+        );
+        assert!(check_drift(u64::MAX, 1_000, u64::MAX).is_ok());
+    }
+
+    #[test]
+    fn test_generate_hlc_orders_after_received() {
+        let ahead = clock(crate::generator::current_time_ms() + 10_000, 7);
+        let (id, updated) = generate_hlc(ahead, DEFAULT_MAX_DRIFT_MS).unwrap();
+        assert!(updated > ahead);
+        assert_eq!(crate::decode_u64(&id).unwrap() >> RANDOM_BITS, updated);
+        let parts = crate::decode_tiny64(&id).unwrap();
+        assert_eq!(parts.timestamp_ms, ahead >> SEQUENCE_BITS);
+        assert!(parts.sequence > 7);
+
+        let mut previous = (id, updated);
+        for _ in 0..100 {
+            let next = generate_hlc(0, DEFAULT_MAX_DRIFT_MS).unwrap();
+            assert!(next.1 > previous.1);
+            assert!(next.0 > previous.0);
+            previous = next;
+        }
+
+        // A far-future clock is refused and doesn't move the local clock
+        assert!(generate_hlc(u64::MAX, DEFAULT_MAX_DRIFT_MS).is_err());
+        let far = clock(crate::generator::current_time_ms() + 3_600_000, 0);
+        assert!(generate_hlc(far, DEFAULT_MAX_DRIFT_MS).is_err());
+        let (_, after) = generate_hlc(0, DEFAULT_MAX_DRIFT_MS).unwrap();
+        assert!(after > previous.1);
+        assert!(after < far);
+    }
+}
@@ -0,0 +1,167 @@
+//! A typed Tiny64 ID.
+
+#[cfg(feature = "std")]
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::decode::{ParseError, decode_u64};
+use crate::encode::base64_encode_u64_const;
+use crate::parts::Tiny64Parts;
+
+/// A Tiny64 ID held as its packed u64 value
+///
+/// Parses from and displays as the 11-character string form, and orders the
+/// same way the strings do:
+///
+/// ```
+/// use tiny64::Tiny64Id;
+///
+/// fn parse_ids(input: &str) -> Result<Vec<Tiny64Id>, Box<dyn std::error::Error>> {
+///     let mut ids = Vec::new();
+///     for line in input.lines() {
+///         let id: Tiny64Id = line.parse()?;
+///         ids.push(id);
+///     }
+///     Ok(ids)
+/// }
+///
+/// assert_eq!(parse_ids("-----------").unwrap(), [Tiny64Id::from_u64(0)]);
+/// let err = parse_ids("Obrl8O3-+Cw").unwrap_err();
+/// assert_eq!(err.to_string(), "invalid character '+' at position 8");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tiny64Id(u64);
+
+/// The string form of [`Tiny64Id::nil`]
+const NIL_STR: &str = "-----------";
+
+impl Tiny64Id {
+    /// The all-zero ID, `-----------`
+    ///
+    /// A well-known placeholder for "no ID", like UUID's nil: it sorts before
+    /// every generated ID, and its timestamp is the Unix epoch itself, so it
+    /// can't be mistaken for one. Use it where a column or field needs an ID
+    /// before a real one exists.
+    pub const fn nil() -> Self {
+        Tiny64Id(0)
+    }
+
+    /// Whether this is [`Tiny64Id::nil`]
+    pub const fn is_nil(self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn from_u64(value: u64) -> Self {
+        Tiny64Id(value)
+    }
+
+    pub const fn as_u64(self) -> u64 {
+        self.0
+    }
+
+    /// Mint a fresh ID, as [`generate_tiny64_u64`](crate::generate_tiny64_u64)
+    /// does, in its typed form
+    #[cfg(feature = "std")]
+    pub fn now() -> Self {
+        Tiny64Id(crate::generate_tiny64_u64())
+    }
+
+    /// Mint a fresh ID in its string form; the same as
+    /// `Tiny64Id::now().to_string()`
+    #[cfg(feature = "std")]
+    pub fn now_string() -> String {
+        crate::encode::base64_encode_u64(crate::generate_tiny64_u64())
+    }
+
+    /// The decoded timestamp, sequence and random fields
+    pub fn parts(self) -> Tiny64Parts {
+        Tiny64Parts::from_u64(self.0)
+    }
+}
+
+/// Whether `id` is the nil ID `-----------`
+///
+/// Compares the string exactly, so it needs no decoding and anything other
+/// than the nil ID, valid or not, is simply not nil.
+pub fn is_nil(id: &str) -> bool {
+    id == NIL_STR
+}
+
+impl FromStr for Tiny64Id {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        decode_u64(s).map(Tiny64Id)
+    }
+}
+
+impl fmt::Display for Tiny64Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bytes = base64_encode_u64_const(self.0);
+        // The alphabet is pure ASCII
+        f.pad(core::str::from_utf8(&bytes).unwrap())
+    }
+}
+
+impl From<u64> for Tiny64Id {
+    fn from(value: u64) -> Self {
+        Tiny64Id(value)
+    }
+}
+
+impl From<Tiny64Id> for u64 {
+    fn from(id: Tiny64Id) -> Self {
+        id.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::generate_pair;
+    use alloc::string::ToString;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_parse_and_display() {
+        let (value, encoded) = generate_pair();
+        let id: Tiny64Id = encoded.parse().unwrap();
+        assert_eq!(id.as_u64(), value);
+        assert_eq!(id.to_string(), encoded);
+        assert_eq!(id.parts(), Tiny64Parts::from_u64(value));
+
+        assert_eq!("abc".parse::<Tiny64Id>(), Err(ParseError::InvalidLength(3)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_now() {
+        let a = Tiny64Id::now();
+        let b: Tiny64Id = Tiny64Id::now_string().parse().unwrap();
+        let c = Tiny64Id::now();
+        assert!(a < b && b < c);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_nil() {
+        let nil = Tiny64Id::nil();
+        assert!(nil.is_nil());
+        assert_eq!(nil.to_string(), NIL_STR);
+        assert!(is_nil(&nil.to_string()));
+        assert!(nil < Tiny64Id::now());
+        assert!(!Tiny64Id::now().is_nil());
+        assert!(!is_nil("----------0"));
+        assert!(!is_nil("bad"));
+    }
+
+    #[test]
+    fn test_ordering_matches_strings() {
+        let a = Tiny64Id::from_u64(0x123);
+        let b = Tiny64Id::from_u64(0x124);
+        assert!(a < b);
+        assert!(a.to_string() < b.to_string());
+    }
+}
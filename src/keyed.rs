@@ -0,0 +1,156 @@
+//! IDs derived from a content key or counter rather than (only) the clock.
+//!
+//! The key-based functions hash the key with a stable hash, so the same key
+//! maps to the same bits in every process and on every platform.
+//! [`from_legacy_id`] carries an existing integer key over into an ID.
+
+use alloc::string::String;
+
+use crate::encode::base64_encode_u64;
+#[cfg(feature = "std")]
+use crate::error::Tiny64Error;
+use crate::hash::{fnv1a_64, mix64};
+use crate::parts::PackError;
+use crate::{MAX_TIMESTAMP_MS, RANDOM_BITS, SEQUENCE_BITS};
+
+fn key_hash(key: &[u8]) -> u64 {
+    mix64(fnv1a_64(key))
+}
+
+/// Generate an ID whose sequence and random bits come from hashing `key`
+///
+/// The timestamp is still the current time, so IDs stay time-sortable, but the
+/// same key only yields the same ID when minted within the same millisecond.
+#[cfg(feature = "std")]
+pub fn generate_from_key(key: &[u8]) -> String {
+    let low_bits = SEQUENCE_BITS + RANDOM_BITS;
+    let timestamp_ms = crate::generator::current_time_ms() & MAX_TIMESTAMP_MS;
+    let value = (timestamp_ms << low_bits) | (key_hash(key) & ((1 << low_bits) - 1));
+    base64_encode_u64(value)
+}
+
+/// Generate an ID where all 64 bits come from hashing `key`
+///
+/// The same key always yields the same ID, which makes it usable as an
+/// idempotency key. The result carries no timestamp: it does not sort by
+/// creation time and decoding it yields meaningless fields.
+pub fn generate_deterministic(key: &[u8]) -> String {
+    base64_encode_u64(key_hash(key))
+}
+
+/// Generate an ID ordered by an application counter instead of the clock
+///
+/// `counter` fills the 54 timestamp and sequence bits, and the low 10 bits
+/// are random, so IDs from increasing counters are strictly increasing. The
+/// ID carries no timestamp and decodes to meaningless fields. Calling it twice
+/// with the same counter yields IDs that differ only in their random bits and
+/// collide with probability 1/1024. Fails if `counter` needs more than 54 bits.
+#[cfg(feature = "std")]
+pub fn generate_from_counter(counter: u64) -> Result<String, Tiny64Error> {
+    if counter >> (64 - RANDOM_BITS) != 0 {
+        return Err(Tiny64Error::CounterOutOfRange(counter));
+    }
+    let random = crate::random::generate_random_10bit() as u64;
+    Ok(base64_encode_u64(counter << RANDOM_BITS | random))
+}
+
+/// Convert a legacy auto-increment integer key into an ID
+///
+/// For migrating rows keyed by `BIGINT AUTO_INCREMENT`: `minted_at_ms`, such
+/// as the time of the migration, becomes the timestamp and `legacy_id` fills
+/// the 22 sequence and random bits. Rows converted with the same
+/// `minted_at_ms` keep their relative order, and all sort before IDs
+/// generated after that time. Legacy integers above `2^22 - 1` (4,194,303)
+/// don't fit and fail with [`PackError::LegacyIdOutOfRange`]. Larger tables
+/// still keep their order if converted in chunks of `2^22` keys, passing
+/// `legacy_id % 2^22` with a later `minted_at_ms` for each successive chunk.
+/// Fails too if `minted_at_ms` exceeds the 42-bit timestamp range.
+pub fn from_legacy_id(legacy_id: u64, minted_at_ms: u64) -> Result<String, PackError> {
+    let low_bits = SEQUENCE_BITS + RANDOM_BITS;
+    if legacy_id >> low_bits != 0 {
+        return Err(PackError::LegacyIdOutOfRange(legacy_id));
+    }
+    if minted_at_ms > MAX_TIMESTAMP_MS {
+        return Err(PackError::TimestampOutOfRange {
+            value: minted_at_ms,
+            max: MAX_TIMESTAMP_MS,
+        });
+    }
+    Ok(base64_encode_u64(minted_at_ms << low_bits | legacy_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::decode_u64;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_deterministic_is_stable() {
+        assert_eq!(
+            generate_deterministic(b"order-42"),
+            generate_deterministic(b"order-42")
+        );
+        assert_ne!(
+            generate_deterministic(b"order-42"),
+            generate_deterministic(b"order-43")
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_from_key_shares_low_bits() {
+        let mask = (1 << (SEQUENCE_BITS + RANDOM_BITS)) - 1;
+        let a = decode_u64(&generate_from_key(b"payload")).unwrap();
+        let b = decode_u64(&generate_from_key(b"payload")).unwrap();
+        assert_eq!(a & mask, b & mask);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_generate_from_counter() {
+        let ids: Vec<String> = (0..100)
+            .map(|i| generate_from_counter(i).unwrap())
+            .collect();
+        for pair in ids.windows(2) {
+            assert!(pair[0] < pair[1]);
+        }
+        assert_eq!(decode_u64(&ids[5]).unwrap() >> RANDOM_BITS, 5);
+
+        let max = (1 << (64 - RANDOM_BITS)) - 1;
+        assert!(generate_from_counter(max).is_ok());
+        assert_eq!(
+            generate_from_counter(max + 1),
+            Err(Tiny64Error::CounterOutOfRange(max + 1))
+        );
+    }
+
+    #[test]
+    fn test_from_legacy_id() {
+        let migrated_at = 1_700_000_000_000;
+        let ids: Vec<String> = [1, 2, 10, 4_194_303]
+            .iter()
+            .map(|&n| from_legacy_id(n, migrated_at).unwrap())
+            .collect();
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+        let parts = crate::decode_tiny64(&ids[2]).unwrap();
+        assert_eq!(parts.timestamp_ms, migrated_at);
+        assert_eq!(
+            (parts.sequence as u64) << RANDOM_BITS | parts.random as u64,
+            10
+        );
+
+        assert_eq!(
+            from_legacy_id(1 << 22, migrated_at),
+            Err(PackError::LegacyIdOutOfRange(1 << 22))
+        );
+        assert_eq!(
+            from_legacy_id(1, 1 << 42),
+            Err(PackError::TimestampOutOfRange {
+                value: 1 << 42,
+                max: MAX_TIMESTAMP_MS
+            })
+        );
+    }
+}
@@ -0,0 +1,302 @@
+use core::fmt;
+
+use crate::decode::{ParseError, decode_u64};
+use crate::parts::{PackError, Tiny64Parts};
+use crate::{RANDOM_BITS, SEQUENCE_BITS, TIMESTAMP_BITS};
+
+/// Bit widths of the timestamp, sequence and random fields
+///
+/// The default is the Tiny64 v1 split of 42/12/10. Other splits trade
+/// per-millisecond throughput (sequence bits) against collision resistance
+/// (random bits) and time range (timestamp bits). IDs minted with a
+/// non-default layout still encode to 11 characters and sort by time, but they
+/// only decode correctly with the same layout.
+///
+/// Timestamps count milliseconds, except in [`Layout::SECONDS`], whose
+/// timestamp field counts seconds; see [`to_common_time`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    timestamp_bits: u32,
+    sequence_bits: u32,
+    random_bits: u32,
+    /// Milliseconds per tick of the timestamp field
+    resolution_ms: u64,
+}
+
+/// Error returned for bit widths that don't form a valid layout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutError {
+    /// The three widths don't add up to 64
+    InvalidTotal(u32),
+    /// The sequence or random field is wider than 16 bits
+    FieldTooWide { field: &'static str, bits: u32 },
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutError::InvalidTotal(total) => {
+                write!(f, "field widths sum to {} bits, expected 64", total)
+            }
+            LayoutError::FieldTooWide { field, bits } => {
+                write!(
+                    f,
+                    "{} field is {} bits wide, at most 16 allowed",
+                    field, bits
+                )
+            }
+        }
+    }
+}
+
+impl core::error::Error for LayoutError {}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Layout::DEFAULT
+    }
+}
+
+impl Layout {
+    /// The Tiny64 v1 layout: 42 timestamp, 12 sequence, 10 random bits
+    pub const DEFAULT: Layout = Layout {
+        timestamp_bits: TIMESTAMP_BITS,
+        sequence_bits: SEQUENCE_BITS,
+        random_bits: RANDOM_BITS,
+        resolution_ms: 1,
+    };
+
+    /// The layout of
+    /// [`generate_seconds_resolution`](crate::generate_seconds_resolution): 32
+    /// timestamp bits counting seconds, 16 sequence and 16 random bits
+    ///
+    /// Meant for decoding: a generator built with it still writes
+    /// milliseconds into the timestamp field.
+    pub const SECONDS: Layout = Layout {
+        timestamp_bits: 32,
+        sequence_bits: 16,
+        random_bits: 16,
+        resolution_ms: 1000,
+    };
+
+    /// Create a layout, checking that the widths sum to 64 and that the
+    /// sequence and random fields fit in 16 bits each
+    pub fn new(
+        timestamp_bits: u32,
+        sequence_bits: u32,
+        random_bits: u32,
+    ) -> Result<Layout, LayoutError> {
+        let total = timestamp_bits
+            .saturating_add(sequence_bits)
+            .saturating_add(random_bits);
+        if total != 64 {
+            return Err(LayoutError::InvalidTotal(total));
+        }
+        if sequence_bits > 16 {
+            return Err(LayoutError::FieldTooWide {
+                field: "sequence",
+                bits: sequence_bits,
+            });
+        }
+        if random_bits > 16 {
+            return Err(LayoutError::FieldTooWide {
+                field: "random",
+                bits: random_bits,
+            });
+        }
+
+        Ok(Layout {
+            timestamp_bits,
+            sequence_bits,
+            random_bits,
+            resolution_ms: 1,
+        })
+    }
+
+    pub fn timestamp_bits(&self) -> u32 {
+        self.timestamp_bits
+    }
+
+    pub fn sequence_bits(&self) -> u32 {
+        self.sequence_bits
+    }
+
+    pub fn random_bits(&self) -> u32 {
+        self.random_bits
+    }
+
+    /// Milliseconds per tick of the timestamp field: 1000 for
+    /// [`Layout::SECONDS`], 1 otherwise
+    pub fn resolution_ms(&self) -> u64 {
+        self.resolution_ms
+    }
+
+    /// Largest timestamp the layout can represent, in ticks of
+    /// [`resolution_ms`](Layout::resolution_ms)
+    pub fn max_timestamp_ms(&self) -> u64 {
+        mask(self.timestamp_bits)
+    }
+
+    pub fn max_sequence(&self) -> u16 {
+        mask(self.sequence_bits) as u16
+    }
+
+    pub fn max_random(&self) -> u16 {
+        mask(self.random_bits) as u16
+    }
+
+    /// Number of IDs a generator can mint per millisecond
+    pub fn ids_per_ms(&self) -> usize {
+        1 << self.sequence_bits
+    }
+
+    /// Pack fields into a u64, validating each against this layout's widths
+    pub fn pack(&self, parts: &Tiny64Parts) -> Result<u64, PackError> {
+        if parts.timestamp_ms > self.max_timestamp_ms() {
+            return Err(PackError::TimestampOutOfRange {
+                value: parts.timestamp_ms,
+                max: self.max_timestamp_ms(),
+            });
+        }
+        if parts.sequence > self.max_sequence() {
+            return Err(PackError::SequenceOutOfRange {
+                value: parts.sequence,
+                max: self.max_sequence(),
+            });
+        }
+        if parts.random > self.max_random() {
+            return Err(PackError::RandomOutOfRange {
+                value: parts.random,
+                max: self.max_random(),
+            });
+        }
+
+        Ok(self.pack_masked(parts.timestamp_ms, parts.sequence, parts.random))
+    }
+
+    /// Pack fields into a u64, truncating each to this layout's widths
+    pub(crate) fn pack_masked(&self, timestamp_ms: u64, sequence: u16, random: u16) -> u64 {
+        let low_bits = self.sequence_bits + self.random_bits;
+        ((timestamp_ms & self.max_timestamp_ms()) << low_bits)
+            | ((sequence & self.max_sequence()) as u64) << self.random_bits
+            | (random & self.max_random()) as u64
+    }
+
+    /// Split a packed u64 into its fields according to this layout
+    pub fn unpack(&self, value: u64) -> Tiny64Parts {
+        Tiny64Parts {
+            timestamp_ms: value
+                .checked_shr(self.sequence_bits + self.random_bits)
+                .unwrap_or(0),
+            sequence: ((value >> self.random_bits) & mask(self.sequence_bits)) as u16,
+            random: (value & mask(self.random_bits)) as u16,
+        }
+    }
+}
+
+/// The time an ID minted with `layout` was minted, in milliseconds since its
+/// epoch, whatever the layout's resolution
+///
+/// Normalising to milliseconds lets IDs of different resolutions be merged
+/// and compared by time. A seconds-resolution ID only knows its second, which
+/// comes back as the first millisecond of that second: such an ID compares
+/// as older than a millisecond ID minted later in the same second, and when
+/// the two are close, order between them is only accurate to a second.
+pub fn to_common_time(id: &str, layout: Layout) -> Result<u64, ParseError> {
+    let ticks = layout.unpack(decode_u64(id)?).timestamp_ms;
+    Ok(ticks * layout.resolution_ms)
+}
+
+/// All-ones mask of the given width (0..=64 bits)
+fn mask(bits: u32) -> u64 {
+    u64::MAX.checked_shr(64 - bits).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_layout_validation() {
+        assert_eq!(Layout::new(42, 12, 10), Ok(Layout::DEFAULT));
+        assert_eq!(Layout::new(42, 6, 10), Err(LayoutError::InvalidTotal(58)));
+        assert_eq!(
+            Layout::new(30, 17, 17),
+            Err(LayoutError::FieldTooWide {
+                field: "sequence",
+                bits: 17
+            })
+        );
+        // Widths too large to add up still fail cleanly
+        assert_eq!(
+            Layout::new(u32::MAX, 1, 0),
+            Err(LayoutError::InvalidTotal(u32::MAX))
+        );
+    }
+
+    #[test]
+    fn test_custom_layout_roundtrip() {
+        let layout = Layout::new(42, 6, 16).unwrap();
+        let parts = Tiny64Parts {
+            timestamp_ms: 1_705_314_225_123,
+            sequence: 63,
+            random: 0xFFFF,
+        };
+        let value = layout.pack(&parts).unwrap();
+        assert_eq!(layout.unpack(value), parts);
+        assert_eq!(
+            layout.pack(&Tiny64Parts {
+                sequence: 64,
+                ..parts
+            }),
+            Err(PackError::SequenceOutOfRange { value: 64, max: 63 })
+        );
+        assert_eq!(
+            layout
+                .pack(&Tiny64Parts {
+                    sequence: 64,
+                    ..parts
+                })
+                .unwrap_err()
+                .to_string(),
+            "sequence 64 exceeds maximum 63"
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_to_common_time() {
+        let before = crate::generator::current_time_ms();
+        let seconds_id = crate::generate_seconds_resolution();
+        let id = crate::generate_tiny64();
+        let after = crate::generator::current_time_ms();
+
+        let seconds_ms = to_common_time(&seconds_id, Layout::SECONDS).unwrap();
+        assert_eq!(seconds_ms % 1000, 0);
+        assert!(before / 1000 * 1000 <= seconds_ms && seconds_ms <= after);
+        assert_eq!(
+            seconds_ms / 1000,
+            crate::timestamp_secs_of(&seconds_id).unwrap()
+        );
+        assert_eq!(
+            to_common_time(&id, Layout::DEFAULT),
+            crate::timestamp_of(&id)
+        );
+        assert_eq!(
+            Layout::SECONDS.sequence_bits(),
+            crate::SECONDS_SEQUENCE_BITS
+        );
+        assert_eq!(Layout::SECONDS.random_bits(), crate::SECONDS_RANDOM_BITS);
+        assert_eq!(
+            to_common_time("x", Layout::SECONDS),
+            Err(ParseError::InvalidLength(1))
+        );
+    }
+
+    #[test]
+    fn test_default_layout_matches_v1() {
+        let value = 0x123456789ABCDEF0;
+        assert_eq!(Layout::DEFAULT.unpack(value), Tiny64Parts::from_u64(value));
+    }
+}
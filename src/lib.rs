@@ -0,0 +1,230 @@
+//! Tiny64 – Time-Ordered Compact Unique IDs
+//!
+//! A Tiny64 ID is a 64-bit value laid out as
+//! `[ 42 bits: timestamp_ms ] [ 12 bits: sequence ] [ 10 bits: random ]`
+//! and encoded as an 11-character, lexically sortable Base64 string.
+//!
+//! The `std` feature (on by default) provides the clock-reading generators.
+//! Without it the crate is `no_std` and only needs `alloc`: encoding, decoding
+//! and packing work unchanged, and IDs are minted with a [`ClockGenerator`]
+//! fed by a caller-supplied [`Clock`] and [`RandomSource`].
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+mod audit;
+#[cfg(feature = "std")]
+mod avoid;
+mod batch;
+mod clock;
+mod datetime;
+mod decode;
+mod descending;
+mod diff;
+mod encode;
+mod encoding;
+mod error;
+mod flags;
+#[cfg(feature = "std")]
+mod generator;
+mod hash;
+mod header;
+#[cfg(feature = "std")]
+mod hlc;
+mod id;
+mod keyed;
+mod layout;
+#[cfg(feature = "prometheus")]
+mod metrics;
+mod mnemonic;
+mod node;
+mod ordering;
+mod pagination;
+mod parts;
+mod priority;
+mod radix;
+mod random;
+mod range;
+#[cfg(feature = "std")]
+mod seconds;
+mod settings;
+mod shard;
+#[cfg(feature = "std")]
+mod shared;
+#[cfg(feature = "std")]
+mod short;
+mod simulate;
+mod size;
+mod snowflake;
+#[cfg(feature = "std")]
+mod stream;
+#[cfg(feature = "std")]
+mod synthetic;
+mod tombstone;
+#[cfg(feature = "std")]
+mod unique;
+mod version;
+mod wire;
+
+#[cfg(feature = "std")]
+pub use audit::generate_with_context;
+#[cfg(feature = "std")]
+pub use avoid::{AVOIDING_ATTEMPTS, generate_avoiding};
+pub use batch::{BatchStats, analyze, find_duplicates, ids_to_string};
+#[cfg(feature = "std")]
+pub use batch::{
+    ReadBatchError, fill_column, read_batch_with_header, write_batch_with_header, write_delimited,
+    write_json_array,
+};
+#[cfg(feature = "std")]
+pub use clock::SystemClock;
+pub use clock::{Clock, ClockGenerator, GeneratorStats, SteppingClock};
+#[cfg(feature = "time")]
+pub use datetime::timestamp_of_datetime;
+pub use datetime::{
+    DatePrefixError, Granularity, format_iso8601, format_local, partition_key, strip_date_prefix,
+};
+#[cfg(feature = "std")]
+pub use datetime::{generate_with_date_prefix, system_time_of};
+pub use decode::{
+    ParseError, debug_fields, decode_pair, decode_tiny64, decode_u64, intra_ms_key, is_canonical,
+    timestamp_of,
+};
+pub use descending::decode_descending;
+#[cfg(feature = "std")]
+pub use descending::{generate_batch_descending, generate_both, generate_tiny64_descending};
+pub use diff::{BitDiff, bit_diff};
+pub use encode::{
+    SORT_COLLATION, base64_encode_u48, base64_encode_u64, base64_encode_u64_const,
+    encode_into_bytes, encode_pair, encode_unchecked,
+};
+pub use encoding::{Encoding, decode_tiny64_with, is_valid_tiny64, is_valid_with};
+pub use error::Tiny64Error;
+#[cfg(feature = "std")]
+pub use flags::generate_with_flags;
+pub use flags::{FlagsError, MAX_FLAG_BITS, flags_of};
+#[cfg(feature = "std")]
+pub use generator::{
+    DEFAULT_SPIN_THRESHOLD, GeneratorState, InvalidGeneratorState, OverflowPolicy, Tiny64Generator,
+    Tiny64GeneratorBuilder, Tiny64Iter, align_to_next_ms, generate_distinct_timestamps,
+    generate_pair, generate_saturating, generate_timed, generate_tiny64, generate_tiny64_bounded,
+    generate_tiny64_u64, max_representable_date, next_window_start_ms, set_spin_threshold,
+    spin_threshold, time_until_overflow,
+};
+pub use hash::id_hash;
+pub use header::{BatchHeader, BatchHeaderError};
+#[cfg(feature = "std")]
+pub use hlc::{ClockDriftError, DEFAULT_MAX_DRIFT_MS, generate_hlc};
+pub use id::{Tiny64Id, is_nil};
+pub use keyed::{from_legacy_id, generate_deterministic};
+#[cfg(feature = "std")]
+pub use keyed::{generate_from_counter, generate_from_key};
+pub use layout::{Layout, LayoutError, to_common_time};
+#[cfg(feature = "prometheus")]
+pub use metrics::metrics_text;
+pub use mnemonic::{MNEMONIC_WORDS, MnemonicError, from_mnemonic, to_mnemonic};
+pub use node::{MAX_NODE_ID, is_from_node, node_of};
+#[cfg(feature = "std")]
+pub use node::{auto_node_id, generate_auto_node};
+#[cfg(feature = "std")]
+pub use ordering::dedup_preserving_order;
+pub use ordering::{
+    MergeSorted, OrderViolation, cmp_str_to_u64, insertion_index, merge_sorted, resequence,
+    total_cmp, verify_ordering, verify_strictly_increasing,
+};
+pub use pagination::{Page, Paginator};
+pub use parts::{PackError, Tiny64Parts};
+pub use priority::decode_priority;
+#[cfg(feature = "std")]
+pub use priority::generate_with_priority;
+pub use radix::{
+    BASE62_ALPHABET, BASE62_ENCODED_LEN, CROCKFORD_ALPHABET, DNS_ALPHABET, DNS_ENCODED_LEN,
+    HEX_ALPHABET, QR_ALPHABET, QR_ENCODED_LEN, UNAMBIGUOUS_ALPHABET, UNAMBIGUOUS_ENCODED_LEN,
+    UPPERCASE_ALPHABET, UPPERCASE_ENCODED_LEN, decode_base62, decode_crockford, decode_decimal,
+    decode_dns_safe, decode_hex, decode_hex_le, decode_qr, decode_unambiguous,
+    decode_uppercase_safe, encode_base62, encode_crockford, encode_decimal, encode_dns_safe,
+    encode_hex, encode_hex_le, encode_qr, encode_unambiguous, encode_uppercase_safe,
+};
+#[cfg(feature = "std")]
+pub use radix::{
+    generate_base62, generate_dns_safe, generate_filesystem_safe, generate_lowercase_sortable,
+    generate_unambiguous, generate_uppercase_safe,
+};
+#[cfg(feature = "std")]
+pub use random::HashRandom;
+#[cfg(feature = "getrandom")]
+pub use random::OsRandom;
+pub use random::{FixedRandom, RandomSource, SaltedRandom, SeededRandom};
+#[cfg(feature = "std")]
+pub use range::{bookmark_now, next_after, time_bucket_prefix, today_range};
+pub use range::{
+    count_in_range, estimated_rate, ids_in_ms, max_id_at, min_id_at, predecessor, successor,
+};
+#[cfg(feature = "std")]
+pub use seconds::{
+    SECONDS_RANDOM_BITS, SECONDS_SEQUENCE_BITS, SECONDS_TIMESTAMP_BITS,
+    generate_seconds_resolution, timestamp_secs_of,
+};
+pub use settings::{Settings, SettingsError};
+pub use shard::shard_of;
+#[cfg(feature = "std")]
+pub use shard::{generate_hash_prefixed, interleave};
+#[cfg(feature = "std")]
+pub use shared::{BlockReservation, Exhausted, SharedGenerator, shared_generator};
+#[cfg(feature = "std")]
+pub use short::{
+    SHORT_CODE_ATTEMPTS, SHORT_CODE_LEN, SHORT_ENCODED_LEN, SHORT_RANDOM_BITS, SHORT_SEQUENCE_BITS,
+    SHORT_TIMESTAMP_BITS, generate_short_code, generate_short_code_unique, generate_short_id,
+};
+#[cfg(feature = "std")]
+pub use simulate::measure_collisions;
+pub use simulate::{CollisionStats, MAX_SIMULATED_IDS, simulate};
+pub use size::{SizeComparison, byte_size, char_size, size_comparison};
+#[cfg(feature = "std")]
+pub use snowflake::generate_snowflake;
+pub use snowflake::{MAX_MACHINE_ID, SNOWFLAKE_EPOCH_MS, SnowflakeParts, decode_snowflake};
+#[cfg(feature = "std")]
+pub use stream::{ReadIdsError, read_ids};
+#[cfg(feature = "std")]
+pub use synthetic::{
+    generate_at, generate_at_clamped, generate_at_time, generate_n_at, generate_ordered_stream,
+    generate_spaced, generate_with_time_override, reroll_random,
+};
+#[cfg(feature = "std")]
+pub use tombstone::generate_live;
+pub use tombstone::{is_tombstone, to_tombstone};
+#[cfg(feature = "std")]
+pub use unique::generate_unique_random;
+#[cfg(feature = "std")]
+pub use version::generate_versioned;
+pub use version::{
+    FORMAT_VERSION, MixedLayoutError, VERSION_BITS, detect_layout, validate_homogeneous, version_of,
+};
+pub use wire::{
+    DeltaError, decode_deltas, encode_deltas, from_16_bytes, from_network_bytes, to_16_bytes,
+    to_network_bytes,
+};
+#[cfg(feature = "std")]
+pub use wire::{read_batch_binary, read_batch_parts, write_batch_binary};
+
+/// Number of bits used by the timestamp field
+pub const TIMESTAMP_BITS: u32 = 42;
+/// Number of bits used by the sequence field
+pub const SEQUENCE_BITS: u32 = 12;
+/// Number of bits used by the random field
+pub const RANDOM_BITS: u32 = 10;
+
+/// Largest timestamp (ms since Unix epoch) that fits in the timestamp field
+pub const MAX_TIMESTAMP_MS: u64 = (1 << TIMESTAMP_BITS) - 1;
+/// Largest value of the sequence field
+pub const MAX_SEQUENCE: u16 = (1 << SEQUENCE_BITS) - 1;
+/// Largest value of the random field
+pub const MAX_RANDOM: u16 = (1 << RANDOM_BITS) - 1;
+
+/// Number of distinct sequence values, i.e. IDs per millisecond per generator
+pub const MAX_IDS_PER_MS: usize = 1 << SEQUENCE_BITS;
+
+/// Length of an encoded Tiny64 ID in characters
+pub const ENCODED_LEN: usize = 11;
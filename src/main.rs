@@ -1,129 +1,17 @@
-use std::cell::Cell;
-use std::collections::hash_map::RandomState;
-use std::hash::{BuildHasher, Hasher};
-use std::time::{SystemTime, UNIX_EPOCH};
-
-// Base64 URL-safe alphabet ordered by ASCII value for lexical sorting
-// This ensures that encoded strings maintain chronological order
-const BASE64_ALPHABET: &[u8; 64] = b"-0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ_abcdefghijklmnopqrstuvwxyz";
-
-/// Encodes a u64 value as Base64 URL-safe string (11 characters, no padding)
-fn base64_encode_u64(value: u64) -> String {
-    let bytes = value.to_be_bytes(); // Big-endian encoding
-    let mut result = Vec::with_capacity(11);
-
-    // Process bytes in groups of 3 (24 bits) -> 4 base64 chars
-    let mut i = 0;
-    while i + 2 < bytes.len() {
-        let b1 = bytes[i] as usize;
-        let b2 = bytes[i + 1] as usize;
-        let b3 = bytes[i + 2] as usize;
-
-        result.push(BASE64_ALPHABET[(b1 >> 2) & 0x3F]);
-        result.push(BASE64_ALPHABET[((b1 & 0x03) << 4) | ((b2 >> 4) & 0x0F)]);
-        result.push(BASE64_ALPHABET[((b2 & 0x0F) << 2) | ((b3 >> 6) & 0x03)]);
-        result.push(BASE64_ALPHABET[b3 & 0x3F]);
-
-        i += 3;
-    }
-
-    // Handle remaining bytes (2 bytes left for 8-byte u64)
-    if i < bytes.len() {
-        let b1 = bytes[i] as usize;
-        result.push(BASE64_ALPHABET[(b1 >> 2) & 0x3F]);
-
-        if i + 1 < bytes.len() {
-            let b2 = bytes[i + 1] as usize;
-            result.push(BASE64_ALPHABET[((b1 & 0x03) << 4) | ((b2 >> 4) & 0x0F)]);
-            result.push(BASE64_ALPHABET[(b2 & 0x0F) << 2]);
-        } else {
-            result.push(BASE64_ALPHABET[(b1 & 0x03) << 4]);
-        }
-    }
-
-    String::from_utf8(result).unwrap()
-}
-
-// Thread-local state for sequence tracking
-thread_local! {
-    static LAST_TIMESTAMP_MS: Cell<u64> = Cell::new(0);
-    static SEQUENCE: Cell<u16> = Cell::new(0);
-}
-
-/// Get current Unix timestamp in milliseconds
-fn current_time_ms() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("System time is before Unix epoch")
-        .as_millis() as u64
-}
-
-/// Generate a 10-bit random value using RandomState
-fn generate_random_10bit() -> u16 {
-    let random_state = RandomState::new();
-    let mut hasher = random_state.build_hasher();
-
-    // Add some entropy from current time nanos
-    let nanos = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .subsec_nanos();
-
-    hasher.write_u32(nanos);
-    let hash = hasher.finish();
-
-    // Take lower 10 bits
-    (hash & 0x3FF) as u16
-}
-
-/// Spin-wait until the next millisecond
-fn wait_next_millisecond(current: u64) {
-    while current_time_ms() == current {
-        std::hint::spin_loop();
-    }
-}
+use std::collections::HashSet;
+use std::io::{self, BufRead, BufWriter, Write};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-/// Generate a Tiny64 ID
-pub fn generate_tiny64() -> String {
-    let (timestamp_ms, sequence, random) = LAST_TIMESTAMP_MS.with(|last_time| {
-        SEQUENCE.with(|seq| {
-            let mut now = current_time_ms();
-            let last = last_time.get();
-            let mut current_seq = seq.get();
-
-            if now == last {
-                // Same millisecond: increment sequence
-                current_seq = (current_seq + 1) % 4096;
-
-                if current_seq == 0 {
-                    // Sequence overflow: wait for next millisecond
-                    wait_next_millisecond(now);
-                    now = current_time_ms();
-                }
-            } else {
-                // New millisecond: reset sequence
-                current_seq = 0;
-            }
+use tiny64::{
+    ENCODED_LEN, Encoding, Layout, MAX_TIMESTAMP_MS, SeededRandom, Settings, SteppingClock,
+    Tiny64Generator, decode_tiny64, find_duplicates, format_iso8601, generate_tiny64,
+    ids_to_string, time_until_overflow, timestamp_of, verify_ordering,
+};
 
-            // Update state
-            last_time.set(now);
-            seq.set(current_seq);
-
-            // Generate random 10-bit value
-            let random = generate_random_10bit();
-
-            (now, current_seq, random)
-        })
-    });
-
-    // Construct 64-bit value:
-    // [ 42 bits: timestamp_ms ] [ 12 bits: sequence ] [ 10 bits: random ]
-    let value = ((timestamp_ms & 0x3FF_FFFF_FFFF) << 22)
-        | ((sequence as u64 & 0xFFF) << 10)
-        | (random as u64 & 0x3FF);
-
-    base64_encode_u64(value)
-}
+/// Where the clock of a `--seed` run starts: 2023-11-14T22:13:20Z
+const SEED_START_MS: u64 = 1_700_000_000_000;
 
 fn print_help() {
     println!("Tiny64 - Time-Ordered Compact Unique IDs");
@@ -131,6 +19,51 @@ fn print_help() {
     println!("USAGE:");
     println!("    tiny64       Generate a single Tiny64 ID");
     println!("    tiny64 -h    Show this help message");
+    println!("    tiny64 timestamps < ids.txt");
+    println!("                 Print the UTC time each ID on stdin was minted");
+    println!("    tiny64 decode --json < ids.json");
+    println!("                 Decode a JSON array of IDs into a JSON array of their fields");
+    println!("    tiny64 collision-test [--rate <n>] [--duration <ms>] [--nodes <n>]");
+    println!("                          [--max-collisions <n>]");
+    println!("                 Mint <n> IDs/ms (default: 5000) on each of <n> independent");
+    println!("                 generators (default: 1) for <ms> (default: 1000) on the real");
+    println!(
+        "                 clock; exits non-zero if collisions exceed the maximum (default: 0)"
+    );
+    println!("    tiny64 --check-epoch <ms>");
+    println!("                 Report whether the current time fits in 42 bits counted from");
+    println!("                 the custom epoch <ms>; exits non-zero if it does not");
+    println!("    tiny64 selftest");
+    println!("                 Check generation invariants; exits non-zero on failure");
+    println!();
+    println!("OPTIONS:");
+    println!("    -n, --count <n>        Generate <n> IDs (default: 1)");
+    println!("    -d, --delimiter <sep>  Separator between IDs (default: newline); accepts the");
+    println!("                           escapes \\n \\r \\t \\0 \\\\ and \\xHH, e.g. '\\x1e'");
+    println!("    --with-time            Print each ID's UTC timestamp after it");
+    println!("    --format <template>    Print each ID through a template with {{id}}, {{time}},");
+    println!("                           {{seq}}, {{random}} and {{raw}} (the u64 value)");
+    println!("    --json                 Print the IDs as a JSON array of strings");
+    println!("    --continuous           Stream IDs until interrupted; Ctrl-C flushes and");
+    println!("                           exits 0 without cutting a line short");
+    println!("    --rate <n>             Stream <n> IDs per second, evenly paced, until");
+    println!("                           interrupted; reports the achieved rate on stderr");
+    println!("    --base <n>             Output encoding: 64 (default), 62 (alphanumeric),");
+    println!("                           32 (Crockford), 16 (hex) or 10 (decimal)");
+    println!("    --seed <n>             Reproducible output: seeded random bits and a clock");
+    println!(
+        "                           that starts at 2023-11-14T22:13:20Z and steps 1 ms per ID"
+    );
+    println!("    --verify               Decode and re-encode each ID before printing it;");
+    println!("                           fails if the round trip does not match");
+    println!("    --unique-check         Check the whole batch is unique before printing it;");
+    println!("                           lists any colliding IDs and exits 1");
+    println!("    --timestamp-bits <n>   Width of the timestamp field (default: the rest of 64)");
+    println!("    --sequence-bits <n>    Width of the sequence field (default: 12)");
+    println!("    --random-bits <n>      Width of the random field (default: 10)");
+    println!("                           Non-default layouts only decode with the same layout");
+    println!("    --config <path>        Read epoch_ms and bit widths from a key = value file");
+    println!("                           (default: $TINY64_CONFIG); flags override it");
     println!();
     println!("DESCRIPTION:");
     println!("    Tiny64 is a compact 64-bit identifier format designed for high-performance");
@@ -162,6 +95,771 @@ fn print_help() {
     println!("    Obrl8O3-3g3");
 }
 
+/// Read newline-separated IDs from stdin and print each one's minted time
+fn print_timestamps() -> io::Result<()> {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+
+    for line in stdin.lock().lines() {
+        let line = line?;
+        let id = line.trim();
+        if id.is_empty() {
+            continue;
+        }
+
+        // Malformed lines are annotated in place so one bad entry doesn't abort the stream
+        match timestamp_of(id) {
+            Ok(timestamp_ms) => writeln!(out, "{} {}", id, format_iso8601(timestamp_ms))?,
+            Err(e) => writeln!(out, "{} ERROR: {}", id, e)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse a JSON array of strings, the shape `--json` prints
+fn parse_json_string_array(input: &str) -> Result<Vec<String>, String> {
+    let mut chars = input.trim().chars().peekable();
+    let skip_ws = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+    };
+    if chars.next() != Some('[') {
+        return Err("expected a JSON array".to_string());
+    }
+
+    let mut items = Vec::new();
+    skip_ws(&mut chars);
+    if chars.next_if_eq(&']').is_none() {
+        loop {
+            skip_ws(&mut chars);
+            if chars.next() != Some('"') {
+                return Err("expected only strings in the JSON array".to_string());
+            }
+            let mut item = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => item.push(match chars.next() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('r') => '\r',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('u') => {
+                            let hex: String = chars.by_ref().take(4).collect();
+                            u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| format!("invalid escape '\\u{}'", hex))?
+                        }
+                        _ => return Err("invalid escape in JSON string".to_string()),
+                    }),
+                    Some(c) => item.push(c),
+                    None => return Err("unterminated JSON string".to_string()),
+                }
+            }
+            items.push(item);
+            skip_ws(&mut chars);
+            match chars.next() {
+                Some(',') => continue,
+                Some(']') => break,
+                _ => return Err("expected ',' or ']' in the JSON array".to_string()),
+            }
+        }
+    }
+    if chars.next().is_some() {
+        return Err("unexpected input after the JSON array".to_string());
+    }
+    Ok(items)
+}
+
+/// Quote `text` as a JSON string
+fn json_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Read a JSON array of IDs from stdin and print a JSON array of their fields
+///
+/// An ID that doesn't decode gets an `error` field in place of its fields,
+/// so one bad element doesn't abort the batch. Input that isn't an array of
+/// strings is an error.
+fn decode_json() -> Result<(), String> {
+    let mut input = String::new();
+    io::Read::read_to_string(&mut io::stdin(), &mut input).map_err(|e| e.to_string())?;
+    let ids = parse_json_string_array(&input)?;
+
+    let elements = ids.iter().map(|id| match decode_tiny64(id) {
+        // Splice the ID in ahead of the fields' own keys
+        Ok(parts) => format!("{{\"id\":{},{}", json_string(id), &parts.to_json()[1..]),
+        Err(e) => format!(
+            "{{\"id\":{},\"error\":{}}}",
+            json_string(id),
+            json_string(&e.to_string())
+        ),
+    });
+    println!("[{}]", ids_to_string(elements, ","));
+    Ok(())
+}
+
+/// Options for `tiny64 collision-test`
+#[derive(Debug, PartialEq)]
+struct CollisionTest {
+    rate: u32,
+    duration_ms: u64,
+    nodes: u32,
+    max_collisions: u64,
+    /// IDs the run aims to mint across all nodes
+    target: u64,
+}
+
+/// Parse the `collision-test` options, rejecting loads whose total number of
+/// IDs doesn't fit in a `u64`
+fn parse_collision_test(args: &[String]) -> Result<CollisionTest, String> {
+    let mut rate = 5000;
+    let mut duration_ms = 1000;
+    let mut nodes = 1;
+    let mut max_collisions = 0;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--rate" => rate = parse_value(arg, iter.next())?,
+            "--duration" => duration_ms = parse_value(arg, iter.next())?,
+            "--nodes" => nodes = parse_value(arg, iter.next())?,
+            "--max-collisions" => max_collisions = parse_value(arg, iter.next())?,
+            other => return Err(format!("unknown option '{}'", other)),
+        }
+    }
+
+    let target = (rate as u64)
+        .checked_mul(duration_ms)
+        .and_then(|per_node| per_node.checked_mul(nodes as u64))
+        .ok_or_else(|| {
+            format!(
+                "{} IDs/ms for {} ms on {} node(s) is too many IDs to count",
+                rate, duration_ms, nodes
+            )
+        })?;
+    Ok(CollisionTest {
+        rate,
+        duration_ms,
+        nodes,
+        max_collisions,
+        target,
+    })
+}
+
+/// Mint IDs live at the requested load and report duplicates
+///
+/// Returns whether the collisions stayed within `--max-collisions`.
+fn run_collision_test(args: &[String]) -> Result<bool, String> {
+    let CollisionTest {
+        rate,
+        duration_ms,
+        nodes,
+        max_collisions,
+        target,
+    } = parse_collision_test(args)?;
+
+    let stats = tiny64::measure_collisions(rate, duration_ms, nodes);
+    println!(
+        "generated:  {} of {} IDs ({} node(s), {} IDs/ms, {} ms)",
+        stats.generated, target, nodes, rate, duration_ms
+    );
+    let unique_ratio = if stats.generated == 0 {
+        1.0
+    } else {
+        stats.unique as f64 / stats.generated as f64
+    };
+    println!("unique:     {} ({:.6})", stats.unique, unique_ratio);
+    println!(
+        "collisions: {} ({:.6}%)",
+        stats.collisions,
+        stats.collision_rate() * 100.0
+    );
+
+    let passed = stats.collisions <= max_collisions;
+    println!(
+        "{}: at most {} collision(s) allowed",
+        if passed { "PASS" } else { "FAIL" },
+        max_collisions
+    );
+    Ok(passed)
+}
+
+/// Report how a custom epoch fits the 42-bit timestamp field; returns
+/// whether the current time is representable
+fn run_check_epoch(args: &[String]) -> Result<bool, String> {
+    let epoch_ms: u64 = match args {
+        [value] => parse_value("--check-epoch", Some(value))?,
+        _ => return Err("usage: tiny64 --check-epoch <ms>".to_string()),
+    };
+    // The overflow date must still be a date
+    if epoch_ms > i64::MAX as u64 - (MAX_TIMESTAMP_MS + 1) {
+        return Err(format!(
+            "epoch {} is past the last representable date",
+            epoch_ms
+        ));
+    }
+    let now_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| "system time is before Unix epoch".to_string())?
+        .as_millis() as u64;
+    let overflow_ms = epoch_ms + MAX_TIMESTAMP_MS + 1;
+
+    println!("epoch:        {} ({})", format_iso8601(epoch_ms), epoch_ms);
+    println!("now:          {}", format_iso8601(now_ms));
+    println!("overflows at: {}", format_iso8601(overflow_ms));
+    if now_ms < epoch_ms {
+        println!(
+            "status:       epoch is {} days in the future; timestamps would be negative",
+            (epoch_ms - now_ms) / 86_400_000
+        );
+        println!("FAIL");
+        return Ok(false);
+    }
+    let remaining = time_until_overflow(epoch_ms);
+    if remaining.is_zero() {
+        println!("status:       the 42-bit timestamp has already overflowed");
+        println!("FAIL");
+        return Ok(false);
+    }
+    let days = remaining.as_secs() / 86_400;
+    println!(
+        "used:         {:.2}% of the 42-bit range",
+        (now_ms - epoch_ms) as f64 / (MAX_TIMESTAMP_MS + 1) as f64 * 100.0
+    );
+    println!(
+        "time left:    {} days (about {:.1} years)",
+        days,
+        days as f64 / 365.25
+    );
+    println!("OK");
+    Ok(true)
+}
+
+const SELFTEST_COUNT: usize = 10_000;
+
+/// Generate a batch of IDs and check they are unique, well-formed and sorted
+fn run_selftest() -> bool {
+    let start = Instant::now();
+    let ids: Vec<String> = (0..SELFTEST_COUNT).map(|_| generate_tiny64()).collect();
+    let elapsed = start.elapsed();
+
+    let unique = ids.iter().collect::<HashSet<_>>().len();
+    let bad_length = ids.iter().filter(|id| id.len() != ENCODED_LEN).count();
+    let ordering = verify_ordering(&ids);
+
+    println!("generated:  {}", ids.len());
+    println!("unique:     {}", unique);
+    println!("bad length: {}", bad_length);
+    match &ordering {
+        Ok(()) => println!("ordering:   sorted"),
+        Err(violation) => println!("ordering:   {}", violation),
+    }
+    println!(
+        "rate:       {:.0} IDs/s",
+        ids.len() as f64 / elapsed.as_secs_f64()
+    );
+
+    let passed = unique == ids.len() && bad_length == 0 && ordering.is_ok();
+    println!("{}", if passed { "PASS" } else { "FAIL" });
+    passed
+}
+
+/// One piece of a `--format` template
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Literal(String),
+    Id,
+    Time,
+    Seq,
+    Random,
+    Raw,
+}
+
+/// Split a `--format` template into literal text and placeholders
+fn parse_template(template: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        if open > 0 {
+            segments.push(Segment::Literal(rest[..open].to_string()));
+        }
+        let close = rest[open..]
+            .find('}')
+            .ok_or_else(|| format!("unclosed '{{' in format '{}'", template))?;
+        let name = &rest[open + 1..open + close];
+        segments.push(match name {
+            "id" => Segment::Id,
+            "time" => Segment::Time,
+            "seq" => Segment::Seq,
+            "random" => Segment::Random,
+            "raw" => Segment::Raw,
+            other => {
+                return Err(format!(
+                    "unknown placeholder '{{{}}}', expected {{id}}, {{time}}, {{seq}}, {{random}} or {{raw}}",
+                    other
+                ));
+            }
+        });
+        rest = &rest[open + close + 1..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Literal(rest.to_string()));
+    }
+    Ok(segments)
+}
+
+/// Fill in a parsed template for the value `value`, encoded as `id`
+fn render_template(segments: &[Segment], id: &str, value: u64, options: &Options) -> String {
+    let parts = options.layout.unpack(value);
+    segments
+        .iter()
+        .map(|segment| match segment {
+            Segment::Literal(text) => text.clone(),
+            Segment::Id => id.to_string(),
+            Segment::Time => format_iso8601(parts.timestamp_ms + options.epoch_ms),
+            Segment::Seq => parts.sequence.to_string(),
+            Segment::Random => parts.random.to_string(),
+            Segment::Raw => value.to_string(),
+        })
+        .collect()
+}
+
+/// Options for ID generation
+struct Options {
+    count: usize,
+    delimiter: String,
+    with_time: bool,
+    format: Option<Vec<Segment>>,
+    json: bool,
+    continuous: bool,
+    rate: Option<u32>,
+    verify: bool,
+    unique_check: bool,
+    encoding: Encoding,
+    seed: Option<u64>,
+    epoch_ms: u64,
+    layout: Layout,
+}
+
+fn parse_value<T: FromStr>(flag: &str, value: Option<&String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{} requires a value", flag))?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{}' for {}", value, flag))
+}
+
+/// Expand the backslash escapes of a `--delimiter` value
+///
+/// `\n`, `\r`, `\t`, `\0` and `\\` mean what they do in C, and `\xHH` is
+/// any ASCII byte in hex, so control characters such as the record separator
+/// `\x1e` can be typed in any shell.
+fn unescape(text: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('t') => out.push('\t'),
+            Some('0') => out.push('\0'),
+            Some('\\') => out.push('\\'),
+            Some('x') => {
+                let hex: String = chars.by_ref().take(2).collect();
+                let byte = (hex.len() == 2 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+                    .then(|| u8::from_str_radix(&hex, 16).ok())
+                    .flatten()
+                    .filter(u8::is_ascii)
+                    .ok_or_else(|| {
+                        format!("invalid escape '\\x{}', expected \\x00 to \\x7f", hex)
+                    })?;
+                out.push(byte as char);
+            }
+            Some(other) => return Err(format!("unknown escape '\\{}'", other)),
+            None => return Err("trailing '\\' in delimiter".to_string()),
+        }
+    }
+    Ok(out)
+}
+
+/// Read and parse the config file at `path`
+fn load_config(path: &str) -> Result<Settings, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("config {}: {}", path, e))?;
+    Settings::parse(&text).map_err(|e| format!("config {}: {}", path, e))
+}
+
+/// Map a `--base` value to the library encoding for it
+fn encoding_for_base(base: &str) -> Result<Encoding, String> {
+    match base {
+        "64" => Ok(Encoding::Base64),
+        "62" => Ok(Encoding::Base62),
+        "32" => Ok(Encoding::Crockford),
+        "16" => Ok(Encoding::Hex),
+        "10" => Ok(Encoding::Decimal),
+        other => Err(format!(
+            "unsupported base '{}', expected 64, 62, 32, 16 or 10",
+            other
+        )),
+    }
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut count = 1;
+    let mut delimiter = "\n".to_string();
+    let mut delimiter_set = false;
+    let mut with_time = false;
+    let mut format = None;
+    let mut json = false;
+    let mut continuous = false;
+    let mut rate = None;
+    let mut verify = false;
+    let mut unique_check = false;
+    let mut encoding = Encoding::Base64;
+    let mut seed = None;
+    let mut config = None;
+    let mut timestamp_bits = None;
+    let mut sequence_bits = None;
+    let mut random_bits = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-n" | "--count" => count = parse_value(arg, iter.next())?,
+            "-d" | "--delimiter" => {
+                delimiter = unescape(&parse_value::<String>(arg, iter.next())?)?;
+                delimiter_set = true;
+            }
+            "--with-time" => with_time = true,
+            "--format" => format = Some(parse_template(&parse_value::<String>(arg, iter.next())?)?),
+            "--json" => json = true,
+            "--continuous" => continuous = true,
+            "--rate" => rate = Some(parse_value(arg, iter.next())?),
+            "--verify" => verify = true,
+            "--unique-check" => unique_check = true,
+            "--seed" => seed = Some(parse_value(arg, iter.next())?),
+            "--base" => encoding = encoding_for_base(&parse_value::<String>(arg, iter.next())?)?,
+            "--timestamp-bits" => timestamp_bits = Some(parse_value(arg, iter.next())?),
+            "--sequence-bits" => sequence_bits = Some(parse_value(arg, iter.next())?),
+            "--random-bits" => random_bits = Some(parse_value(arg, iter.next())?),
+            "--config" => config = Some(parse_value::<String>(arg, iter.next())?),
+            other => return Err(format!("unknown option '{}'", other)),
+        }
+    }
+
+    if json && (with_time || delimiter_set) {
+        return Err("--json cannot be combined with --with-time or --delimiter".to_string());
+    }
+    if format.is_some() && (with_time || json) {
+        return Err("--format cannot be combined with --with-time or --json".to_string());
+    }
+    if rate == Some(0) {
+        return Err("--rate must be at least 1".to_string());
+    }
+    // --rate streams like --continuous, only paced
+    let stream_flag = if rate.is_some() {
+        "--rate"
+    } else {
+        "--continuous"
+    };
+    continuous |= rate.is_some();
+    if continuous && json {
+        return Err(format!("{} cannot be combined with --json", stream_flag));
+    }
+    if continuous && unique_check {
+        return Err(format!(
+            "{} cannot be combined with --unique-check",
+            stream_flag
+        ));
+    }
+
+    let settings = match config.or_else(|| std::env::var("TINY64_CONFIG").ok()) {
+        Some(path) => load_config(&path)?,
+        None => Settings::default(),
+    };
+
+    // Bit-width flags override the config. When only the sequence or random
+    // width is given, the timestamp fills whatever they leave of the 64 bits.
+    let base = settings.layout;
+    let timestamp_bits = timestamp_bits.unwrap_or_else(|| {
+        if sequence_bits.is_none() && random_bits.is_none() {
+            base.timestamp_bits()
+        } else {
+            let taken = sequence_bits
+                .unwrap_or(base.sequence_bits())
+                .saturating_add(random_bits.unwrap_or(base.random_bits()));
+            64u32.saturating_sub(taken)
+        }
+    });
+    let sequence_bits = sequence_bits.unwrap_or(base.sequence_bits());
+    let random_bits = random_bits.unwrap_or(base.random_bits());
+    let layout = Layout::new(timestamp_bits, sequence_bits, random_bits)
+        .map_err(|e| format!("invalid layout: {}", e))?;
+
+    Ok(Options {
+        count,
+        delimiter,
+        with_time,
+        format,
+        json,
+        continuous,
+        rate,
+        verify,
+        unique_check,
+        encoding,
+        seed,
+        epoch_ms: settings.epoch_ms,
+        layout,
+    })
+}
+
+/// Packed values from the real-time generator, or the deterministic one
+/// under `--seed`
+type IdSource = Box<dyn FnMut() -> u64>;
+
+fn build_source(options: &Options) -> Result<IdSource, String> {
+    let start_ms = match options.seed {
+        Some(_) => SEED_START_MS,
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_| "system time is before Unix epoch".to_string())?
+            .as_millis() as u64,
+    };
+    // Every ID would carry timestamp 0, and the sequence would run out
+    if start_ms < options.epoch_ms {
+        return Err("epoch is in the future".to_string());
+    }
+    if start_ms - options.epoch_ms > options.layout.max_timestamp_ms() {
+        return Err(format!(
+            "current time does not fit in a {}-bit timestamp",
+            options.layout.timestamp_bits()
+        ));
+    }
+
+    let builder = Tiny64Generator::builder()
+        .epoch_ms(options.epoch_ms)
+        .layout(options.layout);
+    Ok(match options.seed {
+        Some(seed) => {
+            let mut generator = builder
+                .clock(SteppingClock::new(start_ms))
+                .random_source(SeededRandom::new(seed))
+                .build()
+                .map_err(|e| e.to_string())?;
+            Box::new(move || generator.generate_u64())
+        }
+        None => {
+            let mut generator = builder.build().map_err(|e| e.to_string())?;
+            Box::new(move || generator.generate_u64())
+        }
+    })
+}
+
+/// Mint one ID and format it as a line of output, without the separator;
+/// returns the bare ID and the line
+fn next_id(options: &Options, source: &mut IdSource) -> Result<(String, String), String> {
+    let value = source();
+    let id = options.encoding.encode(value);
+    if options.verify {
+        verify_round_trip(options, &id)?;
+    }
+    let line = if let Some(template) = &options.format {
+        render_template(template, &id, value, options)
+    } else if options.with_time {
+        // Unpack the value we just minted so the printed time is what it encodes
+        let timestamp_ms = options.layout.unpack(value).timestamp_ms + options.epoch_ms;
+        format!("{} {}", id, format_iso8601(timestamp_ms))
+    } else {
+        id.clone()
+    };
+    Ok((id, line))
+}
+
+/// Mint the batch, returning the bare IDs and their lines of output
+fn generate(options: &Options) -> Result<(Vec<String>, Vec<String>), String> {
+    let mut source = build_source(options)?;
+    (0..options.count)
+        .map(|_| next_id(options, &mut source))
+        .collect()
+}
+
+fn render(options: &Options, lines: Vec<String>) -> String {
+    if options.json {
+        // Same shape as tiny64::write_json_array; no alphabet needs escaping
+        let elements = lines.into_iter().map(|id| format!("\"{}\"", id));
+        return format!("[{}]", ids_to_string(elements, ","));
+    }
+    ids_to_string(lines, &options.delimiter)
+}
+
+/// Report every ID that occurs more than once; returns whether all are unique
+fn check_unique(ids: &[String]) -> bool {
+    let duplicates = find_duplicates(ids);
+    if duplicates.is_empty() {
+        return true;
+    }
+    let extra: usize = duplicates.iter().map(|(_, count)| count - 1).sum();
+    eprintln!(
+        "tiny64: unique-check failed: {} of {} IDs are duplicates of another",
+        extra,
+        ids.len()
+    );
+    for (id, count) in duplicates {
+        eprintln!("    {} x{}", id, count);
+    }
+    false
+}
+
+/// Set by the SIGINT handler; the continuous loop checks it between IDs
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(unix)]
+fn install_sigint_handler() {
+    const SIGINT: i32 = 2;
+    unsafe extern "C" {
+        fn signal(signum: i32, handler: extern "C" fn(i32)) -> usize;
+    }
+    extern "C" fn on_sigint(_: i32) {
+        INTERRUPTED.store(true, Ordering::SeqCst);
+    }
+    // SAFETY: the handler only stores to an atomic, which is async-signal-safe
+    unsafe {
+        signal(SIGINT, on_sigint);
+    }
+}
+
+#[cfg(not(unix))]
+fn install_sigint_handler() {}
+
+/// Write IDs until interrupted or the reader goes away
+///
+/// Output is buffered. On Ctrl-C the loop finishes the line it is on, flushes
+/// and returns, so a downstream reader never sees a partial line; a closed
+/// pipe ends the loop quietly.
+fn run_continuous(options: &Options, mut source: IdSource) -> Result<(), String> {
+    install_sigint_handler();
+
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    let mut pacer = options.rate.map(Pacer::new);
+    while !INTERRUPTED.load(Ordering::SeqCst) {
+        if pacer.as_mut().is_some_and(|pacer| !pacer.wait()) {
+            break;
+        }
+        let (_, line) = next_id(options, &mut source)?;
+        if let Err(e) = write!(out, "{}{}", line, options.delimiter) {
+            return ignore_broken_pipe(e);
+        }
+        if pacer.is_some() {
+            // A paced stream is slow enough to hand over each ID as it comes
+            if let Err(e) = out.flush() {
+                return ignore_broken_pipe(e);
+            }
+        }
+    }
+    out.flush().or_else(ignore_broken_pipe)
+}
+
+/// Schedules `--rate` IDs at even intervals and reports the rate achieved
+///
+/// Each ID is due at a fixed offset from the start, so time lost to a late
+/// wakeup is made up by the next IDs rather than lowering the rate.
+struct Pacer {
+    rate: u32,
+    start: Instant,
+    sent: u64,
+    report_at: Instant,
+    report_sent: u64,
+}
+
+impl Pacer {
+    const REPORT_EVERY: Duration = Duration::from_secs(1);
+
+    fn new(rate: u32) -> Pacer {
+        let start = Instant::now();
+        Pacer {
+            rate,
+            start,
+            sent: 0,
+            report_at: start,
+            report_sent: 0,
+        }
+    }
+
+    /// Sleep until the next ID is due; false if interrupted meanwhile
+    fn wait(&mut self) -> bool {
+        let due = self.start + Duration::from_secs(self.sent) / self.rate;
+        loop {
+            if INTERRUPTED.load(Ordering::SeqCst) {
+                return false;
+            }
+            let now = Instant::now();
+            if now >= due {
+                break;
+            }
+            // Short naps so Ctrl-C is noticed promptly even at low rates
+            std::thread::sleep((due - now).min(Duration::from_millis(100)));
+        }
+
+        let elapsed = self.report_at.elapsed();
+        if elapsed >= Self::REPORT_EVERY {
+            let achieved = (self.sent - self.report_sent) as f64 / elapsed.as_secs_f64();
+            eprintln!("tiny64: {:.1} IDs/s (target {})", achieved, self.rate);
+            self.report_at = Instant::now();
+            self.report_sent = self.sent;
+        }
+        self.sent += 1;
+        true
+    }
+}
+
+/// A reader that stopped reading (e.g. `| head`) is a normal way to finish
+fn ignore_broken_pipe(e: io::Error) -> Result<(), String> {
+    if e.kind() == io::ErrorKind::BrokenPipe {
+        Ok(())
+    } else {
+        Err(e.to_string())
+    }
+}
+
+/// Decode `id` into its fields, pack and encode them again, and check the
+/// result is the same string
+fn verify_round_trip(options: &Options, id: &str) -> Result<(), String> {
+    let value = options
+        .encoding
+        .decode(id)
+        .map_err(|e| format!("verify: '{}' does not decode: {}", id, e))?;
+    let parts = options.layout.unpack(value);
+    let repacked = options
+        .layout
+        .pack(&parts)
+        .map_err(|e| format!("verify: fields of '{}' do not pack: {}", id, e))?;
+    let reencoded = options.encoding.encode(repacked);
+    if reencoded != id {
+        return Err(format!("verify: '{}' re-encodes as '{}'", id, reencoded));
+    }
+    Ok(())
+}
+
 fn main() {
     let args: Vec<String> = std::env::args().collect();
 
@@ -171,64 +869,336 @@ fn main() {
         return;
     }
 
-    // Generate and print a single ID
-    println!("{}", generate_tiny64());
+    if args.len() > 1 && args[1] == "timestamps" {
+        if let Err(e) = print_timestamps() {
+            eprintln!("tiny64: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "decode" {
+        if args[2..] != ["--json"] {
+            eprintln!("tiny64: usage: tiny64 decode --json < ids.json");
+            std::process::exit(2);
+        }
+        if let Err(e) = decode_json() {
+            eprintln!("tiny64: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "collision-test" {
+        match run_collision_test(&args[2..]) {
+            Ok(passed) => std::process::exit(if passed { 0 } else { 1 }),
+            Err(e) => {
+                eprintln!("tiny64: {}", e);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    if args.len() > 1 && args[1] == "--check-epoch" {
+        match run_check_epoch(&args[2..]) {
+            Ok(fits) => std::process::exit(if fits { 0 } else { 1 }),
+            Err(e) => {
+                eprintln!("tiny64: {}", e);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    if args.len() > 1 && args[1] == "selftest" {
+        std::process::exit(if run_selftest() { 0 } else { 1 });
+    }
+
+    let options = match parse_options(&args[1..]) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("tiny64: {}", e);
+            std::process::exit(2);
+        }
+    };
+
+    if options.continuous {
+        let source = match build_source(&options) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("tiny64: {}", e);
+                std::process::exit(2);
+            }
+        };
+        if let Err(e) = run_continuous(&options, source) {
+            eprintln!("tiny64: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let (ids, lines) = match generate(&options) {
+        Ok(batch) => batch,
+        Err(e) => {
+            eprintln!("tiny64: {}", e);
+            std::process::exit(2);
+        }
+    };
+    if options.unique_check && !check_unique(&ids) {
+        std::process::exit(1);
+    }
+    println!("{}", render(&options, lines));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    /// Write `text` to a config file unique to this test run and return its
+    /// path
+    fn config_file(name: &str, text: &str) -> String {
+        let path =
+            std::env::temp_dir().join(format!("tiny64-test-{}-{}.conf", std::process::id(), name));
+        std::fs::write(&path, text).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
     #[test]
-    fn test_base64_encode_length() {
-        let id = base64_encode_u64(0x123456789ABCDEF0);
-        assert_eq!(id.len(), 11);
+    fn test_parse_options_oversized_widths() {
+        for flags in [
+            &["--random-bits", "4294967295"][..],
+            &[
+                "--timestamp-bits",
+                "4294967295",
+                "--sequence-bits",
+                "1",
+                "--random-bits",
+                "0",
+            ],
+        ] {
+            let err = parse_options(&args(flags)).err().unwrap();
+            assert!(err.starts_with("invalid layout:"), "{}", err);
+        }
     }
 
     #[test]
-    fn test_generate_tiny64_format() {
-        let id = generate_tiny64();
-        assert_eq!(id.len(), 11);
+    fn test_parse_collision_test() {
+        let options = parse_collision_test(&args(&["--rate", "10", "--nodes", "3"])).unwrap();
+        assert_eq!(
+            options,
+            CollisionTest {
+                rate: 10,
+                duration_ms: 1000,
+                nodes: 3,
+                max_collisions: 0,
+                target: 30_000,
+            }
+        );
 
-        // Check all characters are Base64 URL-safe
-        for ch in id.chars() {
-            assert!(ch.is_ascii_alphanumeric() || ch == '-' || ch == '_');
+        for (flags, message) in [
+            (&["--seed"][..], "unknown option '--seed'"),
+            (&["--rate"], "--rate requires a value"),
+            (&["--nodes", "-1"], "invalid value '-1' for --nodes"),
+            (
+                &["--rate", "4000000000", "--duration", "18446744073709551615"],
+                "4000000000 IDs/ms for 18446744073709551615 ms on 1 node(s) is too many IDs to count",
+            ),
+            (
+                &[
+                    "--rate",
+                    "4294967295",
+                    "--duration",
+                    "4294967297",
+                    "--nodes",
+                    "2",
+                ],
+                "4294967295 IDs/ms for 4294967297 ms on 2 node(s) is too many IDs to count",
+            ),
+        ] {
+            assert_eq!(parse_collision_test(&args(flags)), Err(message.to_string()));
         }
     }
 
     #[test]
-    fn test_multiple_ids_different() {
-        let id1 = generate_tiny64();
-        let id2 = generate_tiny64();
-        let id3 = generate_tiny64();
+    fn test_parse_template() {
+        assert_eq!(
+            parse_template("{id} at {time}: {seq}/{random} ({raw})").unwrap(),
+            [
+                Segment::Id,
+                Segment::Literal(" at ".to_string()),
+                Segment::Time,
+                Segment::Literal(": ".to_string()),
+                Segment::Seq,
+                Segment::Literal("/".to_string()),
+                Segment::Random,
+                Segment::Literal(" (".to_string()),
+                Segment::Raw,
+                Segment::Literal(")".to_string()),
+            ]
+        );
+        // A lone '}' is plain text
+        assert_eq!(
+            parse_template("a}b{id}}").unwrap(),
+            [
+                Segment::Literal("a}b".to_string()),
+                Segment::Id,
+                Segment::Literal("}".to_string()),
+            ]
+        );
+        assert!(parse_template("").unwrap().is_empty());
 
-        // IDs should be different (very high probability)
-        assert_ne!(id1, id2);
-        assert_ne!(id2, id3);
+        let err = parse_template("x {id").unwrap_err();
+        assert_eq!(err, "unclosed '{' in format 'x {id'");
+        let err = parse_template("{name}").unwrap_err();
+        assert!(err.starts_with("unknown placeholder '{name}'"), "{}", err);
+        assert!(parse_template("{}").is_err());
     }
 
     #[test]
-    fn test_time_ordering() {
-        let mut ids = vec![];
-        for _ in 0..100 {
-            ids.push(generate_tiny64());
-        }
+    fn test_parse_json_string_array() {
+        assert_eq!(
+            parse_json_string_array(" [ \"a\" , \"b\\\"c\" ]\n").unwrap(),
+            ["a", "b\"c"]
+        );
+        assert!(parse_json_string_array("[]").unwrap().is_empty());
+        assert_eq!(
+            parse_json_string_array(r#"["\u0041\u00e9", "\n\/"]"#).unwrap(),
+            ["A\u{e9}", "\n/"]
+        );
 
-        // IDs should be in lexical order (or equal if same millisecond)
-        for i in 0..ids.len() - 1 {
-            if ids[i] > ids[i + 1] {
-                eprintln!("Order violation at index {}: '{}' > '{}'", i, ids[i], ids[i + 1]);
-            }
-            assert!(ids[i] <= ids[i + 1]);
+        let err = |input: &str| parse_json_string_array(input).unwrap_err();
+        assert_eq!(err(r#"["a",]"#), "expected only strings in the JSON array");
+        assert_eq!(err(r#"["a" "b"]"#), "expected ',' or ']' in the JSON array");
+        assert_eq!(
+            err(r#"["a", 1]"#),
+            "expected only strings in the JSON array"
+        );
+        assert_eq!(err(r#"[null]"#), "expected only strings in the JSON array");
+        assert_eq!(err(r#"["\uZZZZ"]"#), "invalid escape '\\uZZZZ'");
+        assert_eq!(err(r#"["\u00"]"#), "invalid escape '\\u00\"]'");
+        assert_eq!(err(r#"["\q"]"#), "invalid escape in JSON string");
+        assert_eq!(err(r#"["a"#), "unterminated JSON string");
+        assert_eq!(err(r#"{"a": 1}"#), "expected a JSON array");
+        assert_eq!(err(r#"["a"] x"#), "unexpected input after the JSON array");
+    }
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!(unescape(r"\x1e").unwrap(), "\x1e");
+        assert_eq!(unescape(r"a\tb\n\r\0\\").unwrap(), "a\tb\n\r\0\\");
+        assert_eq!(unescape(r"\x7F|").unwrap(), "\x7f|");
+        assert_eq!(unescape(", ").unwrap(), ", ");
+
+        assert_eq!(
+            unescape(r"\xZZ").unwrap_err(),
+            r"invalid escape '\xZZ', expected \x00 to \x7f"
+        );
+        assert_eq!(
+            unescape(r"\x80").unwrap_err(),
+            r"invalid escape '\x80', expected \x00 to \x7f"
+        );
+        assert_eq!(
+            unescape(r"\x1").unwrap_err(),
+            r"invalid escape '\x1', expected \x00 to \x7f"
+        );
+        assert_eq!(unescape(r"\q").unwrap_err(), r"unknown escape '\q'");
+        assert_eq!(unescape(r"ab\").unwrap_err(), r"trailing '\' in delimiter");
+    }
+
+    #[test]
+    fn test_parse_options_fills_timestamp_bits() {
+        // An empty config, so a TINY64_CONFIG in the environment can't interfere
+        let empty = config_file("empty", "");
+        let layout = |flags: &[&str]| {
+            let mut list = vec!["--config", empty.as_str()];
+            list.extend_from_slice(flags);
+            parse_options(&args(&list)).ok().unwrap().layout
+        };
+        assert_eq!(layout(&[]), Layout::DEFAULT);
+        assert_eq!(
+            layout(&["--sequence-bits", "14"]),
+            Layout::new(40, 14, 10).unwrap()
+        );
+        assert_eq!(
+            layout(&["--random-bits", "4", "--sequence-bits", "16"]),
+            Layout::new(44, 16, 4).unwrap()
+        );
+        let err = parse_options(&args(&["--config", &empty, "--timestamp-bits", "40"]))
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            "invalid layout: field widths sum to 62 bits, expected 64"
+        );
+    }
+
+    #[test]
+    fn test_flags_override_config() {
+        let path = config_file(
+            "override",
+            "# test\nepoch_ms = 1700000000000\nsequence_bits = 14\nrandom_bits = 8\n",
+        );
+        let settings = load_config(&path).unwrap();
+        assert_eq!(settings.epoch_ms, 1_700_000_000_000);
+        assert_eq!(settings.layout, Layout::new(42, 14, 8).unwrap());
+
+        let options = parse_options(&args(&["--config", &path])).ok().unwrap();
+        assert_eq!(options.epoch_ms, 1_700_000_000_000);
+        assert_eq!(options.layout, settings.layout);
+
+        // The flag wins over the file, and the timestamp takes what's left
+        let options = parse_options(&args(&["--config", &path, "--random-bits", "10"]))
+            .ok()
+            .unwrap();
+        assert_eq!(options.layout, Layout::new(40, 14, 10).unwrap());
+        assert_eq!(options.epoch_ms, 1_700_000_000_000);
+
+        let bad = config_file("bad", "epoch_ms = 1\nsequence_bits = 4294967295\n");
+        let err = load_config(&bad).unwrap_err();
+        assert!(
+            err.ends_with("line 2: invalid value for `sequence_bits`"),
+            "{}",
+            err
+        );
+        assert!(load_config("/nonexistent/tiny64.conf").is_err());
+    }
+
+    #[test]
+    fn test_run_check_epoch() {
+        assert_eq!(run_check_epoch(&args(&["1600000000000"])), Ok(true));
+        // Year 5138
+        assert_eq!(run_check_epoch(&args(&["99999999999999"])), Ok(false));
+
+        for epoch in ["18446744073709551615", "9223367638808264704"] {
+            assert_eq!(
+                run_check_epoch(&args(&[epoch])),
+                Err(format!(
+                    "epoch {} is past the last representable date",
+                    epoch
+                ))
+            );
         }
+        assert!(run_check_epoch(&args(&["9223367638808264703"])).is_ok());
+        assert!(run_check_epoch(&args(&[])).is_err());
+        assert!(run_check_epoch(&args(&["-1"])).is_err());
     }
 
     #[test]
-    fn test_debug_values() {
-        // Generate a few IDs and print raw values
-        for _ in 0..5 {
-            let id = generate_tiny64();
-            println!("Generated ID: {}", id);
+    fn test_build_source_rejects_future_epoch() {
+        let path = config_file("future", "epoch_ms = 99999999999999\n");
+        for extra in [&[][..], &["--seed", "1"]] {
+            let mut flags = vec!["--config", path.as_str()];
+            flags.extend_from_slice(extra);
+            let options = parse_options(&args(&flags)).ok().unwrap();
+            let err = build_source(&options).err().unwrap();
+            assert_eq!(err, "epoch is in the future");
         }
+
+        let path = config_file("past", "epoch_ms = 1600000000000\n");
+        let options = parse_options(&args(&["--config", &path])).ok().unwrap();
+        assert!(build_source(&options).is_ok());
     }
 }
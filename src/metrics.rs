@@ -0,0 +1,95 @@
+//! The shared generator's counters in the Prometheus text exposition format.
+//!
+//! Enabled by the `prometheus` feature. [`metrics_text`] renders
+//! [`shared_generator`]'s [`GeneratorStats`] for a `/metrics` endpoint to
+//! serve as-is; nothing here depends on a Prometheus client crate.
+//!
+//! | Metric | Type | Meaning |
+//! |---|---|---|
+//! | `tiny64_ids_generated_total` | counter | IDs minted |
+//! | `tiny64_sequence_spins_total` | counter | Times a millisecond's sequence numbers ran out and the generator waited for the clock |
+//! | `tiny64_sequence_high_water` | gauge | Highest sequence number reached in any millisecond |
+//! | `tiny64_clock_backwards_total` | counter | Times the clock read earlier than the last ID's timestamp |
+
+use std::fmt::Write;
+
+use crate::clock::GeneratorStats;
+use crate::shared::shared_generator;
+
+/// Render the shared generator's counters as Prometheus text
+pub fn metrics_text() -> String {
+    render(&shared_generator().stats())
+}
+
+fn render(stats: &GeneratorStats) -> String {
+    let metrics = [
+        (
+            "tiny64_ids_generated_total",
+            "counter",
+            "IDs minted by the shared generator.",
+            stats.generated,
+        ),
+        (
+            "tiny64_sequence_spins_total",
+            "counter",
+            "Times a millisecond's sequence numbers ran out and the generator waited for the clock.",
+            stats.spins,
+        ),
+        (
+            "tiny64_sequence_high_water",
+            "gauge",
+            "Highest sequence number reached in any millisecond.",
+            stats.max_sequence as u64,
+        ),
+        (
+            "tiny64_clock_backwards_total",
+            "counter",
+            "Times the clock read earlier than the last ID's timestamp.",
+            stats.clock_backwards,
+        ),
+    ];
+    let mut text = String::new();
+    for (name, kind, help, value) in metrics {
+        // Writing to a String can't fail
+        let _ = writeln!(text, "# HELP {} {}", name, help);
+        let _ = writeln!(text, "# TYPE {} {}", name, kind);
+        let _ = writeln!(text, "{} {}", name, value);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_metrics() {
+        let stats = GeneratorStats {
+            generated: 10,
+            last_timestamp_ms: 1_000,
+            max_sequence: 4,
+            spins: 1,
+            clock_backwards: 2,
+        };
+        let text = render(&stats);
+        assert!(text.contains(
+            "# TYPE tiny64_ids_generated_total counter\ntiny64_ids_generated_total 10\n"
+        ));
+        assert!(
+            text.contains(
+                "# TYPE tiny64_sequence_high_water gauge\ntiny64_sequence_high_water 4\n"
+            )
+        );
+        assert!(text.contains("tiny64_sequence_spins_total 1\n"));
+        assert!(text.contains("tiny64_clock_backwards_total 2\n"));
+        assert_eq!(text.lines().count(), 12);
+
+        shared_generator().generate();
+        let live = metrics_text();
+        let generated = live
+            .lines()
+            .find_map(|line| line.strip_prefix("tiny64_ids_generated_total "))
+            .unwrap();
+        assert!(generated.parse::<u64>().unwrap() >= 1);
+    }
+}
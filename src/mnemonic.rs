@@ -0,0 +1,154 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::decode::{ParseError, decode_u64};
+use crate::encode::base64_encode_u64;
+
+/// Number of words in a mnemonic: one word per byte of the 64-bit value
+pub const MNEMONIC_WORDS: usize = 8;
+
+// Sorted so lookups can binary search. A list of 2^k words encodes k bits per
+// word, so covering 64 bits takes ceil(64 / k) words: 256 words (k = 8) gives
+// 8 words, while a BIP-39 sized list of 2048 (k = 11) would still need 6.
+const WORDLIST: [&str; 256] = [
+    "acid", "acorn", "actor", "adobe", "agent", "alarm", "album", "alley", "amber", "anchor",
+    "angle", "apple", "apron", "arena", "arrow", "aspen", "atlas", "attic", "autumn", "bacon",
+    "badge", "bagel", "baker", "bamboo", "banjo", "barley", "basil", "basin", "beach", "beacon",
+    "beard", "beaver", "berry", "bike", "bison", "blade", "blaze", "bloom", "bolt", "bonus",
+    "boot", "bottle", "bowl", "brain", "brave", "bread", "brick", "bridge", "brook", "broom",
+    "bubble", "bucket", "bunny", "cabin", "cactus", "camel", "candle", "canoe", "canyon", "carpet",
+    "carrot", "castle", "cedar", "cello", "chalk", "cherry", "chess", "chili", "cider", "circle",
+    "citrus", "clay", "cliff", "clock", "cloud", "clover", "cobra", "cocoa", "comet", "coral",
+    "cotton", "cougar", "crane", "crater", "crayon", "crown", "cubic", "daisy", "dancer", "delta",
+    "denim", "desert", "dingo", "dolphin", "donkey", "dragon", "drum", "eagle", "easel", "echo",
+    "elbow", "ember", "engine", "falcon", "feather", "fern", "ferry", "fiddle", "flame", "flute",
+    "forest", "fossil", "fox", "galaxy", "garden", "garlic", "gecko", "geyser", "ginger",
+    "giraffe", "globe", "gopher", "grape", "gravel", "guitar", "hammer", "harbor", "hazel",
+    "helmet", "heron", "hippo", "honey", "hornet", "husky", "igloo", "iris", "island", "ivory",
+    "jacket", "jaguar", "jelly", "jungle", "kayak", "kettle", "kiwi", "koala", "ladder", "lagoon",
+    "lantern", "lemon", "lentil", "lily", "lizard", "llama", "locket", "lotus", "lunar", "magnet",
+    "mango", "maple", "marble", "meadow", "melon", "meteor", "mint", "mitten", "monkey", "moose",
+    "mosaic", "muffin", "nectar", "needle", "nickel", "noodle", "nutmeg", "oasis", "ocean",
+    "olive", "onion", "orbit", "orchid", "otter", "owl", "oyster", "paddle", "panda", "papaya",
+    "parrot", "peach", "peanut", "pebble", "pepper", "piano", "pickle", "pigeon", "pillow", "pine",
+    "pirate", "planet", "plum", "pony", "poppy", "prism", "pumpkin", "puzzle", "quail", "quartz",
+    "quill", "rabbit", "radish", "raven", "reef", "ribbon", "river", "robin", "rocket", "ruby",
+    "saddle", "salmon", "sandal", "saturn", "scarf", "shadow", "shell", "sierra", "silver",
+    "sketch", "sloth", "snail", "spruce", "squid", "stone", "sunset", "swan", "tango", "teapot",
+    "tiger", "timber", "tomato", "topaz", "tulip", "tundra", "turtle", "valley", "velvet",
+    "violet", "violin", "walnut", "walrus", "willow", "wizard", "yacht", "yogurt", "zebra",
+    "zephyr", "zinc",
+];
+
+/// Error returned when a mnemonic cannot be turned back into an ID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MnemonicError {
+    WrongWordCount(usize),
+    UnknownWord { word: String, position: usize },
+}
+
+impl fmt::Display for MnemonicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MnemonicError::WrongWordCount(n) => {
+                write!(f, "expected {} words, got {}", MNEMONIC_WORDS, n)
+            }
+            MnemonicError::UnknownWord { word, position } => {
+                write!(f, "unknown word '{}' at position {}", word, position)
+            }
+        }
+    }
+}
+
+impl core::error::Error for MnemonicError {}
+
+/// Spell a Tiny64 ID as hyphen-separated words for reading aloud
+///
+/// Each byte of the value, most significant first, picks one of 256 words, so
+/// the mnemonic is lossless and [`from_mnemonic`] restores the exact ID. Store
+/// the compact form and use this only for display.
+pub fn to_mnemonic(id: &str) -> Result<String, ParseError> {
+    let value = decode_u64(id)?;
+    let words: Vec<&str> = value
+        .to_be_bytes()
+        .iter()
+        .map(|&b| WORDLIST[b as usize])
+        .collect();
+    Ok(words.join("-"))
+}
+
+/// Turn a mnemonic produced by [`to_mnemonic`] back into the Tiny64 ID
+///
+/// Words are matched case-insensitively.
+pub fn from_mnemonic(mnemonic: &str) -> Result<String, MnemonicError> {
+    let words: Vec<&str> = mnemonic.trim().split('-').collect();
+    if words.len() != MNEMONIC_WORDS {
+        return Err(MnemonicError::WrongWordCount(words.len()));
+    }
+
+    let mut bytes = [0u8; MNEMONIC_WORDS];
+    for (position, (word, byte)) in words.iter().zip(bytes.iter_mut()).enumerate() {
+        let lower = word.to_ascii_lowercase();
+        let index =
+            WORDLIST
+                .binary_search(&lower.as_str())
+                .map_err(|_| MnemonicError::UnknownWord {
+                    word: word.to_string(),
+                    position,
+                })?;
+        *byte = index as u8;
+    }
+
+    Ok(base64_encode_u64(u64::from_be_bytes(bytes)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::generate_tiny64;
+
+    #[test]
+    fn test_wordlist_is_sorted_and_unique() {
+        assert!(WORDLIST.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_mnemonic_round_trip() {
+        for id in [
+            generate_tiny64(),
+            "-----------".to_string(),
+            "zzzzzzzzzzw".to_string(),
+        ] {
+            let mnemonic = to_mnemonic(&id).unwrap();
+            assert_eq!(mnemonic.split('-').count(), MNEMONIC_WORDS);
+            assert_eq!(from_mnemonic(&mnemonic).unwrap(), id);
+        }
+
+        assert_eq!(
+            to_mnemonic("-----------").unwrap(),
+            "acid-acid-acid-acid-acid-acid-acid-acid"
+        );
+        assert_eq!(
+            from_mnemonic("ACID-acid-acid-acid-acid-acid-acid-acid").unwrap(),
+            "-----------"
+        );
+    }
+
+    #[test]
+    fn test_from_mnemonic_errors() {
+        assert_eq!(
+            from_mnemonic("acid-acid"),
+            Err(MnemonicError::WrongWordCount(2))
+        );
+        assert_eq!(
+            from_mnemonic("acid-acid-acid-nope-acid-acid-acid-acid"),
+            Err(MnemonicError::UnknownWord {
+                word: "nope".to_string(),
+                position: 3
+            })
+        );
+    }
+}
@@ -0,0 +1,154 @@
+//! Node IDs carried in place of the random field.
+//!
+//! Layout: `[ 42 bits: timestamp_ms ] [ 12 bits: sequence ] [ 10 bits: node_id ]`.
+//!
+//! A generator built with
+//! [`Tiny64GeneratorBuilder::node_id`](crate::Tiny64GeneratorBuilder::node_id)
+//! writes its node ID where the random bits would go. Uniqueness then no
+//! longer rests on chance: as long as every generator has its own node ID,
+//! two of them can never mint the same ID. The field is the default layout's
+//! random field, so node-layout IDs encode, sort and decode like any other,
+//! and [`node_of`] is simply their `random` part.
+//!
+//! Nothing in an ID says which layout minted it. On a default-layout ID the
+//! "node" is just its random bits, so [`is_from_node`] is only meaningful for
+//! IDs known to come from node-ID generators.
+//!
+//! [`generate_auto_node`] skips assigning node IDs by deriving one from the
+//! hostname.
+
+#[cfg(feature = "std")]
+use alloc::string::String;
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "std")]
+use std::sync::{Mutex, OnceLock};
+
+use crate::RANDOM_BITS;
+#[cfg(feature = "std")]
+use crate::clock::{SequenceState, SystemClock};
+use crate::decode::{ParseError, decode_tiny64};
+
+/// Largest node ID that fits in the 10-bit node field
+pub const MAX_NODE_ID: u16 = (1 << RANDOM_BITS) - 1;
+
+/// Read the node ID of an ID minted by a node-ID generator
+pub fn node_of(id: &str) -> Result<u16, ParseError> {
+    Ok(decode_tiny64(id)?.random)
+}
+
+/// Whether `id` was minted by the generator with node ID `node_id`
+///
+/// Only meaningful for node-layout IDs: a default-layout ID "matches" any
+/// node ID equal to its random bits. Node IDs above [`MAX_NODE_ID`] never
+/// match.
+pub fn is_from_node(id: &str, node_id: u16) -> Result<bool, ParseError> {
+    Ok(node_of(id)? == node_id)
+}
+
+/// The machine's hostname
+#[cfg(all(feature = "std", unix))]
+fn hostname() -> io::Result<String> {
+    unsafe extern "C" {
+        fn gethostname(name: *mut core::ffi::c_char, len: usize) -> core::ffi::c_int;
+    }
+    let mut buf = [0u8; 256];
+    // SAFETY: the pointer and length describe `buf`, which outlives the call
+    if unsafe { gethostname(buf.as_mut_ptr().cast(), buf.len()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    Ok(String::from_utf8_lossy(&buf[..len]).into_owned())
+}
+
+/// The machine's hostname
+#[cfg(all(feature = "std", not(unix)))]
+fn hostname() -> io::Result<String> {
+    std::env::var("COMPUTERNAME").map_err(|e| io::Error::new(io::ErrorKind::NotFound, e))
+}
+
+/// Hash a hostname into the 10-bit node field
+#[cfg(feature = "std")]
+fn node_id_for_host(host: &str) -> u16 {
+    let hash = crate::hash::mix64(crate::hash::fnv1a_64(host.as_bytes()));
+    (hash >> (64 - RANDOM_BITS)) as u16
+}
+
+/// The node ID [`generate_auto_node`] uses: a hash of this machine's hostname
+///
+/// The hostname is read once per process. Fails if it can't be read.
+#[cfg(feature = "std")]
+pub fn auto_node_id() -> io::Result<u16> {
+    static NODE_ID: OnceLock<u16> = OnceLock::new();
+    if let Some(&node_id) = NODE_ID.get() {
+        return Ok(node_id);
+    }
+    let node_id = node_id_for_host(&hostname()?);
+    Ok(*NODE_ID.get_or_init(|| node_id))
+}
+
+/// Generate a node-layout ID whose node ID is derived from the hostname
+///
+/// Zero-config node IDs for the common case: each host gets a stable node
+/// ID from a hash of its name, and all threads in the process share one
+/// sequence, so IDs from one host never collide. Different hosts can still
+/// hash to the same node ID: with only 1024 of them, two given hosts clash
+/// with probability 1/1024, and among 40 hosts the odds that some pair clashes
+/// pass one half. Colliding hosts are no worse off than plain IDs, but for
+/// guaranteed uniqueness assign node IDs with
+/// [`Tiny64GeneratorBuilder::node_id`](crate::Tiny64GeneratorBuilder::node_id).
+/// Fails if the hostname can't be read.
+#[cfg(feature = "std")]
+pub fn generate_auto_node() -> io::Result<String> {
+    static STATE: Mutex<SequenceState> = Mutex::new(SequenceState {
+        last_timestamp_ms: 0,
+        sequence: 0,
+    });
+    let node_id = auto_node_id()?;
+    let (timestamp_ms, sequence, _) = STATE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .reserve(1, crate::MAX_IDS_PER_MS, &mut SystemClock);
+    Ok(crate::base64_encode_u64(crate::parts::pack_masked(
+        timestamp_ms,
+        sequence,
+        node_id,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Tiny64Parts;
+
+    #[test]
+    fn test_is_from_node() {
+        let id = Tiny64Parts {
+            timestamp_ms: 1_700_000_000_000,
+            sequence: 5,
+            random: 42,
+        }
+        .to_id()
+        .unwrap();
+        assert_eq!(node_of(&id), Ok(42));
+        assert_eq!(is_from_node(&id, 42), Ok(true));
+        assert_eq!(is_from_node(&id, 43), Ok(false));
+        assert_eq!(is_from_node(&id, 42 + MAX_NODE_ID + 1), Ok(false));
+        assert_eq!(is_from_node("bad", 42), Err(ParseError::InvalidLength(3)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_auto_node() {
+        let node_id = auto_node_id().unwrap();
+        assert!(node_id <= MAX_NODE_ID);
+        assert_eq!(node_id, node_id_for_host(&hostname().unwrap()));
+
+        let ids: Vec<String> = (0..5000).map(|_| generate_auto_node().unwrap()).collect();
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(ids.iter().all(|id| is_from_node(id, node_id) == Ok(true)));
+
+        assert_eq!(node_id_for_host("web-1"), node_id_for_host("web-1"));
+        assert_ne!(node_id_for_host("web-1"), node_id_for_host("web-2"));
+    }
+}
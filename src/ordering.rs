@@ -0,0 +1,367 @@
+use alloc::collections::BinaryHeap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::{Ordering, Reverse};
+use core::fmt;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+
+use crate::MAX_SEQUENCE;
+use crate::decode::{ParseError, decode_tiny64, decode_u64};
+use crate::parts::{PackError, Tiny64Parts};
+
+/// The first adjacent pair of IDs found out of order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderViolation {
+    /// Index of `left`; `right` is at `index + 1`
+    pub index: usize,
+    pub left: String,
+    pub right: String,
+}
+
+impl fmt::Display for OrderViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let relation = if self.left == self.right { "=" } else { ">" };
+        write!(
+            f,
+            "order violation at index {}: '{}' {} '{}'",
+            self.index, self.left, relation, self.right
+        )
+    }
+}
+
+impl core::error::Error for OrderViolation {}
+
+/// Check that IDs are in lexical (and therefore time) order
+///
+/// Equal adjacent IDs are allowed. Returns the first pair where
+/// `ids[i] > ids[i + 1]`.
+pub fn verify_ordering(ids: &[String]) -> Result<(), OrderViolation> {
+    first_violation(ids, |left, right| left > right)
+}
+
+/// Check that IDs strictly increase, with no two equal
+///
+/// This is the check for cursors and pagination, where an equal pair would
+/// make a page boundary skip or repeat IDs. Returns the first pair where
+/// `ids[i] >= ids[i + 1]`.
+pub fn verify_strictly_increasing(ids: &[String]) -> Result<(), OrderViolation> {
+    first_violation(ids, |left, right| left >= right)
+}
+
+/// The index at which `id` belongs in `sorted_ids` to keep it sorted
+///
+/// IDs sort lexically in time order, so this is the chronological position,
+/// found by binary search without decoding anything. If `id` is already
+/// present, the index is that of its first occurrence, so
+/// `sorted_ids.get(i) == Some(id)` detects a duplicate before inserting.
+/// `sorted_ids` must be in lexical order.
+pub fn insertion_index(sorted_ids: &[String], id: &str) -> usize {
+    sorted_ids.partition_point(|existing| existing.as_str() < id)
+}
+
+/// Compare two IDs by their decoded timestamp, then sequence, then random
+/// bits
+///
+/// A deterministic total order for merging IDs from different sources. For
+/// canonical default-layout IDs it agrees with plain string comparison, but
+/// it also orders equal values equally when one string is padded with `=` or
+/// has stray bits in its last character. IDs that don't decode sort after
+/// every valid one, in string order among themselves, so the comparator stays
+/// total and can be passed straight to `sort_by`.
+pub fn total_cmp(a: &str, b: &str) -> Ordering {
+    let key = |id: &str| {
+        decode_tiny64(id)
+            .ok()
+            .map(|parts| (parts.timestamp_ms, parts.sequence, parts.random))
+    };
+    match (key(a), key(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.cmp(b),
+    }
+}
+
+/// Compare an ID with a packed value, as if the value were encoded
+///
+/// Decodes `id` instead of encoding `value`, so nothing is allocated: handy
+/// for range filters whose bounds are kept as integers.
+pub fn cmp_str_to_u64(id: &str, value: u64) -> Result<Ordering, ParseError> {
+    Ok(decode_u64(id)?.cmp(&value))
+}
+
+fn first_violation(
+    ids: &[String],
+    violates: impl Fn(&String, &String) -> bool,
+) -> Result<(), OrderViolation> {
+    match ids.windows(2).position(|pair| violates(&pair[0], &pair[1])) {
+        Some(index) => Err(OrderViolation {
+            index,
+            left: ids[index].clone(),
+            right: ids[index + 1].clone(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Iterator returned by [`merge_sorted`]
+pub struct MergeSorted<I: Iterator<Item = String>> {
+    streams: Vec<I>,
+    // Min-heap of (next ID, stream index); the index keeps ties stable
+    heap: BinaryHeap<Reverse<(String, usize)>>,
+}
+
+impl<I: Iterator<Item = String>> Iterator for MergeSorted<I> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        let Reverse((id, index)) = self.heap.pop()?;
+        if let Some(next) = self.streams[index].next() {
+            self.heap.push(Reverse((next, index)));
+        }
+        Some(id)
+    }
+}
+
+/// Merge individually sorted ID streams into one sorted stream
+///
+/// This is a k-way merge: it only holds one pending ID per stream and never
+/// sorts the full input. Each stream must already be in lexical order.
+pub fn merge_sorted<I: Iterator<Item = String>>(mut streams: Vec<I>) -> MergeSorted<I> {
+    let mut heap = BinaryHeap::with_capacity(streams.len());
+    for (index, stream) in streams.iter_mut().enumerate() {
+        if let Some(id) = stream.next() {
+            heap.push(Reverse((id, index)));
+        }
+    }
+    MergeSorted { streams, heap }
+}
+
+/// Yield each ID only the first time it appears
+///
+/// Useful after [`merge_sorted`] when shards may overlap. Every distinct ID seen
+/// so far is kept in memory.
+#[cfg(feature = "std")]
+pub fn dedup_preserving_order(ids: impl Iterator<Item = String>) -> impl Iterator<Item = String> {
+    let mut seen = HashSet::new();
+    ids.filter(move |id| seen.insert(id.clone()))
+}
+
+/// Rewrite the sequence numbers of imported parts to follow their order in
+/// the slice
+///
+/// Within each run of adjacent parts sharing a timestamp, sequences become
+/// 0, 1, 2, ... in slice order, so re-encoding gives strictly increasing IDs
+/// whenever the timestamps are non-decreasing. Use it on data whose order
+/// within a millisecond is known but whose sequence numbers were lost or
+/// collide. Parts with equal timestamps must be adjacent: a stable sort by
+/// timestamp keeps the known order. Random bits are left alone.
+///
+/// Fails without changing anything if a millisecond holds more parts than
+/// the sequence field can number.
+pub fn resequence(parts: &mut [Tiny64Parts]) -> Result<(), PackError> {
+    let longest = parts
+        .chunk_by(|a, b| a.timestamp_ms == b.timestamp_ms)
+        .map(<[Tiny64Parts]>::len)
+        .max()
+        .unwrap_or(0);
+    if longest > MAX_SEQUENCE as usize + 1 {
+        let last = u16::try_from(longest - 1).unwrap_or(u16::MAX);
+        return Err(PackError::SequenceOutOfRange {
+            value: last,
+            max: MAX_SEQUENCE,
+        });
+    }
+    for run in parts.chunk_by_mut(|a, b| a.timestamp_ms == b.timestamp_ms) {
+        for (sequence, part) in run.iter_mut().enumerate() {
+            part.sequence = sequence as u16;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::generate_tiny64;
+    use crate::parts::id_from_fields;
+    use alloc::string::ToString;
+    use alloc::{format, vec};
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_verify_ordering() {
+        let mut ids: Vec<String> = (0..100).map(|_| generate_tiny64()).collect();
+        assert_eq!(verify_ordering(&ids), Ok(()));
+        assert_eq!(verify_ordering(&[]), Ok(()));
+
+        ids.swap(10, 11);
+        let violation = verify_ordering(&ids).unwrap_err();
+        assert_eq!(violation.index, 10);
+        assert_eq!(violation.left, ids[10]);
+        assert_eq!(violation.right, ids[11]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_verify_strictly_increasing() {
+        let mut ids: Vec<String> = (0..100).map(|_| generate_tiny64()).collect();
+        assert_eq!(verify_strictly_increasing(&ids), Ok(()));
+        assert_eq!(verify_strictly_increasing(&[]), Ok(()));
+
+        // Equal neighbours pass verify_ordering but not the strict check
+        ids[21] = ids[20].clone();
+        assert_eq!(verify_ordering(&ids), Ok(()));
+        let violation = verify_strictly_increasing(&ids).unwrap_err();
+        assert_eq!(violation.index, 20);
+        assert!(
+            violation
+                .to_string()
+                .ends_with(&format!("'{}' = '{}'", ids[20], ids[20]))
+        );
+
+        // Past the equal pair, an out-of-order pair is caught as well
+        ids.swap(50, 51);
+        let violation = verify_strictly_increasing(&ids[30..]).unwrap_err();
+        assert_eq!(violation.index, 20);
+        assert_eq!(violation.left, ids[50]);
+        assert_eq!(violation.right, ids[51]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_insertion_index() {
+        let ids: Vec<String> = (0..20).map(|_| crate::generate_tiny64()).collect();
+        let mut index = Vec::new();
+        for i in [7, 3, 15, 0, 19, 11, 3, 8] {
+            let at = insertion_index(&index, &ids[i]);
+            index.insert(at, ids[i].clone());
+        }
+        assert_eq!(verify_ordering(&index), Ok(()));
+        assert_eq!(index.first(), Some(&ids[0]));
+        assert_eq!(index.last(), Some(&ids[19]));
+
+        let at = insertion_index(&index, &ids[3]);
+        assert_eq!(
+            (index[at].as_str(), index[at + 1].as_str()),
+            (&*ids[3], &*ids[3])
+        );
+        assert_eq!(insertion_index(&[], &ids[0]), 0);
+    }
+
+    #[test]
+    fn test_total_cmp() {
+        let (a, b, c) = (
+            id_from_fields(1_000, 0, 900),
+            id_from_fields(1_000, 1, 5),
+            id_from_fields(1_001, 0, 0),
+        );
+        assert_eq!(total_cmp(&a, &b), Ordering::Less);
+        assert_eq!(total_cmp(&c, &b), Ordering::Greater);
+        assert_eq!(total_cmp(&a, &a), Ordering::Equal);
+        // Padding doesn't change the value
+        assert_eq!(total_cmp(&a, &format!("{}=", a)), Ordering::Equal);
+
+        let mut ids = vec![
+            "bad".to_string(),
+            c.clone(),
+            a.clone(),
+            "also bad".to_string(),
+            b.clone(),
+        ];
+        ids.sort_by(|x, y| total_cmp(x, y));
+        assert_eq!(ids, [a, b, c, "also bad".to_string(), "bad".to_string()]);
+    }
+
+    #[test]
+    fn test_cmp_str_to_u64() {
+        let id = crate::base64_encode_u64(1 << 40);
+        assert_eq!(cmp_str_to_u64(&id, 1 << 40), Ok(Ordering::Equal));
+        assert_eq!(cmp_str_to_u64(&id, (1 << 40) + 1), Ok(Ordering::Less));
+        assert_eq!(cmp_str_to_u64(&id, (1 << 40) - 1), Ok(Ordering::Greater));
+        assert_eq!(
+            cmp_str_to_u64("short", 0),
+            Err(ParseError::InvalidLength(5))
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_merge_sorted() {
+        let ids: Vec<String> = (0..60).map(|_| generate_tiny64()).collect();
+
+        // Deal the IDs round-robin into three individually sorted shards
+        let shards: Vec<Vec<String>> = (0..3)
+            .map(|shard| ids.iter().skip(shard).step_by(3).cloned().collect())
+            .collect();
+
+        let merged: Vec<String> =
+            merge_sorted(shards.into_iter().map(|s| s.into_iter()).collect()).collect();
+        assert_eq!(merged, ids);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_dedup_preserving_order() {
+        let ids: Vec<String> = ["b", "a", "b", "c", "a"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let deduped: Vec<String> = dedup_preserving_order(ids.into_iter()).collect();
+        assert_eq!(deduped, ["b", "a", "c"]);
+
+        // Overlapping shards merge back to the original set once duplicates go
+        let ids: Vec<String> = (0..20).map(|_| generate_tiny64()).collect();
+        let shards = vec![ids[..15].iter().cloned(), ids[5..].iter().cloned()];
+        let merged: Vec<String> = dedup_preserving_order(merge_sorted(shards)).collect();
+        assert_eq!(merged, ids);
+    }
+
+    #[test]
+    fn test_resequence() {
+        let part = |timestamp_ms, sequence, random| Tiny64Parts {
+            timestamp_ms,
+            sequence,
+            random,
+        };
+        let mut parts = vec![
+            part(1_000, 7, 1),
+            part(1_000, 7, 2),
+            part(1_000, 0, 3),
+            part(1_001, 9, 4),
+            part(1_002, 3, 5),
+            part(1_002, 3, 6),
+        ];
+        resequence(&mut parts).unwrap();
+        let fields: Vec<(u64, u16, u16)> = parts
+            .iter()
+            .map(|p| (p.timestamp_ms, p.sequence, p.random))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                (1_000, 0, 1),
+                (1_000, 1, 2),
+                (1_000, 2, 3),
+                (1_001, 0, 4),
+                (1_002, 0, 5),
+                (1_002, 1, 6)
+            ]
+        );
+        let ids: Vec<String> = parts.iter().map(|p| p.to_id().unwrap()).collect();
+        assert_eq!(verify_strictly_increasing(&ids), Ok(()));
+
+        let mut full = vec![part(5, 0, 0); MAX_SEQUENCE as usize + 2];
+        assert_eq!(
+            resequence(&mut full),
+            Err(PackError::SequenceOutOfRange {
+                value: MAX_SEQUENCE + 1,
+                max: MAX_SEQUENCE
+            })
+        );
+        assert!(full.iter().all(|p| p.sequence == 0));
+        resequence(&mut []).unwrap();
+    }
+}
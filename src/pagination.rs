@@ -0,0 +1,177 @@
+//! Keyset pagination over a sorted ID column.
+//!
+//! Keyset pagination pages by value rather than by offset: each page is
+//! queried as `WHERE id >= cursor ORDER BY id LIMIT n`, and the next cursor
+//! comes from the last ID returned. Unlike `OFFSET`, this stays cheap deep
+//! into a table and never skips or repeats a row when rows are inserted
+//! between page loads. Every cursor here is an inclusive lower bound, so the
+//! query shape is the same for every page.
+
+use alloc::string::String;
+
+use crate::decode::ParseError;
+use crate::encode::base64_encode_u64;
+use crate::range::successor;
+
+/// Cursors and page splitting for keyset pagination with a fixed page size
+///
+/// Query each page for up to [`fetch_limit`](Self::fetch_limit) rows, one
+/// more than the page size, starting at [`first_cursor`](Self::first_cursor)
+/// and then at each page's [`Page::next`] cursor, and hand the rows to
+/// [`page`](Self::page). The extra row only answers whether another page
+/// exists; it is not part of this page, and it is fetched again as the
+/// first row of the next one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Paginator {
+    page_size: usize,
+}
+
+/// One page of IDs, from [`Paginator::page`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<'a> {
+    /// The IDs on this page, at most the page size of them
+    pub ids: &'a [String],
+    /// Inclusive lower bound for the next page, or `None` if this page is
+    /// the last
+    pub next: Option<String>,
+}
+
+impl Page<'_> {
+    /// Whether another page follows this one
+    pub fn has_more(&self) -> bool {
+        self.next.is_some()
+    }
+}
+
+impl Paginator {
+    /// A paginator returning `page_size` IDs per page
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page_size` is 0.
+    pub fn new(page_size: usize) -> Self {
+        assert!(page_size > 0, "page size must be at least 1");
+        Paginator { page_size }
+    }
+
+    /// Number of IDs per page
+    pub fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    /// Number of rows to fetch per query: one more than the page size
+    pub fn fetch_limit(&self) -> usize {
+        self.page_size.saturating_add(1)
+    }
+
+    /// Lower bound of the first page: the smallest ID, so `id >= cursor`
+    /// matches every ID
+    pub fn first_cursor(&self) -> String {
+        base64_encode_u64(0)
+    }
+
+    /// The cursor for the page after one ending at `last_id`
+    ///
+    /// The [`successor`] of `last_id`, or `None` if `last_id` is the largest
+    /// ID and nothing can follow it.
+    pub fn next_cursor(&self, last_id: &str) -> Result<Option<String>, ParseError> {
+        successor(last_id)
+    }
+
+    /// Split the sorted rows fetched for one page into the page itself and
+    /// the next cursor
+    ///
+    /// `rows` is the result of the query for up to
+    /// [`fetch_limit`](Self::fetch_limit) IDs. If it holds more than a page,
+    /// the page is the first [`page_size`](Self::page_size) of them and the
+    /// next cursor follows the last; otherwise this is the last page. Fails
+    /// if the page's last ID doesn't parse.
+    pub fn page<'a>(&self, rows: &'a [String]) -> Result<Page<'a>, ParseError> {
+        if rows.len() <= self.page_size {
+            return Ok(Page {
+                ids: rows,
+                next: None,
+            });
+        }
+        let ids = &rows[..self.page_size];
+        let next = self.next_cursor(&ids[self.page_size - 1])?;
+        Ok(Page { ids, next })
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    /// Run `SELECT id WHERE id >= cursor ORDER BY id LIMIT limit` over a
+    /// sorted slice
+    fn query<'a>(table: &'a [String], cursor: &str, limit: usize) -> &'a [String] {
+        let start = table.partition_point(|id| id.as_str() < cursor);
+        &table[start..table.len().min(start + limit)]
+    }
+
+    fn pages(table: &[String], paginator: Paginator) -> Vec<Vec<String>> {
+        let mut pages = Vec::new();
+        let mut cursor = Some(paginator.first_cursor());
+        while let Some(at) = cursor {
+            let page = paginator
+                .page(query(table, &at, paginator.fetch_limit()))
+                .unwrap();
+            pages.push(page.ids.to_vec());
+            cursor = page.next;
+        }
+        pages
+    }
+
+    fn pages_of_len(table: &[String], page_size: usize) -> Vec<usize> {
+        pages(table, Paginator::new(page_size))
+            .iter()
+            .map(Vec::len)
+            .collect()
+    }
+
+    #[test]
+    fn test_pages_cover_table() {
+        let table = crate::generate_n_at(1_000, 10).unwrap();
+        assert_eq!(pages(&table, Paginator::new(4)).concat(), table);
+        assert_eq!(pages_of_len(&table, 4), [4, 4, 2]);
+
+        // An exact multiple ends on a full page, not an empty one
+        assert_eq!(pages_of_len(&table[..8], 4), [4, 4]);
+    }
+
+    #[test]
+    fn test_empty_and_single_page() {
+        let paginator = Paginator::new(5);
+        let page = paginator.page(&[]).unwrap();
+        assert!(page.ids.is_empty());
+        assert!(!page.has_more());
+        assert_eq!(pages_of_len(&[], 5), [0]);
+
+        let table = crate::generate_n_at(1_000, 5).unwrap();
+        let page = paginator.page(&table).unwrap();
+        assert_eq!(page.ids, &table[..]);
+        assert!(!page.has_more());
+        assert_eq!(pages_of_len(&table, 5), [5]);
+    }
+
+    #[test]
+    fn test_cursors() {
+        let paginator = Paginator::new(2);
+        assert_eq!(paginator.first_cursor(), "-----------");
+        assert_eq!(paginator.fetch_limit(), 3);
+
+        let rows = crate::generate_n_at(1_000, 3).unwrap();
+        let page = paginator.page(&rows).unwrap();
+        assert_eq!(page.ids, &rows[..2]);
+        let next = page.next.unwrap();
+        assert!(rows[1] < next && next <= rows[2]);
+
+        assert_eq!(paginator.next_cursor("zzzzzzzzzzw"), Ok(None));
+        assert_eq!(
+            paginator.next_cursor("abc"),
+            Err(ParseError::InvalidLength(3))
+        );
+    }
+}
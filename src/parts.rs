@@ -0,0 +1,217 @@
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+
+use crate::encode::base64_encode_u64;
+use crate::{MAX_RANDOM, MAX_SEQUENCE, MAX_TIMESTAMP_MS, RANDOM_BITS, SEQUENCE_BITS};
+
+/// The decoded fields of a Tiny64 ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tiny64Parts {
+    pub timestamp_ms: u64,
+    pub sequence: u16,
+    pub random: u16,
+}
+
+/// Error returned when a field does not fit in its bit width
+///
+/// Each field variant carries the largest value the layout allows, so the
+/// error reads right for custom [`Layout`](crate::Layout)s too.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackError {
+    TimestampOutOfRange {
+        value: u64,
+        max: u64,
+    },
+    SequenceOutOfRange {
+        value: u16,
+        max: u16,
+    },
+    RandomOutOfRange {
+        value: u16,
+        max: u16,
+    },
+    /// A legacy integer ID too wide for the sequence and random fields
+    LegacyIdOutOfRange(u64),
+}
+
+impl fmt::Display for PackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PackError::TimestampOutOfRange { value, max } => {
+                write!(f, "timestamp {} exceeds maximum {}", value, max)
+            }
+            PackError::SequenceOutOfRange { value, max } => {
+                write!(f, "sequence {} exceeds maximum {}", value, max)
+            }
+            PackError::RandomOutOfRange { value, max } => {
+                write!(f, "random {} exceeds maximum {}", value, max)
+            }
+            PackError::LegacyIdOutOfRange(v) => write!(
+                f,
+                "legacy ID {} exceeds maximum {}",
+                v,
+                (1u64 << (SEQUENCE_BITS + RANDOM_BITS)) - 1
+            ),
+        }
+    }
+}
+
+impl core::error::Error for PackError {}
+
+/// Pack fields into a u64, truncating each to its bit width
+pub(crate) fn pack_masked(timestamp_ms: u64, sequence: u16, random: u16) -> u64 {
+    ((timestamp_ms & MAX_TIMESTAMP_MS) << (SEQUENCE_BITS + RANDOM_BITS))
+        | ((sequence & MAX_SEQUENCE) as u64) << RANDOM_BITS
+        | (random & MAX_RANDOM) as u64
+}
+
+impl Tiny64Parts {
+    /// Split a packed u64 into its fields
+    pub fn from_u64(value: u64) -> Self {
+        Tiny64Parts {
+            timestamp_ms: value >> (SEQUENCE_BITS + RANDOM_BITS),
+            sequence: ((value >> RANDOM_BITS) & MAX_SEQUENCE as u64) as u16,
+            random: (value & MAX_RANDOM as u64) as u16,
+        }
+    }
+
+    /// Pack the fields into a u64, validating each against its bit width
+    pub fn pack(&self) -> Result<u64, PackError> {
+        if self.timestamp_ms > MAX_TIMESTAMP_MS {
+            return Err(PackError::TimestampOutOfRange {
+                value: self.timestamp_ms,
+                max: MAX_TIMESTAMP_MS,
+            });
+        }
+        if self.sequence > MAX_SEQUENCE {
+            return Err(PackError::SequenceOutOfRange {
+                value: self.sequence,
+                max: MAX_SEQUENCE,
+            });
+        }
+        if self.random > MAX_RANDOM {
+            return Err(PackError::RandomOutOfRange {
+                value: self.random,
+                max: MAX_RANDOM,
+            });
+        }
+
+        Ok(pack_masked(self.timestamp_ms, self.sequence, self.random))
+    }
+
+    /// Pack the fields and encode them as a Tiny64 string
+    pub fn to_id(&self) -> Result<String, PackError> {
+        self.pack().map(base64_encode_u64)
+    }
+
+    /// The fields as a compact JSON object with a fixed key order
+    ///
+    /// `{"timestamp_ms":…,"time":…,"sequence":…,"random":…}`, where `time` is
+    /// the timestamp in ISO-8601 UTC. Key order and spacing never vary, so
+    /// the output can be compared byte for byte in golden files and diffs.
+    /// The same object, with the ID in front, is what `tiny64 decode --json`
+    /// prints.
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"timestamp_ms\":{},\"time\":\"{}\",\"sequence\":{},\"random\":{}}}",
+            self.timestamp_ms,
+            crate::format_iso8601(self.timestamp_ms),
+            self.sequence,
+            self.random
+        )
+    }
+}
+
+/// The ID with the given fields, for test fixtures
+///
+/// Panics if a field is out of range.
+#[cfg(test)]
+pub(crate) fn id_from_fields(timestamp_ms: u64, sequence: u16, random: u16) -> String {
+    Tiny64Parts {
+        timestamp_ms,
+        sequence,
+        random,
+    }
+    .to_id()
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::{decode_tiny64, generate_tiny64};
+
+    #[test]
+    fn test_to_json() {
+        let parts = Tiny64Parts {
+            timestamp_ms: 1_705_312_345_123,
+            sequence: 42,
+            random: 768,
+        };
+        assert_eq!(
+            parts.to_json(),
+            r#"{"timestamp_ms":1705312345123,"time":"2024-01-15T09:52:25.123Z","sequence":42,"random":768}"#
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_to_id_after_modification() {
+        let id = generate_tiny64();
+        let mut parts = decode_tiny64(&id).unwrap();
+        parts.sequence = (parts.sequence + 1) % (MAX_SEQUENCE + 1);
+
+        let new_id = parts.to_id().unwrap();
+        let reparsed = decode_tiny64(&new_id).unwrap();
+        assert_eq!(reparsed, parts);
+        assert_ne!(new_id, id);
+    }
+
+    #[test]
+    fn test_field_extremes() {
+        // A zero random field is a legitimate output and must round-trip
+        let min = Tiny64Parts {
+            timestamp_ms: 0,
+            sequence: 0,
+            random: 0,
+        };
+        assert_eq!(min.to_id().unwrap(), "-----------");
+        assert_eq!(Tiny64Parts::from_u64(0), min);
+
+        let max = Tiny64Parts {
+            timestamp_ms: MAX_TIMESTAMP_MS,
+            sequence: MAX_SEQUENCE,
+            random: MAX_RANDOM,
+        };
+        assert_eq!(max.to_id().unwrap(), "zzzzzzzzzzw");
+        assert_eq!(Tiny64Parts::from_u64(u64::MAX), max);
+    }
+
+    #[test]
+    fn test_to_id_validates_widths() {
+        let parts = Tiny64Parts {
+            timestamp_ms: 0,
+            sequence: MAX_SEQUENCE + 1,
+            random: 0,
+        };
+        assert_eq!(
+            parts.to_id(),
+            Err(PackError::SequenceOutOfRange {
+                value: MAX_SEQUENCE + 1,
+                max: MAX_SEQUENCE
+            })
+        );
+
+        let parts = Tiny64Parts {
+            timestamp_ms: MAX_TIMESTAMP_MS + 1,
+            sequence: 0,
+            random: 0,
+        };
+        assert!(matches!(
+            parts.to_id(),
+            Err(PackError::TimestampOutOfRange { .. })
+        ));
+    }
+}
@@ -0,0 +1,74 @@
+//! Priority-banded IDs for priority queues kept in a sorted store.
+//!
+//! Layout: `[ 8 bits: priority ] [ 42 bits: timestamp_ms ] [ 12 bits: sequence ] [ 2 bits: random ]`.
+//!
+//! The priority sits above the timestamp, so these IDs sort by priority
+//! first and by time only within a priority: every priority-0 ID sorts before
+//! every priority-1 ID, whenever either was minted. A store scanned in key
+//! order then pops the lowest band first and each band in FIFO order. That
+//! makes them the wrong choice anywhere IDs are expected to sort by time.
+//!
+//! The band displaces the low 8 random bits, as the hash prefix of
+//! [`generate_hash_prefixed`](crate::generate_hash_prefixed) does. The
+//! sequence still keeps one thread's IDs distinct, but two processes minting
+//! the same priority in the same millisecond slot collide with probability
+//! 1/4. Decode with [`decode_priority`]: the plain decoders read the band as
+//! part of the timestamp.
+
+#[cfg(feature = "std")]
+use alloc::string::String;
+
+use crate::decode::{ParseError, decode_u64};
+use crate::parts::Tiny64Parts;
+
+/// Width of the priority band
+const PRIORITY_BITS: u32 = 8;
+
+/// Generate an ID that sorts by `priority`, then by time
+///
+/// Lower priorities sort first.
+#[cfg(feature = "std")]
+pub fn generate_with_priority(priority: u8) -> String {
+    let value = crate::generate_tiny64_u64() >> PRIORITY_BITS;
+    crate::base64_encode_u64((priority as u64) << (64 - PRIORITY_BITS) | value)
+}
+
+/// Split an ID minted by [`generate_with_priority`] into its priority and
+/// its fields
+///
+/// Only the top 2 random bits survive, so `random` is a multiple of 256.
+pub fn decode_priority(id: &str) -> Result<(u8, Tiny64Parts), ParseError> {
+    let value = decode_u64(id)?;
+    let priority = (value >> (64 - PRIORITY_BITS)) as u8;
+    Ok((priority, Tiny64Parts::from_u64(value << PRIORITY_BITS)))
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority_sorts_before_time() {
+        let before = crate::generator::current_time_ms();
+        let mut ids = Vec::new();
+        for round in 0..50 {
+            for priority in [7, 0, 255, 3] {
+                ids.push((priority, round, generate_with_priority(priority)));
+            }
+        }
+
+        let mut sorted = ids.clone();
+        sorted.sort_by(|a, b| a.2.cmp(&b.2));
+        let order: Vec<(u8, u32)> = sorted.iter().map(|&(p, r, _)| (p, r)).collect();
+        let mut expected: Vec<(u8, u32)> = ids.iter().map(|&(p, r, _)| (p, r)).collect();
+        expected.sort();
+        assert_eq!(order, expected);
+
+        let (priority, parts) = decode_priority(&ids[0].2).unwrap();
+        assert_eq!(priority, 7);
+        assert!(parts.timestamp_ms >= before);
+        assert!(parts.timestamp_ms <= crate::generator::current_time_ms());
+        assert_eq!(parts.random % 256, 0);
+        assert_eq!(decode_priority("x"), Err(ParseError::InvalidLength(1)));
+    }
+}
@@ -0,0 +1,525 @@
+//! Fixed-width encodings over alternate alphabets.
+//!
+//! These trade length for a restricted character set. Each encoding is
+//! big-endian and zero-padded to a fixed width, so as long as the alphabet is
+//! in ASCII order the encoded strings sort exactly like the numeric values.
+//! The QR alphabet is the exception: it keeps the QR code's own ordering.
+
+use alloc::string::String;
+use alloc::vec;
+
+use crate::ParseError;
+
+/// 38-character alphabet that survives uppercasing: `-0-9A-Z_`
+pub const UPPERCASE_ALPHABET: &[u8; 38] = b"-0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ_";
+
+/// Length of an uppercase-safe ID (`38^13 > 2^64`)
+pub const UPPERCASE_ENCODED_LEN: usize = 13;
+
+/// The 45-character QR code alphanumeric charset, in QR (and Base45) order:
+/// `0-9A-Z $%*+-./:`
+pub const QR_ALPHABET: &[u8; 45] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+/// Length of a QR-alphanumeric ID (`45^12 > 2^64`)
+pub const QR_ENCODED_LEN: usize = 12;
+
+/// 37-character alphabet valid in DNS labels: `-0-9a-z`
+pub const DNS_ALPHABET: &[u8; 37] = b"-0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// Length of a DNS-safe ID
+pub const DNS_ENCODED_LEN: usize = 13;
+
+/// Lowercase hexadecimal digits
+pub const HEX_ALPHABET: &[u8; 16] = b"0123456789abcdef";
+
+/// Crockford's Base32 alphabet, which omits `I`, `L`, `O` and `U`
+pub const CROCKFORD_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// 40-character alphabet without the easily confused `0`, `1`, `I`, `O`,
+/// `i`, `l` and `o`: `2-9`, `A-Z` less `I` and `O`, and `abdefhnr`
+pub const UNAMBIGUOUS_ALPHABET: &[u8; 40] = b"23456789ABCDEFGHJKLMNPQRSTUVWXYZabdefhnr";
+
+/// Length of an unambiguous ID (`40^12 < 2^64 <= 40^13`)
+pub const UNAMBIGUOUS_ENCODED_LEN: usize = 13;
+
+/// The 62 ASCII letters and digits, in ASCII order: `0-9A-Za-z`
+pub const BASE62_ALPHABET: &[u8; 62] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Length of a Base62 ID (`62^10 < 2^64 <= 62^11`)
+pub const BASE62_ENCODED_LEN: usize = 11;
+
+const DECIMAL_ALPHABET: &[u8; 10] = b"0123456789";
+
+/// Number of digits needed to represent any u64 in the given radix
+pub(crate) const fn width_for_radix(radix: u64) -> usize {
+    let mut width = 1;
+    let mut max = u64::MAX / radix;
+    while max > 0 {
+        max /= radix;
+        width += 1;
+    }
+    width
+}
+
+/// Encode `value` as fixed-width big-endian digits over `alphabet`
+pub(crate) fn encode_with_alphabet(value: u64, alphabet: &[u8]) -> String {
+    let radix = alphabet.len() as u64;
+    let mut out = vec![alphabet[0]; width_for_radix(radix)];
+
+    let mut rest = value;
+    for slot in out.iter_mut().rev() {
+        *slot = alphabet[(rest % radix) as usize];
+        rest /= radix;
+    }
+
+    String::from_utf8(out).unwrap()
+}
+
+/// Decode a fixed-width string produced by [`encode_with_alphabet`]
+pub(crate) fn decode_with_alphabet(id: &str, alphabet: &[u8]) -> Result<u64, ParseError> {
+    let radix = alphabet.len() as u64;
+    let len = id.chars().count();
+    if len != width_for_radix(radix) {
+        return Err(ParseError::InvalidLength(len));
+    }
+
+    let mut value: u64 = 0;
+    for (position, ch) in id.chars().enumerate() {
+        let digit = ch
+            .is_ascii()
+            .then(|| alphabet.iter().position(|&c| c == ch as u8))
+            .flatten()
+            .ok_or(ParseError::InvalidChar { ch, position })?;
+
+        // The top digit can exceed what fits in 64 bits
+        value = value
+            .checked_mul(radix)
+            .and_then(|v| v.checked_add(digit as u64))
+            .ok_or(ParseError::Overflow)?;
+    }
+
+    Ok(value)
+}
+
+/// Encode a value with the uppercase-safe alphabet (13 characters)
+pub fn encode_uppercase_safe(value: u64) -> String {
+    encode_with_alphabet(value, UPPERCASE_ALPHABET)
+}
+
+/// Decode an uppercase-safe ID back into its u64 value
+pub fn decode_uppercase_safe(id: &str) -> Result<u64, ParseError> {
+    decode_with_alphabet(id, UPPERCASE_ALPHABET)
+}
+
+/// Generate a Tiny64 ID that is unchanged by uppercasing
+///
+/// Uses the 38-character `-0-9A-Z_` alphabet, so IDs are 13 characters long
+/// instead of 11. The alphabet is in ASCII order, so these IDs still sort
+/// chronologically.
+#[cfg(feature = "std")]
+pub fn generate_uppercase_safe() -> String {
+    encode_uppercase_safe(crate::generate_tiny64_u64())
+}
+
+/// Encode a value as a 13-character DNS label: `-0-9a-z`, never starting or
+/// ending with `-`
+///
+/// DNS labels may not begin or end with a hyphen, so the first and last
+/// characters are drawn from the 36 characters `0-9a-z` and the 11 in between
+/// from the full 37-character [`DNS_ALPHABET`]. Each position's alphabet is in
+/// ASCII order, so the labels still sort like the values they encode.
+pub fn encode_dns_safe(value: u64) -> String {
+    let mut out = vec![0u8; DNS_ENCODED_LEN];
+    let mut rest = value;
+    for (i, slot) in out.iter_mut().enumerate().rev() {
+        let alphabet = dns_alphabet_at(i);
+        let radix = alphabet.len() as u64;
+        *slot = alphabet[(rest % radix) as usize];
+        rest /= radix;
+    }
+
+    String::from_utf8(out).unwrap()
+}
+
+/// Decode a label produced by [`encode_dns_safe`], ignoring ASCII case
+pub fn decode_dns_safe(id: &str) -> Result<u64, ParseError> {
+    let len = id.chars().count();
+    if len != DNS_ENCODED_LEN {
+        return Err(ParseError::InvalidLength(len));
+    }
+
+    let mut value: u64 = 0;
+    for (position, ch) in id.chars().enumerate() {
+        let alphabet = dns_alphabet_at(position);
+        let digit = alphabet
+            .iter()
+            .position(|&c| c as char == ch.to_ascii_lowercase())
+            .ok_or(ParseError::InvalidChar { ch, position })?;
+
+        value = value
+            .checked_mul(alphabet.len() as u64)
+            .and_then(|v| v.checked_add(digit as u64))
+            .ok_or(ParseError::Overflow)?;
+    }
+
+    Ok(value)
+}
+
+/// The alphabet for position `i` of a DNS-safe ID; the ends exclude `-`
+fn dns_alphabet_at(i: usize) -> &'static [u8] {
+    if i == 0 || i == DNS_ENCODED_LEN - 1 {
+        &DNS_ALPHABET[1..]
+    } else {
+        DNS_ALPHABET
+    }
+}
+
+/// Generate a Tiny64 ID usable as a DNS label (e.g. a subdomain)
+///
+/// See [`encode_dns_safe`]. IDs are 13 characters instead of 11 and still
+/// sort chronologically.
+#[cfg(feature = "std")]
+pub fn generate_dns_safe() -> String {
+    encode_dns_safe(crate::generate_tiny64_u64())
+}
+
+/// Generate a Tiny64 ID usable as a file name on Windows, macOS and Linux
+///
+/// This is the DNS-safe encoding, which already meets every platform's rules:
+/// it is all lowercase, so IDs stay distinct on case-insensitive file systems
+/// (the default on Windows and macOS) where Base64's `a` and `A` would clash;
+/// it has none of the reserved `<>:"/\|?*` characters; it never starts with
+/// `-`, which command-line tools would read as an option; and at 13 characters
+/// with no dot it can't spell a reserved device name such as `CON` or `NUL`.
+/// Decode with [`decode_dns_safe`]. IDs still sort chronologically.
+#[cfg(feature = "std")]
+pub fn generate_filesystem_safe() -> String {
+    generate_dns_safe()
+}
+
+/// Generate a Tiny64 ID that is all lowercase and sorts like its value
+///
+/// Don't lowercase Base64 IDs instead: `.to_lowercase()` folds `A`-`Z` onto
+/// `a`-`z`, which both merges distinct IDs and moves them past `_`, so the
+/// sort order breaks as well. Only values whose every Base64 character avoids
+/// `A`-`Z` survive it, which no useful range of IDs does. This is the
+/// DNS-safe encoding, whose alphabet is lowercase from the start; decode with
+/// [`decode_dns_safe`].
+#[cfg(feature = "std")]
+pub fn generate_lowercase_sortable() -> String {
+    generate_dns_safe()
+}
+
+/// Encode a value as 16 zero-padded lowercase hex digits
+pub fn encode_hex(value: u64) -> String {
+    encode_with_alphabet(value, HEX_ALPHABET)
+}
+
+/// Decode 16 hex digits (either case) back into a u64
+pub fn decode_hex(id: &str) -> Result<u64, ParseError> {
+    decode_with_alphabet(&id.to_ascii_lowercase(), HEX_ALPHABET)
+}
+
+/// Encode a value as the hex of its little-endian bytes, for interop with
+/// systems that store IDs that way
+///
+/// The least significant byte comes first, so these strings do **not** sort
+/// like the values they encode; decode before comparing.
+pub fn encode_hex_le(value: u64) -> String {
+    encode_hex(value.swap_bytes())
+}
+
+/// Decode 16 hex digits (either case) of little-endian bytes back into a u64
+pub fn decode_hex_le(id: &str) -> Result<u64, ParseError> {
+    decode_hex(id).map(u64::swap_bytes)
+}
+
+/// Encode a value as 20 zero-padded decimal digits
+pub fn encode_decimal(value: u64) -> String {
+    encode_with_alphabet(value, DECIMAL_ALPHABET)
+}
+
+/// Decode 20 decimal digits back into a u64
+pub fn decode_decimal(id: &str) -> Result<u64, ParseError> {
+    decode_with_alphabet(id, DECIMAL_ALPHABET)
+}
+
+/// Encode a value as 13 characters of Crockford's Base32
+pub fn encode_crockford(value: u64) -> String {
+    encode_with_alphabet(value, CROCKFORD_ALPHABET)
+}
+
+/// Decode Crockford's Base32, ignoring case and reading `I`/`L` as `1` and
+/// `O` as `0` as the spec asks
+pub fn decode_crockford(id: &str) -> Result<u64, ParseError> {
+    let normalized: String = id
+        .chars()
+        .map(|ch| match ch.to_ascii_uppercase() {
+            'I' | 'L' => '1',
+            'O' => '0',
+            other => other,
+        })
+        .collect();
+    decode_with_alphabet(&normalized, CROCKFORD_ALPHABET)
+}
+
+/// Encode a value with the unambiguous alphabet (13 characters)
+pub fn encode_unambiguous(value: u64) -> String {
+    encode_with_alphabet(value, UNAMBIGUOUS_ALPHABET)
+}
+
+/// Decode an unambiguous ID back into its u64 value
+///
+/// Case matters: the alphabet holds both `A` and `a`.
+pub fn decode_unambiguous(id: &str) -> Result<u64, ParseError> {
+    decode_with_alphabet(id, UNAMBIGUOUS_ALPHABET)
+}
+
+/// Generate a Tiny64 ID meant to be read aloud or copied by hand
+///
+/// Uses [`UNAMBIGUOUS_ALPHABET`], which drops the characters most often
+/// misread (`0`/`O`/`o`, `1`/`I`/`i`/`l`). With 40 characters instead of 64,
+/// IDs are 13 characters long instead of 11. The alphabet is in ASCII order,
+/// so these IDs still sort chronologically. It is not case-insensitive: the
+/// remaining lowercase letters were kept for being visually distinct, so say
+/// "lowercase" when spelling one out.
+#[cfg(feature = "std")]
+pub fn generate_unambiguous() -> String {
+    encode_unambiguous(crate::generate_tiny64_u64())
+}
+
+/// Encode a value as 11 alphanumeric characters, for systems that accept
+/// only `[0-9A-Za-z]`
+///
+/// As short as Base64, since `62^11` still exceeds `2^64`, and the alphabet
+/// is in ASCII order, so the strings sort like the values. 62 is not a power
+/// of two, though, so each digit takes a 64-bit division rather than a shift
+/// and mask, which makes this several times slower than
+/// [`base64_encode_u64`](crate::base64_encode_u64).
+pub fn encode_base62(value: u64) -> String {
+    encode_with_alphabet(value, BASE62_ALPHABET)
+}
+
+/// Decode a Base62 ID back into its u64 value
+///
+/// Case matters: `a` and `A` are different digits.
+pub fn decode_base62(id: &str) -> Result<u64, ParseError> {
+    decode_with_alphabet(id, BASE62_ALPHABET)
+}
+
+/// Generate a Tiny64 ID using only ASCII letters and digits
+///
+/// The 11-character [`encode_base62`] form, which sorts chronologically.
+#[cfg(feature = "std")]
+pub fn generate_base62() -> String {
+    encode_base62(crate::generate_tiny64_u64())
+}
+
+/// Encode a value over the QR alphanumeric charset (12 characters)
+///
+/// QR codes store this charset at 5.5 bits per character instead of 8 in
+/// byte mode, so a printed label fits in a smaller symbol. The charset is not
+/// in ASCII order (digits sort after the space and symbols), so these strings
+/// do not sort like the values they encode; decode before comparing.
+pub fn encode_qr(value: u64) -> String {
+    encode_with_alphabet(value, QR_ALPHABET)
+}
+
+/// Decode a string produced by [`encode_qr`] back into its u64 value
+pub fn decode_qr(id: &str) -> Result<u64, ParseError> {
+    decode_with_alphabet(id, QR_ALPHABET)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uppercase_safe_roundtrip() {
+        assert_eq!(width_for_radix(38), UPPERCASE_ENCODED_LEN);
+        for value in [0, 1, 0x123456789ABCDEF0, u64::MAX] {
+            let id = encode_uppercase_safe(value);
+            assert_eq!(id.len(), UPPERCASE_ENCODED_LEN);
+            assert_eq!(id.to_uppercase(), id);
+            assert_eq!(decode_uppercase_safe(&id), Ok(value));
+        }
+        assert_eq!(encode_uppercase_safe(0), "-------------");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_uppercase_safe_ordering() {
+        let ids: Vec<String> = (0..100).map(|_| generate_uppercase_safe()).collect();
+        for pair in ids.windows(2) {
+            assert!(pair[0] <= pair[1]);
+        }
+        assert!(encode_uppercase_safe(37) < encode_uppercase_safe(38));
+    }
+
+    #[test]
+    fn test_decode_rejects_overflow() {
+        assert_eq!(
+            decode_uppercase_safe("_____________"),
+            Err(ParseError::Overflow)
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_base62_roundtrip_and_order() {
+        for value in [0, 61, 62, 0x123456789ABCDEF0, u64::MAX] {
+            let id = encode_base62(value);
+            assert_eq!(id.len(), BASE62_ENCODED_LEN);
+            assert!(id.bytes().all(|b| b.is_ascii_alphanumeric()));
+            assert_eq!(decode_base62(&id), Ok(value));
+        }
+        assert_eq!(encode_base62(0), "00000000000");
+        assert_eq!(encode_base62(61), "0000000000z");
+        assert_eq!(encode_base62(u64::MAX), "LygHa16AHYF");
+
+        let ids: Vec<String> = (0..100).map(|_| generate_base62()).collect();
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+
+        assert_eq!(
+            decode_base62("0000000000-"),
+            Err(ParseError::InvalidChar {
+                ch: '-',
+                position: 10
+            })
+        );
+        assert_eq!(decode_base62("zzzzzzzzzzz"), Err(ParseError::Overflow));
+    }
+
+    #[test]
+    fn test_qr_roundtrip() {
+        assert_eq!(width_for_radix(45), QR_ENCODED_LEN);
+        for value in [0, 1, 0x123456789ABCDEF0, u64::MAX] {
+            let id = encode_qr(value);
+            assert_eq!(id.len(), QR_ENCODED_LEN);
+            assert!(id.bytes().all(|b| QR_ALPHABET.contains(&b)));
+            assert_eq!(decode_qr(&id), Ok(value));
+        }
+        assert_eq!(encode_qr(0), "000000000000");
+
+        // 36 encodes as ' ', which sorts before '1'
+        assert!(encode_qr(36) < encode_qr(1));
+        assert!(matches!(
+            decode_qr("00000000000a"),
+            Err(ParseError::InvalidChar { ch: 'a', .. })
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_dns_safe_roundtrip() {
+        for value in [0, 1, 36, 37, 0x123456789ABCDEF0, u64::MAX] {
+            let id = encode_dns_safe(value);
+            assert_eq!(id.len(), DNS_ENCODED_LEN);
+            assert!(!id.starts_with('-') && !id.ends_with('-'));
+            assert!(id.bytes().all(|b| DNS_ALPHABET.contains(&b)));
+            assert_eq!(decode_dns_safe(&id), Ok(value));
+            assert_eq!(decode_dns_safe(&id.to_uppercase()), Ok(value));
+        }
+        assert_eq!(encode_dns_safe(0), "0-----------0");
+
+        let ids: Vec<String> = (0..100).map(|_| generate_dns_safe()).collect();
+        for pair in ids.windows(2) {
+            assert!(pair[0] < pair[1]);
+        }
+        assert!(matches!(
+            decode_dns_safe("-------------"),
+            Err(ParseError::InvalidChar { position: 0, .. })
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_unambiguous_roundtrip() {
+        assert_eq!(width_for_radix(40), UNAMBIGUOUS_ENCODED_LEN);
+        assert!(
+            UNAMBIGUOUS_ALPHABET
+                .windows(2)
+                .all(|pair| pair[0] < pair[1])
+        );
+        for value in [0, 1, 0x123456789ABCDEF0, u64::MAX] {
+            let id = encode_unambiguous(value);
+            assert_eq!(id.len(), UNAMBIGUOUS_ENCODED_LEN);
+            assert!(!id.contains(['0', '1', 'I', 'O', 'i', 'l', 'o']));
+            assert_eq!(decode_unambiguous(&id), Ok(value));
+        }
+        assert_eq!(encode_unambiguous(0), "2222222222222");
+
+        let ids: Vec<String> = (0..100).map(|_| generate_unambiguous()).collect();
+        for pair in ids.windows(2) {
+            assert!(pair[0] < pair[1]);
+        }
+        assert!(matches!(
+            decode_unambiguous("222222222222O"),
+            Err(ParseError::InvalidChar { ch: 'O', .. })
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_filesystem_safe() {
+        let ids: Vec<String> = (0..100).map(|_| generate_filesystem_safe()).collect();
+        for pair in ids.windows(2) {
+            assert!(pair[0] < pair[1]);
+        }
+        for id in &ids {
+            assert_eq!(id.len(), DNS_ENCODED_LEN);
+            assert_eq!(&id.to_lowercase(), id);
+            assert!(!id.starts_with('-'));
+            assert!(!id.contains(['<', '>', ':', '"', '/', '\\', '|', '?', '*', '.']));
+            assert!(decode_dns_safe(id).is_ok());
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_lowercase_sortable() {
+        let mut values: Vec<u64> = (0..200).map(crate::hash::mix64).collect();
+        values.push(u64::MAX);
+        values.push(0);
+        let mut ids: Vec<String> = values.iter().map(|&v| encode_dns_safe(v)).collect();
+        values.sort();
+        ids.sort();
+        let decoded: Vec<u64> = ids.iter().map(|id| decode_dns_safe(id).unwrap()).collect();
+        assert_eq!(decoded, values);
+
+        let ids: Vec<String> = (0..100).map(|_| generate_lowercase_sortable()).collect();
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(ids.iter().all(|id| id.to_lowercase() == *id));
+
+        // What lowercasing Base64 does to order: `A` sorts below `_`, `a` above
+        let (a, b) = (
+            crate::base64_encode_u64(11 << 4),
+            crate::base64_encode_u64(37 << 4),
+        );
+        assert!(a < b);
+        assert!(a.to_lowercase() > b.to_lowercase());
+    }
+
+    #[test]
+    fn test_hex_decimal_crockford_roundtrip() {
+        for value in [0, 1, 0x123456789ABCDEF0, u64::MAX] {
+            assert_eq!(decode_hex(&encode_hex(value)), Ok(value));
+            assert_eq!(decode_decimal(&encode_decimal(value)), Ok(value));
+            assert_eq!(decode_crockford(&encode_crockford(value)), Ok(value));
+        }
+        assert_eq!(encode_hex(0xABC), "0000000000000abc");
+        assert_eq!(encode_hex_le(0x0123_4567_89AB_CDEF), "efcdab8967452301");
+        assert_eq!(decode_hex_le("EFCDAB8967452301"), Ok(0x0123_4567_89AB_CDEF));
+        assert_eq!(
+            decode_hex_le(&encode_hex_le(u64::MAX - 1)),
+            Ok(u64::MAX - 1)
+        );
+        // Little-endian order breaks sorting: 256 encodes below 1
+        assert!(encode_hex_le(256) < encode_hex_le(1));
+        assert_eq!(encode_decimal(u64::MAX), "18446744073709551615");
+        assert_eq!(encode_crockford(u64::MAX), "FZZZZZZZZZZZZ");
+        assert_eq!(decode_crockford("o00000000000l"), Ok(1));
+        assert!(encode_crockford(1 << 40) < encode_crockford((1 << 40) + 1));
+    }
+}
@@ -0,0 +1,212 @@
+//! Sources of the random bits packed into each ID.
+
+#[cfg(feature = "std")]
+use std::collections::hash_map::RandomState;
+#[cfg(feature = "std")]
+use std::hash::{BuildHasher, Hasher};
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::hash::mix64;
+
+/// A source of random bits for [`Tiny64Generator`](crate::Tiny64Generator)
+pub trait RandomSource {
+    /// Return a value whose low `n` bits (`n <= 64`) are random and whose
+    /// remaining bits are zero
+    fn next_bits(&mut self, n: u32) -> u64;
+
+    /// How many of `n` drawn bits an observer cannot predict
+    ///
+    /// Defaults to `n`, i.e. every bit is unpredictable. Sources whose output
+    /// can be guessed should override it.
+    fn entropy_bits(&self, n: u32) -> f64 {
+        n as f64
+    }
+}
+
+/// All-ones mask of the given width (0..=64 bits)
+fn low_bits(bits: u32) -> u64 {
+    u64::MAX.checked_shr(64 - bits).unwrap_or(0)
+}
+
+/// Generate a 10-bit random value using RandomState
+#[cfg(feature = "std")]
+pub(crate) fn generate_random_10bit() -> u16 {
+    generate_random_bits(10) as u16
+}
+
+/// Generate a random value of up to 64 bits using RandomState
+#[cfg(feature = "std")]
+pub(crate) fn generate_random_bits(bits: u32) -> u64 {
+    let random_state = RandomState::new();
+    let mut hasher = random_state.build_hasher();
+
+    // Add some entropy from current time nanos
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .subsec_nanos();
+
+    hasher.write_u32(nanos);
+    let hash = hasher.finish();
+
+    // Take the requested number of low bits
+    hash & low_bits(bits)
+}
+
+/// The default source: std's randomly keyed SipHash over the current nanos
+///
+/// Needs no dependencies. It is unpredictable enough to spread IDs across the
+/// random field, but is not a cryptographic RNG. Requires the `std` feature.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HashRandom;
+
+#[cfg(feature = "std")]
+impl RandomSource for HashRandom {
+    fn next_bits(&mut self, n: u32) -> u64 {
+        generate_random_bits(n)
+    }
+}
+
+/// Always returns the same bits; for tests and reproducible fixtures
+#[derive(Debug, Clone, Copy)]
+pub struct FixedRandom {
+    value: u64,
+}
+
+impl FixedRandom {
+    pub fn new(value: u64) -> Self {
+        FixedRandom { value }
+    }
+}
+
+impl RandomSource for FixedRandom {
+    fn next_bits(&mut self, n: u32) -> u64 {
+        self.value & low_bits(n)
+    }
+
+    fn entropy_bits(&self, _n: u32) -> f64 {
+        0.0
+    }
+}
+
+/// A deterministic SplitMix64 stream: the same seed yields the same bits
+///
+/// For golden tests, simulations and replaying a known sequence. Anyone who
+/// learns the seed can predict every draw.
+#[derive(Debug, Clone, Copy)]
+pub struct SeededRandom {
+    state: u64,
+}
+
+impl SeededRandom {
+    pub fn new(seed: u64) -> Self {
+        SeededRandom { state: seed }
+    }
+}
+
+impl RandomSource for SeededRandom {
+    fn next_bits(&mut self, n: u32) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        mix64(self.state) & low_bits(n)
+    }
+
+    fn entropy_bits(&self, _n: u32) -> f64 {
+        0.0
+    }
+}
+
+/// Wraps another source and mixes a fixed per-node salt into every draw
+///
+/// Nodes that can't be given distinct node IDs can instead be given distinct
+/// salts, so that even sources which would otherwise produce the same stream
+/// on every node (a fixed seed, a coarse clock) diverge. This only helps if
+/// each node's salt is different; nodes sharing a salt are no better off. A
+/// source that is already independent per process, like [`HashRandom`], is
+/// not weakened by salting but gains little from it.
+#[derive(Debug, Clone, Copy)]
+pub struct SaltedRandom<R> {
+    inner: R,
+    salt: u64,
+}
+
+impl<R: RandomSource> SaltedRandom<R> {
+    pub fn new(inner: R, salt: u64) -> Self {
+        SaltedRandom { inner, salt }
+    }
+}
+
+impl<R: RandomSource> RandomSource for SaltedRandom<R> {
+    fn next_bits(&mut self, n: u32) -> u64 {
+        mix64(self.inner.next_bits(64) ^ self.salt) & low_bits(n)
+    }
+
+    // The salt is fixed per node, so it hides nothing from whoever knows it
+    fn entropy_bits(&self, n: u32) -> f64 {
+        self.inner.entropy_bits(n)
+    }
+}
+
+/// Random bits from the operating system's CSPRNG via `getrandom`
+///
+/// Requires the `getrandom` feature.
+#[cfg(feature = "getrandom")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsRandom;
+
+#[cfg(feature = "getrandom")]
+impl RandomSource for OsRandom {
+    fn next_bits(&mut self, n: u32) -> u64 {
+        let mut bytes = [0u8; 8];
+        getrandom::fill(&mut bytes).expect("operating system RNG failed");
+        u64::from_ne_bytes(bytes) & low_bits(n)
+    }
+}
+
+#[cfg(all(test, any(feature = "std", feature = "getrandom")))]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_sources_respect_width() {
+        let mut fixed = FixedRandom::new(u64::MAX);
+        assert_eq!(fixed.next_bits(10), 0x3FF);
+        assert_eq!(fixed.next_bits(64), u64::MAX);
+        assert_eq!(fixed.next_bits(0), 0);
+        assert_eq!(fixed.entropy_bits(10), 0.0);
+
+        let mut hash = HashRandom;
+        for _ in 0..100 {
+            assert!(hash.next_bits(10) <= 0x3FF);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_salts_diverge() {
+        // Same underlying stream, different salts
+        let draw = |salt| {
+            let mut source = SaltedRandom::new(FixedRandom::new(42), salt);
+            (0..4).map(|_| source.next_bits(10)).collect::<Vec<_>>()
+        };
+        assert_ne!(draw(1), draw(2));
+        assert_eq!(draw(1), draw(1));
+        assert!(draw(2).iter().all(|&bits| bits <= 0x3FF));
+        assert_eq!(
+            SaltedRandom::new(FixedRandom::new(42), 1).entropy_bits(10),
+            0.0
+        );
+        assert_eq!(SaltedRandom::new(HashRandom, 1).entropy_bits(10), 10.0);
+    }
+
+    #[cfg(feature = "getrandom")]
+    #[test]
+    fn test_os_random_respects_width() {
+        let mut os = OsRandom;
+        for _ in 0..100 {
+            assert!(os.next_bits(12) <= 0xFFF);
+        }
+    }
+}
@@ -0,0 +1,391 @@
+//! Bounds and queries over time ranges of IDs.
+//!
+//! Because IDs sort lexically in time order, the smallest and largest IDs a
+//! millisecond can contain bracket every ID minted in it, and a sorted list
+//! of IDs can be searched by comparing strings without decoding them.
+
+use alloc::string::String;
+
+use crate::decode::{ParseError, decode_u64, timestamp_of};
+use crate::encode::base64_encode_u64;
+use crate::error::Tiny64Error;
+use crate::parts::{Tiny64Parts, pack_masked};
+use crate::{MAX_RANDOM, MAX_SEQUENCE, MAX_TIMESTAMP_MS};
+
+/// The smallest ID that can be minted in the given millisecond
+pub fn min_id_at(timestamp_ms: u64) -> Result<String, Tiny64Error> {
+    if timestamp_ms > MAX_TIMESTAMP_MS {
+        return Err(Tiny64Error::TimestampOutOfRange(timestamp_ms));
+    }
+    Ok(base64_encode_u64(pack_masked(timestamp_ms, 0, 0)))
+}
+
+/// The largest ID that can be minted in the given millisecond
+pub fn max_id_at(timestamp_ms: u64) -> Result<String, Tiny64Error> {
+    if timestamp_ms > MAX_TIMESTAMP_MS {
+        return Err(Tiny64Error::TimestampOutOfRange(timestamp_ms));
+    }
+    Ok(base64_encode_u64(pack_masked(
+        timestamp_ms,
+        MAX_SEQUENCE,
+        MAX_RANDOM,
+    )))
+}
+
+#[cfg(feature = "std")]
+const MS_PER_DAY: u64 = 86_400_000;
+
+/// First and last millisecond of the UTC day containing `timestamp_ms`
+#[cfg(feature = "std")]
+fn utc_day_span(timestamp_ms: u64) -> (u64, u64) {
+    let start = timestamp_ms - timestamp_ms % MS_PER_DAY;
+    (start, start + MS_PER_DAY - 1)
+}
+
+/// The smallest and largest IDs that can be minted during the current day
+///
+/// The day runs from midnight to midnight UTC, not local time; the Unix epoch
+/// has no leap seconds, so every day is exactly 86,400,000 ms. Both bounds are
+/// inclusive: an ID was minted today if `min <= id && id <= max`.
+#[cfg(feature = "std")]
+pub fn today_range() -> (String, String) {
+    today_range_at(crate::generator::current_time_ms())
+}
+
+/// [`today_range`] for the UTC day containing `now_ms`
+#[cfg(feature = "std")]
+fn today_range_at(now_ms: u64) -> (String, String) {
+    let (start, end) = utc_day_span(now_ms);
+    let bound = |result: Result<String, Tiny64Error>| {
+        result.expect("current time exceeds the timestamp field")
+    };
+    (bound(min_id_at(start)), bound(max_id_at(end)))
+}
+
+/// Every sequence-distinct ID of one millisecond, in order
+///
+/// Yields exactly [`MAX_IDS_PER_MS`](crate::MAX_IDS_PER_MS) IDs, one per
+/// sequence number from 0 up, all stamped `timestamp_ms` with the random
+/// field fixed at 0. Meant for exhaustive tests of ordering and coverage
+/// over a narrow window. Fails if `timestamp_ms` doesn't fit in 42 bits.
+pub fn ids_in_ms(timestamp_ms: u64) -> Result<impl Iterator<Item = String>, Tiny64Error> {
+    if timestamp_ms > MAX_TIMESTAMP_MS {
+        return Err(Tiny64Error::TimestampOutOfRange(timestamp_ms));
+    }
+    Ok((0..=MAX_SEQUENCE).map(move |sequence| {
+        Tiny64Parts {
+            timestamp_ms,
+            sequence,
+            random: 0,
+        }
+        .to_id()
+        .expect("fields are in range")
+    }))
+}
+
+/// The largest ID that can be minted in the current millisecond
+///
+/// A cursor for "everything up to now" queries: every ID minted in this
+/// millisecond has a sequence and random value no greater than the
+/// bookmark's, so it sorts at or below it whether it was written before this
+/// call or concurrently after it. A page that ends at `id <= bookmark_now()`
+/// therefore never splits a millisecond, and the next page can start strictly
+/// after the bookmark without skipping a same-millisecond write.
+#[cfg(feature = "std")]
+pub fn bookmark_now() -> String {
+    max_id_at(crate::generator::current_time_ms())
+        .expect("current time exceeds the timestamp field")
+}
+
+/// The ID sorting immediately before `id`, or `None` if `id` is the smallest
+///
+/// No valid ID sorts strictly between the two, so this is the inclusive form
+/// of an exclusive upper bound: `id < x` is the same as `x <= predecessor`.
+pub fn predecessor(id: &str) -> Result<Option<String>, ParseError> {
+    Ok(decode_u64(id)?.checked_sub(1).map(base64_encode_u64))
+}
+
+/// The ID sorting immediately after `id`, or `None` if `id` is the largest
+///
+/// No valid ID sorts strictly between the two, so this is the inclusive form
+/// of an exclusive lower bound: `x > id` is the same as `x >= successor`.
+pub fn successor(id: &str) -> Result<Option<String>, ParseError> {
+    Ok(decode_u64(id)?.checked_add(1).map(base64_encode_u64))
+}
+
+/// The smallest ID after `id` that a generator could still mint, or `None`
+/// if `id` is the largest
+///
+/// The larger of [`successor`] and the smallest ID of the current
+/// millisecond. Everything this process mints from now on sorts at or above
+/// it, so it is a lower bound for "what's new since `id`" that skips the gap
+/// of IDs nobody can mint any more when `id` is old. It is not a cursor over
+/// concurrent writes: an ID minted by a thread or host that read its clock
+/// before this call, or whose clock runs behind ours, can still land between
+/// `id` and the result. Poll with [`successor`] alone where such writes matter.
+#[cfg(feature = "std")]
+pub fn next_after(id: &str) -> Result<Option<String>, ParseError> {
+    let Some(next) = decode_u64(id)?.checked_add(1) else {
+        return Ok(None);
+    };
+    let now = crate::generator::current_time_ms().min(MAX_TIMESTAMP_MS);
+    Ok(Some(base64_encode_u64(next.max(pack_masked(now, 0, 0)))))
+}
+
+/// Estimate throughput in IDs per second from the first and last of `count`
+/// IDs minted in a run
+///
+/// Divides `count` by the time between the two IDs' timestamps, in either
+/// order. Timestamps only have millisecond resolution, so the span is rounded
+/// up to at least 1 ms: for a run within a single millisecond this returns
+/// `count * 1000`, a lower bound on the true rate, rather than dividing by
+/// zero.
+pub fn estimated_rate(first: &str, last: &str, count: usize) -> Result<f64, ParseError> {
+    let span_ms = timestamp_of(first)?.abs_diff(timestamp_of(last)?).max(1);
+    Ok(count as f64 * 1000.0 / span_ms as f64)
+}
+
+/// Count the IDs in `sorted_ids` minted between `start_ms` and `end_ms`,
+/// both inclusive
+///
+/// Runs two binary searches against [`min_id_at`] and [`max_id_at`], so no
+/// entry is decoded. `sorted_ids` must be in lexical order; malformed entries
+/// are counted wherever they happen to sort.
+pub fn count_in_range(sorted_ids: &[String], start_ms: u64, end_ms: u64) -> usize {
+    if start_ms > end_ms || start_ms > MAX_TIMESTAMP_MS {
+        return 0;
+    }
+    let lower = min_id_at(start_ms).unwrap();
+    let upper = max_id_at(end_ms.min(MAX_TIMESTAMP_MS)).unwrap();
+
+    let first = sorted_ids.partition_point(|id| *id < lower);
+    let last = sorted_ids.partition_point(|id| *id <= upper);
+    last - first
+}
+
+/// The current time bucket as a short sortable string, for the first part of
+/// a composite key in a store that hashes keys
+///
+/// Time is cut into buckets of `granularity_ms` milliseconds and the bucket
+/// number is written in the ID alphabet, padded to the width the largest
+/// bucket needs, so prefixes of one granularity all have the same length
+/// and sort in time order: one hour gives 4 characters, one day 3.
+///
+/// Hashing a full ID scatters neighbouring IDs, so a store that hashes keys
+/// loses their order. Keying records by `(time_bucket_prefix(g), id)`
+/// brings back coarse order: the store hashes only the prefix, every record
+/// minted in one bucket lands in the same place, and within a bucket the ID
+/// itself is the sort key. Call this right before minting the ID; the two
+/// read the clock separately, so an ID minted on a bucket boundary may be
+/// filed under the bucket before its own.
+///
+/// # Panics
+///
+/// Panics if `granularity_ms` is 0.
+#[cfg(feature = "std")]
+pub fn time_bucket_prefix(granularity_ms: u64) -> String {
+    bucket_prefix(crate::generator::current_time_ms(), granularity_ms)
+}
+
+/// The bucket number of `timestamp_ms`, fixed-width in the ID alphabet
+#[cfg(feature = "std")]
+fn bucket_prefix(timestamp_ms: u64, granularity_ms: u64) -> String {
+    assert!(granularity_ms > 0, "bucket granularity must be positive");
+    let bucket = timestamp_ms.min(MAX_TIMESTAMP_MS) / granularity_ms;
+    let bits = u64::BITS - (MAX_TIMESTAMP_MS / granularity_ms).leading_zeros();
+    let width = bits.div_ceil(6).max(1);
+    (0..width)
+        .rev()
+        .map(|digit| {
+            crate::encode::BASE64_ALPHABET[((bucket >> (digit * 6)) & 0x3F) as usize] as char
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode_tiny64;
+    #[cfg(feature = "std")]
+    use crate::generate_n_at;
+    #[cfg(feature = "std")]
+    use crate::parts::id_from_fields;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_ids_in_ms() {
+        let ids: Vec<String> = ids_in_ms(1_000_000).unwrap().collect();
+        assert_eq!(ids.len(), crate::MAX_IDS_PER_MS);
+        assert_eq!(crate::verify_strictly_increasing(&ids), Ok(()));
+        assert_eq!(ids[0], min_id_at(1_000_000).unwrap());
+        assert!(ids.iter().all(|id| timestamp_of(id) == Ok(1_000_000)));
+        assert_eq!(decode_tiny64(&ids[4095]).unwrap().sequence, 4095);
+
+        assert!(ids_in_ms(MAX_TIMESTAMP_MS).is_ok());
+        assert!(matches!(
+            ids_in_ms(MAX_TIMESTAMP_MS + 1),
+            Err(Tiny64Error::TimestampOutOfRange(_))
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_bookmark_now() {
+        let before = crate::generate_tiny64();
+        let bookmark = bookmark_now();
+        assert!(before <= bookmark);
+        let parts = decode_tiny64(&bookmark).unwrap();
+        assert_eq!((parts.sequence, parts.random), (MAX_SEQUENCE, MAX_RANDOM));
+        assert!(timestamp_of(&before).unwrap() <= parts.timestamp_ms);
+    }
+
+    #[test]
+    fn test_id_bounds() {
+        assert_eq!(min_id_at(0).unwrap(), "-----------");
+        assert_eq!(max_id_at(MAX_TIMESTAMP_MS).unwrap(), "zzzzzzzzzzw");
+        assert!(max_id_at(999).unwrap() < min_id_at(1_000).unwrap());
+        assert_eq!(
+            min_id_at(MAX_TIMESTAMP_MS + 1),
+            Err(Tiny64Error::TimestampOutOfRange(MAX_TIMESTAMP_MS + 1))
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_count_in_range() {
+        // 10 IDs at each of t=1000, 1001, ..., 1009
+        let ids: Vec<String> = (0..10)
+            .flat_map(|i| generate_n_at(1_000 + i, 10).unwrap())
+            .collect();
+
+        assert_eq!(count_in_range(&ids, 1_000, 1_009), 100);
+        assert_eq!(count_in_range(&ids, 1_003, 1_003), 10);
+        assert_eq!(count_in_range(&ids, 1_002, 1_005), 40);
+        assert_eq!(count_in_range(&ids, 1_008, u64::MAX), 20);
+        assert_eq!(count_in_range(&ids, 0, 999), 0);
+        assert_eq!(count_in_range(&ids, 1_005, 1_004), 0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_predecessor_successor() {
+        let id = crate::generate_tiny64();
+        let value = decode_u64(&id).unwrap();
+        let next = successor(&id).unwrap().unwrap();
+        let prev = predecessor(&id).unwrap().unwrap();
+        assert!(prev < id && id < next);
+        assert_eq!(decode_u64(&next).unwrap(), value + 1);
+        assert_eq!(predecessor(&next).unwrap().as_ref(), Some(&id));
+
+        // Nothing fits between an ID and its successor, even across the
+        // 4-bit final character
+        let edge = base64_encode_u64(0xF);
+        let after = successor(&edge).unwrap().unwrap();
+        assert_eq!(
+            (edge.as_str(), after.as_str()),
+            ("----------w", "---------0-")
+        );
+
+        assert_eq!(predecessor("-----------"), Ok(None));
+        assert_eq!(successor("zzzzzzzzzzw"), Ok(None));
+        assert_eq!(successor("abc"), Err(ParseError::InvalidLength(3)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_next_after() {
+        let old = min_id_at(1_000).unwrap();
+        let next = next_after(&old).unwrap().unwrap();
+        let now = crate::generator::current_time_ms();
+        assert!(timestamp_of(&next).unwrap() <= now);
+        assert!(timestamp_of(&next).unwrap() + 1_000 > now);
+        assert_eq!(decode_u64(&next).unwrap() % (1 << 22), 0);
+        assert!(next <= crate::generate_tiny64());
+
+        // An ID from the future only advances by one
+        let ahead = max_id_at(now + 60_000).unwrap();
+        assert_eq!(next_after(&ahead).unwrap(), successor(&ahead).unwrap());
+
+        assert_eq!(next_after("zzzzzzzzzzw"), Ok(None));
+        assert_eq!(next_after("abc"), Err(ParseError::InvalidLength(3)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_estimated_rate() {
+        let first = min_id_at(1_000).unwrap();
+        let last = max_id_at(1_500).unwrap();
+        assert_eq!(estimated_rate(&first, &last, 1_000), Ok(2_000.0));
+        assert_eq!(estimated_rate(&last, &first, 1_000), Ok(2_000.0));
+
+        // Same millisecond: treated as a 1 ms span
+        let ids = generate_n_at(1_000, 50).unwrap();
+        assert_eq!(estimated_rate(&ids[0], &ids[49], 50), Ok(50_000.0));
+        assert_eq!(
+            estimated_rate("abc", &last, 1),
+            Err(ParseError::InvalidLength(3))
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_today_range() {
+        // 2024-03-10T00:00:00Z, its last millisecond, and the next midnight
+        let midnight = 1_710_028_800_000;
+        assert_eq!(
+            utc_day_span(midnight),
+            (midnight, midnight + MS_PER_DAY - 1)
+        );
+        assert_eq!(
+            utc_day_span(midnight + MS_PER_DAY - 1),
+            (midnight, midnight + MS_PER_DAY - 1)
+        );
+        assert_eq!(utc_day_span(midnight + MS_PER_DAY).0, midnight + MS_PER_DAY);
+
+        let last = midnight + MS_PER_DAY - 1;
+        let day = (min_id_at(midnight).unwrap(), max_id_at(last).unwrap());
+        for now in [midnight, midnight + 12 * 3_600_000, last] {
+            assert_eq!(today_range_at(now), day);
+        }
+        let (min, max) = today_range_at(last);
+        let latest = id_from_fields(last, MAX_SEQUENCE, MAX_RANDOM);
+        assert!(min <= latest && latest <= max);
+        assert!(id_from_fields(last + 1, 0, 0) > max);
+        assert!(id_from_fields(midnight - 1, MAX_SEQUENCE, MAX_RANDOM) < min);
+
+        // The public wrapper reads the clock once, so its bounds always
+        // span one whole day
+        let (min, max) = today_range();
+        let start = decode_tiny64(&min).unwrap().timestamp_ms;
+        assert_eq!(start % MS_PER_DAY, 0);
+        assert_eq!(
+            decode_tiny64(&max).unwrap().timestamp_ms,
+            start + MS_PER_DAY - 1
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_time_bucket_prefix() {
+        const HOUR: u64 = 3_600_000;
+        let t = 1_700_000_000_000;
+        assert_eq!(bucket_prefix(t, HOUR).len(), 4);
+        assert_eq!(bucket_prefix(t, 86_400_000).len(), 3);
+        assert_eq!(bucket_prefix(t, 1).len(), 7);
+        assert_eq!(bucket_prefix(0, MAX_TIMESTAMP_MS + 1), "-");
+
+        let start = t - t % HOUR;
+        assert_eq!(
+            bucket_prefix(start, HOUR),
+            bucket_prefix(start + HOUR - 1, HOUR)
+        );
+        assert!(bucket_prefix(start - 1, HOUR) < bucket_prefix(start, HOUR));
+        assert!(bucket_prefix(start + HOUR, HOUR) > bucket_prefix(start, HOUR));
+        assert!(bucket_prefix(0, HOUR) < bucket_prefix(MAX_TIMESTAMP_MS, HOUR));
+
+        let before = bucket_prefix(crate::generator::current_time_ms(), HOUR);
+        let prefix = time_bucket_prefix(HOUR);
+        let after = bucket_prefix(crate::generator::current_time_ms(), HOUR);
+        assert!(before <= prefix && prefix <= after);
+    }
+}
@@ -0,0 +1,112 @@
+//! Seconds-resolution variant of the 64-bit format.
+//!
+//! Layout: `[ 32 bits: timestamp (s since Unix epoch) ] [ 16 bits: sequence ] [ 16 bits: random ]`,
+//! encoded as 11 Base64 characters like a regular Tiny64 ID.
+//!
+//! Trading millisecond resolution for a narrower timestamp leaves room for
+//! 65536 IDs per second per thread and 16 random bits. The timestamp covers
+//! 1970 through early 2106 (about 136 years). Ordering is only by second:
+//! IDs minted within the same second sort by their sequence number, and there
+//! is no finer notion of time to recover.
+//!
+//! Nothing in the string marks which variant produced it, and a seconds ID
+//! decoded with [`decode_tiny64`](crate::decode_tiny64) yields meaningless
+//! fields. Keep the two in separate columns, and decode these with
+//! [`timestamp_secs_of`].
+
+use std::cell::Cell;
+use std::time::Duration;
+
+use crate::decode::{ParseError, decode_u64};
+use crate::encode::base64_encode_u64;
+use crate::generator::current_time_ms;
+use crate::random::generate_random_bits;
+
+/// Number of bits used by the seconds variant's timestamp
+pub const SECONDS_TIMESTAMP_BITS: u32 = 32;
+/// Number of bits used by the seconds variant's sequence field
+pub const SECONDS_SEQUENCE_BITS: u32 = 16;
+/// Number of bits used by the seconds variant's random field
+pub const SECONDS_RANDOM_BITS: u32 = 16;
+
+thread_local! {
+    static LAST_SECOND: Cell<u64> = const { Cell::new(0) };
+    static SECONDS_SEQUENCE: Cell<u16> = const { Cell::new(0) };
+}
+
+/// Sleep until the next second (a spin would burn up to a full second of CPU)
+fn wait_next_second(current: u64) {
+    loop {
+        let now_ms = current_time_ms();
+        if now_ms / 1000 != current {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(1000 - now_ms % 1000));
+    }
+}
+
+/// Generate an 11-character ID with a seconds timestamp
+pub fn generate_seconds_resolution() -> String {
+    let (seconds, sequence) = LAST_SECOND.with(|last_second| {
+        SECONDS_SEQUENCE.with(|seq| {
+            let mut now = current_time_ms() / 1000;
+            let mut current_seq = seq.get();
+
+            if now == last_second.get() {
+                current_seq = current_seq.wrapping_add(1);
+
+                if current_seq == 0 {
+                    // Sequence overflow: wait for next second
+                    wait_next_second(now);
+                    now = current_time_ms() / 1000;
+                }
+            } else {
+                current_seq = 0;
+            }
+
+            last_second.set(now);
+            seq.set(current_seq);
+
+            (now, current_seq)
+        })
+    });
+
+    let random = generate_random_bits(SECONDS_RANDOM_BITS);
+
+    // [ 32 bits: seconds ] [ 16 bits: sequence ] [ 16 bits: random ]
+    let value = ((seconds & 0xFFFF_FFFF) << (SECONDS_SEQUENCE_BITS + SECONDS_RANDOM_BITS))
+        | ((sequence as u64) << SECONDS_RANDOM_BITS)
+        | random;
+
+    base64_encode_u64(value)
+}
+
+/// Extracts the second (since Unix epoch) at which a seconds-resolution ID
+/// was minted
+pub fn timestamp_secs_of(id: &str) -> Result<u64, ParseError> {
+    decode_u64(id).map(|value| value >> (SECONDS_SEQUENCE_BITS + SECONDS_RANDOM_BITS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seconds_resolution_round_trip() {
+        let before = current_time_ms() / 1000;
+        let id = generate_seconds_resolution();
+        let after = current_time_ms() / 1000;
+
+        assert_eq!(id.len(), crate::ENCODED_LEN);
+        let seconds = timestamp_secs_of(&id).unwrap();
+        assert!((before..=after).contains(&seconds));
+    }
+
+    #[test]
+    fn test_seconds_resolution_ordering() {
+        let ids: Vec<String> = (0..1000).map(|_| generate_seconds_resolution()).collect();
+        for pair in ids.windows(2) {
+            assert!(pair[0] < pair[1]);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Generator settings read from a minimal `key = value` config format.
+//!
+//! ```text
+//! # tiny64.conf
+//! epoch_ms = 1700000000000
+//! sequence_bits = 14
+//! random_bits = 8
+//! ```
+//!
+//! Blank lines and lines starting with `#` are ignored. Recognised keys are
+//! `epoch_ms`, `timestamp_bits`, `sequence_bits` and `random_bits`; any key
+//! left out keeps its default. Parsing does no I/O, so callers read the file
+//! however they like.
+
+use alloc::string::{String, ToString};
+use core::fmt;
+
+use crate::layout::{Layout, LayoutError};
+
+/// Epoch and field layout for a generator
+///
+/// Apply to a generator with [`crate::Tiny64GeneratorBuilder::settings`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Settings {
+    /// Milliseconds since the Unix epoch that timestamps count from
+    pub epoch_ms: u64,
+    /// Bit widths of the timestamp, sequence and random fields
+    pub layout: Layout,
+}
+
+/// Error returned for config text that can't be parsed into [`Settings`]
+///
+/// Line numbers start at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SettingsError {
+    /// The line is neither blank, a comment nor `key = value`
+    Syntax { line: usize },
+    /// The key is not one of the recognised settings
+    UnknownKey { line: usize, key: String },
+    /// The value is not a valid unsigned integer
+    InvalidValue { line: usize, key: String },
+    /// The bit widths don't form a valid layout
+    Layout(LayoutError),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::Syntax { line } => {
+                write!(f, "line {}: expected `key = value`", line)
+            }
+            SettingsError::UnknownKey { line, key } => {
+                write!(f, "line {}: unknown key `{}`", line, key)
+            }
+            SettingsError::InvalidValue { line, key } => {
+                write!(f, "line {}: invalid value for `{}`", line, key)
+            }
+            SettingsError::Layout(err) => write!(f, "invalid layout: {}", err),
+        }
+    }
+}
+
+impl core::error::Error for SettingsError {}
+
+impl Settings {
+    /// Parse settings from config text
+    ///
+    /// Bit widths that are left out keep their default, except that when
+    /// `timestamp_bits` is missing it takes whatever the other two leave of
+    /// the 64 bits.
+    pub fn parse(text: &str) -> Result<Settings, SettingsError> {
+        let mut epoch_ms = 0;
+        let mut timestamp_bits = None;
+        let mut sequence_bits = None;
+        let mut random_bits = None;
+        for (index, raw) in text.lines().enumerate() {
+            let line = index + 1;
+            let trimmed = raw.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let (key, value) = trimmed
+                .split_once('=')
+                .ok_or(SettingsError::Syntax { line })?;
+            let (key, value) = (key.trim(), value.trim());
+            let invalid = || SettingsError::InvalidValue {
+                line,
+                key: key.to_string(),
+            };
+            // No field can be wider than the whole ID, which also keeps the
+            // widths small enough to add up
+            let bits = || {
+                value
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|&bits| bits <= 64)
+                    .ok_or_else(invalid)
+            };
+            match key {
+                "epoch_ms" => epoch_ms = value.parse().map_err(|_| invalid())?,
+                "timestamp_bits" => timestamp_bits = Some(bits()?),
+                "sequence_bits" => sequence_bits = Some(bits()?),
+                "random_bits" => random_bits = Some(bits()?),
+                _ => {
+                    return Err(SettingsError::UnknownKey {
+                        line,
+                        key: key.to_string(),
+                    });
+                }
+            }
+        }
+
+        let default = Layout::DEFAULT;
+        let sequence_bits = sequence_bits.unwrap_or(default.sequence_bits());
+        let random_bits = random_bits.unwrap_or(default.random_bits());
+        let timestamp_bits =
+            timestamp_bits.unwrap_or_else(|| 64u32.saturating_sub(sequence_bits + random_bits));
+        let layout = Layout::new(timestamp_bits, sequence_bits, random_bits)
+            .map_err(SettingsError::Layout)?;
+        Ok(Settings { epoch_ms, layout })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_settings() {
+        let text = "# comment\n\nepoch_ms = 1700000000000\nsequence_bits=14\n  random_bits = 8  \n";
+        let settings = Settings::parse(text).unwrap();
+        assert_eq!(settings.epoch_ms, 1_700_000_000_000);
+        assert_eq!(settings.layout, Layout::new(42, 14, 8).unwrap());
+        assert_eq!(Settings::parse("").unwrap(), Settings::default());
+    }
+
+    #[test]
+    fn test_parse_settings_errors() {
+        assert_eq!(
+            Settings::parse("epoch_ms = 1\nnonsense"),
+            Err(SettingsError::Syntax { line: 2 })
+        );
+        assert_eq!(
+            Settings::parse("epoch = 1"),
+            Err(SettingsError::UnknownKey {
+                line: 1,
+                key: "epoch".to_string()
+            })
+        );
+        assert_eq!(
+            Settings::parse("random_bits = ten"),
+            Err(SettingsError::InvalidValue {
+                line: 1,
+                key: "random_bits".to_string()
+            })
+        );
+        assert_eq!(
+            Settings::parse("sequence_bits = 4294967295"),
+            Err(SettingsError::InvalidValue {
+                line: 1,
+                key: "sequence_bits".to_string()
+            })
+        );
+        assert_eq!(
+            Settings::parse("timestamp_bits = 40"),
+            Err(SettingsError::Layout(LayoutError::InvalidTotal(62)))
+        );
+    }
+}
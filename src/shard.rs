@@ -0,0 +1,206 @@
+//! Hash-prefixed IDs that spread writes across key-range shards.
+//!
+//! Layout: `[ prefix_bits: hash ] [ 42 bits: timestamp_ms ] [ 12 bits: sequence ] [ 10 - prefix_bits: random ]`.
+//!
+//! Plain IDs all land at the top of the key space, so a store that shards by
+//! key range sends every write to the same shard. Here the top `prefix_bits`
+//! are a hash of the sequence and random bits, which scatters consecutive IDs
+//! evenly over `2^prefix_bits` key ranges.
+//!
+//! The cost is global order: IDs only sort by time among those sharing a
+//! prefix, so a time-range query has to visit every shard and merge (see
+//! [`merge_sorted`](crate::merge_sorted)). The prefix also takes over low
+//! random bits, weakening collision resistance the same way
+//! [`generate_with_flags`](crate::generate_with_flags) does.
+//!
+//! [`interleave`] covers the other side of sharding: dealing IDs from several
+//! generators out to several consumers, and [`shard_of`] routes plain IDs to
+//! a fixed number of shards.
+
+#[cfg(feature = "std")]
+use alloc::string::String;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use crate::clock::Clock;
+use crate::decode::{ParseError, decode_u64};
+#[cfg(feature = "std")]
+use crate::encode::base64_encode_u64;
+#[cfg(feature = "std")]
+use crate::generator::Tiny64Generator;
+use crate::hash::mix64;
+#[cfg(feature = "std")]
+use crate::random::RandomSource;
+use crate::{RANDOM_BITS, SEQUENCE_BITS};
+
+/// Generate an ID whose top `prefix_bits` are a hash of its sequence and
+/// random bits
+///
+/// # Panics
+///
+/// Panics if `prefix_bits` exceeds the 10-bit random field it displaces.
+#[cfg(feature = "std")]
+pub fn generate_hash_prefixed(prefix_bits: u32) -> String {
+    base64_encode_u64(hash_prefixed(crate::generate_tiny64_u64(), prefix_bits))
+}
+
+/// Generate `total` IDs by pulling from each generator in turn
+///
+/// ID `i` comes from `gens[i % gens.len()]`, so dealing the output out
+/// round-robin gives every consumer the same number of IDs, each from its own
+/// generator. Give each generator a distinct node ID (see
+/// [`Tiny64GeneratorBuilder::node_id`](crate::Tiny64GeneratorBuilder::node_id))
+/// so no two of them can ever mint the same ID.
+///
+/// Each generator's IDs stay in order, but the output as a whole is not
+/// globally time-sorted: sort or [`merge_sorted`](crate::merge_sorted) it if
+/// that matters. Returns an empty vector when `gens` is empty.
+#[cfg(feature = "std")]
+pub fn interleave<R: RandomSource, C: Clock>(
+    gens: &mut [Tiny64Generator<R, C>],
+    total: usize,
+) -> Vec<String> {
+    if gens.is_empty() {
+        return Vec::new();
+    }
+    let count = gens.len();
+    (0..total).map(|i| gens[i % count].generate()).collect()
+}
+
+/// Which of `shard_count` shards `id` belongs to, from 0 to
+/// `shard_count - 1`
+///
+/// Hashes only the sequence and random bits, so IDs minted in the same
+/// stretch of time spread evenly over the shards instead of all landing on
+/// one. The hash is Lamping and Veach's jump consistent hash, which depends
+/// on nothing but the ID and `shard_count`: the answer is the same in every
+/// process, after restarts and across versions of this crate. Growing from
+/// `n` to `n + 1` shards moves only the `1 / (n + 1)` of IDs that now belong
+/// to the new shard; the rest stay put.
+///
+/// # Panics
+///
+/// Panics if `shard_count` is 0.
+pub fn shard_of(id: &str, shard_count: u32) -> Result<u32, ParseError> {
+    assert!(shard_count > 0, "at least one shard");
+    let low_bits = decode_u64(id)? & ((1 << (SEQUENCE_BITS + RANDOM_BITS)) - 1);
+    Ok(jump_hash(mix64(low_bits), shard_count))
+}
+
+/// Jump consistent hash: the bucket of `key` among `buckets`
+fn jump_hash(mut key: u64, buckets: u32) -> u32 {
+    let (mut bucket, mut next) = (0u64, 0u64);
+    while next < buckets as u64 {
+        bucket = next;
+        key = key.wrapping_mul(2_862_933_555_777_941_757).wrapping_add(1);
+        next = ((bucket + 1) as f64 * ((1u64 << 31) as f64 / ((key >> 33) + 1) as f64)) as u64;
+    }
+    bucket as u32
+}
+
+/// Shift `value` right by `prefix_bits` and fill the gap with the hash
+#[cfg(feature = "std")]
+fn hash_prefixed(value: u64, prefix_bits: u32) -> u64 {
+    assert!(
+        prefix_bits <= RANDOM_BITS,
+        "at most {} prefix bits",
+        RANDOM_BITS
+    );
+    if prefix_bits == 0 {
+        return value;
+    }
+
+    let shifted = value >> prefix_bits;
+    let low_mask = (1u64 << (SEQUENCE_BITS + RANDOM_BITS - prefix_bits)) - 1;
+    let prefix = mix64(shifted & low_mask) >> (64 - prefix_bits);
+    prefix << (64 - prefix_bits) | shifted
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::decode_u64;
+
+    #[test]
+    fn test_prefixes_spread_and_sort_within_shard() {
+        let ids: Vec<String> = (0..800).map(|_| generate_hash_prefixed(3)).collect();
+
+        let mut shards: Vec<Vec<&String>> = vec![Vec::new(); 8];
+        for id in &ids {
+            shards[(decode_u64(id).unwrap() >> 61) as usize].push(id);
+        }
+        // Consecutive IDs reach every shard, and each shard stays time-ordered
+        for shard in &shards {
+            assert!(!shard.is_empty());
+            assert!(shard.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+    }
+
+    #[test]
+    fn test_interleave_round_robin() {
+        let mut gens: Vec<_> = (0..3)
+            .map(|node| Tiny64Generator::builder().node_id(node).build().unwrap())
+            .collect();
+        let ids = interleave(&mut gens, 10);
+        assert_eq!(ids.len(), 10);
+        let unique: std::collections::HashSet<&String> = ids.iter().collect();
+        assert_eq!(unique.len(), 10);
+        for consumer in 0..3 {
+            let dealt: Vec<&String> = ids.iter().skip(consumer).step_by(3).collect();
+            assert!(dealt.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+        assert_eq!(gens[0].stats().generated, 4);
+        assert_eq!(gens[2].stats().generated, 3);
+
+        assert!(interleave::<crate::FixedRandom, crate::SystemClock>(&mut [], 5).is_empty());
+    }
+
+    #[test]
+    fn test_shard_of_is_even_and_consistent() {
+        let ids: Vec<String> = (0..8000).map(|_| crate::generate_tiny64()).collect();
+        let mut counts = [0usize; 8];
+        for id in &ids {
+            counts[shard_of(id, 8).unwrap() as usize] += 1;
+        }
+        assert!(
+            counts.iter().all(|&n| (700..1300).contains(&n)),
+            "{:?}",
+            counts
+        );
+
+        // Adding a ninth shard only moves IDs onto it
+        let moved = ids
+            .iter()
+            .filter(|id| shard_of(id, 9).unwrap() != shard_of(id, 8).unwrap())
+            .inspect(|id| assert_eq!(shard_of(id, 9), Ok(8)))
+            .count();
+        assert!((600..1200).contains(&moved), "{}", moved);
+
+        // Pinned so a change in the hash, which would reroute stored data,
+        // can't slip through
+        let id = crate::base64_encode_u64(0x0123_4567_89AB_CDEF);
+        let pinned: Vec<u32> = [1, 2, 10, 1000]
+            .iter()
+            .map(|&n| shard_of(&id, n).unwrap())
+            .collect();
+        assert_eq!(pinned, [0, 1, 1, 283]);
+        assert_eq!(shard_of("?", 4), Err(ParseError::InvalidLength(1)));
+    }
+
+    #[test]
+    fn test_hash_prefixed_keeps_time_and_sequence() {
+        let value = crate::Tiny64Parts {
+            timestamp_ms: 1_700_000_000_000,
+            sequence: 42,
+            random: 0x3FF,
+        }
+        .pack()
+        .unwrap();
+        assert_eq!(hash_prefixed(value, 0), value);
+
+        let prefixed = hash_prefixed(value, 4);
+        assert_eq!(prefixed << 4, value & !0xF);
+        assert_eq!(prefixed, hash_prefixed(value, 4));
+    }
+}
@@ -0,0 +1,206 @@
+use std::fmt;
+use std::ops::Range;
+use std::sync::Mutex;
+
+use crate::MAX_IDS_PER_MS;
+use crate::clock::{GeneratorStats, SequenceState};
+use crate::encode::base64_encode_u64;
+use crate::parts::pack_masked;
+use crate::random::generate_random_10bit;
+
+/// A thread-safe generator shared by every thread in the process
+///
+/// Where [`generate_tiny64`](crate::generate_tiny64) keeps one sequence per
+/// thread, all users of a `SharedGenerator` draw from a single sequence, so
+/// IDs from different threads never share a timestamp/sequence slot.
+#[derive(Debug, Default)]
+pub struct SharedGenerator {
+    state: Mutex<SharedState>,
+}
+
+#[derive(Debug, Default)]
+struct SharedState {
+    sequence: SequenceState,
+    stats: GeneratorStats,
+}
+
+static SHARED: SharedGenerator = SharedGenerator::new();
+
+/// The process-wide shared generator
+pub fn shared_generator() -> &'static SharedGenerator {
+    &SHARED
+}
+
+/// Error returned when a block reservation can never fit in one millisecond
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Exhausted {
+    pub requested: u16,
+}
+
+impl fmt::Display for Exhausted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cannot reserve {} sequence numbers, at most {} fit in one millisecond",
+            self.requested, MAX_IDS_PER_MS
+        )
+    }
+}
+
+impl std::error::Error for Exhausted {}
+
+/// A block of sequence numbers reserved within a single millisecond
+///
+/// Iterating mints one ID per reserved sequence number, in order, without
+/// touching the shared generator again.
+#[derive(Debug, Clone)]
+pub struct BlockReservation {
+    timestamp_ms: u64,
+    sequences: Range<u16>,
+}
+
+impl BlockReservation {
+    /// The millisecond every ID in the block is stamped with
+    pub fn timestamp_ms(&self) -> u64 {
+        self.timestamp_ms
+    }
+
+    /// The sequence numbers not yet minted from this block
+    pub fn sequences(&self) -> Range<u16> {
+        self.sequences.clone()
+    }
+}
+
+impl Iterator for BlockReservation {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        let sequence = self.sequences.next()?;
+        let value = pack_masked(self.timestamp_ms, sequence, generate_random_10bit());
+        Some(base64_encode_u64(value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.sequences.size_hint()
+    }
+}
+
+impl ExactSizeIterator for BlockReservation {}
+
+impl SharedGenerator {
+    pub const fn new() -> Self {
+        SharedGenerator {
+            state: Mutex::new(SharedState {
+                sequence: SequenceState {
+                    last_timestamp_ms: 0,
+                    sequence: 0,
+                },
+                stats: GeneratorStats {
+                    generated: 0,
+                    last_timestamp_ms: 0,
+                    max_sequence: 0,
+                    spins: 0,
+                    clock_backwards: 0,
+                },
+            }),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SharedState> {
+        // The state is a few integers updated together; a panic elsewhere
+        // can't leave it half-written, so a poisoned lock is still safe to use
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Claim `n` sequence numbers from the system clock and count them
+    fn reserve(&self, n: u16) -> (u64, u16) {
+        let mut state = self.lock();
+        let last_ms = state.sequence.last_timestamp_ms;
+        let mut backwards = false;
+        let mut clock = || {
+            let now = crate::generator::current_time_ms();
+            backwards |= now < last_ms;
+            now
+        };
+        let (timestamp_ms, start, waited) = state.sequence.reserve(n, MAX_IDS_PER_MS, &mut clock);
+        state
+            .stats
+            .record(n, timestamp_ms, start, waited, backwards);
+        (timestamp_ms, start)
+    }
+
+    /// Counters accumulated across every thread since the generator was
+    /// created
+    ///
+    /// A block from [`reserve_block`](Self::reserve_block) counts as generated
+    /// when it is reserved, not as its IDs are iterated.
+    pub fn stats(&self) -> GeneratorStats {
+        self.lock().stats
+    }
+
+    /// Generate the next Tiny64 ID
+    pub fn generate(&self) -> String {
+        let (timestamp_ms, sequence) = self.reserve(1);
+        base64_encode_u64(pack_masked(timestamp_ms, sequence, generate_random_10bit()))
+    }
+
+    /// Reserve `n` consecutive sequence numbers in a single millisecond
+    ///
+    /// If the current millisecond doesn't have `n` slots left, the block is
+    /// taken from the start of the next millisecond instead. Requests larger
+    /// than [`MAX_IDS_PER_MS`] fail with [`Exhausted`].
+    pub fn reserve_block(&self, n: u16) -> Result<BlockReservation, Exhausted> {
+        if n as usize > MAX_IDS_PER_MS {
+            return Err(Exhausted { requested: n });
+        }
+
+        let (timestamp_ms, start) = self.reserve(n);
+        Ok(BlockReservation {
+            timestamp_ms,
+            sequences: start..start + n,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode_tiny64;
+
+    #[test]
+    fn test_reserve_block() {
+        let generator = SharedGenerator::new();
+        let block = generator.reserve_block(500).unwrap();
+        assert_eq!(block.len(), 500);
+
+        let timestamp_ms = block.timestamp_ms();
+        let ids: Vec<String> = block.collect();
+        for pair in ids.windows(2) {
+            assert!(pair[0] < pair[1]);
+        }
+        for id in &ids {
+            assert_eq!(decode_tiny64(id).unwrap().timestamp_ms, timestamp_ms);
+        }
+
+        // The next ID continues after the block
+        assert!(generator.generate() > *ids.last().unwrap());
+
+        let stats = generator.stats();
+        assert_eq!(stats.generated, 501);
+        assert!(stats.max_sequence >= 499);
+    }
+
+    #[test]
+    fn test_reserve_block_too_large() {
+        let generator = SharedGenerator::new();
+        assert!(generator.reserve_block(MAX_IDS_PER_MS as u16).is_ok());
+        assert_eq!(
+            generator
+                .reserve_block(MAX_IDS_PER_MS as u16 + 1)
+                .unwrap_err(),
+            Exhausted {
+                requested: MAX_IDS_PER_MS as u16 + 1
+            }
+        );
+    }
+}
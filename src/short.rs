@@ -0,0 +1,158 @@
+//! Compact 48-bit "short" variant.
+//!
+//! Layout: `[ 32 bits: timestamp (s since Unix epoch) ] [ 8 bits: sequence ] [ 8 bits: random ]`,
+//! encoded as exactly 8 Base64 characters.
+//!
+//! The seconds timestamp covers 1970 through early 2106. Only 256 IDs per second
+//! can be minted per thread, and 8 random bits give far weaker cross-process
+//! collision resistance than the 64-bit format, so short IDs are meant for
+//! ephemeral values such as request traces, not durable keys.
+//!
+//! [`generate_short_code`] is the user-facing counterpart: 8 random
+//! characters for short URLs and coupon codes, with no timestamp at all.
+
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::time::Duration;
+
+use crate::encode::base64_encode_u48;
+use crate::generator::current_time_ms;
+use crate::radix::UNAMBIGUOUS_ALPHABET;
+use crate::random::{generate_random_10bit, generate_random_bits};
+
+/// Number of bits used by the short variant's seconds timestamp
+pub const SHORT_TIMESTAMP_BITS: u32 = 32;
+/// Number of bits used by the short variant's sequence field
+pub const SHORT_SEQUENCE_BITS: u32 = 8;
+/// Number of bits used by the short variant's random field
+pub const SHORT_RANDOM_BITS: u32 = 8;
+/// Length of an encoded short ID in characters
+pub const SHORT_ENCODED_LEN: usize = 8;
+/// Length of a short code in characters
+pub const SHORT_CODE_LEN: usize = 8;
+/// Codes [`generate_short_code_unique`] draws before giving up
+pub const SHORT_CODE_ATTEMPTS: usize = 32;
+
+thread_local! {
+    static LAST_SECOND: Cell<u64> = const { Cell::new(0) };
+    static SHORT_SEQUENCE: Cell<u8> = const { Cell::new(0) };
+}
+
+/// Sleep until the next second (a spin would burn up to a full second of CPU)
+fn wait_next_second(current: u64) {
+    loop {
+        let now_ms = current_time_ms();
+        if now_ms / 1000 != current {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(1000 - now_ms % 1000));
+    }
+}
+
+/// Generate an 8-character short ID
+pub fn generate_short_id() -> String {
+    let (seconds, sequence) = LAST_SECOND.with(|last_second| {
+        SHORT_SEQUENCE.with(|seq| {
+            let mut now = current_time_ms() / 1000;
+            let mut current_seq = seq.get();
+
+            if now == last_second.get() {
+                current_seq = current_seq.wrapping_add(1);
+
+                if current_seq == 0 {
+                    // Sequence overflow: wait for next second
+                    wait_next_second(now);
+                    now = current_time_ms() / 1000;
+                }
+            } else {
+                current_seq = 0;
+            }
+
+            last_second.set(now);
+            seq.set(current_seq);
+
+            (now, current_seq)
+        })
+    });
+
+    let random = generate_random_10bit() as u64 & 0xFF;
+
+    // [ 32 bits: seconds ] [ 8 bits: sequence ] [ 8 bits: random ]
+    let value = ((seconds & 0xFFFF_FFFF) << (SHORT_SEQUENCE_BITS + SHORT_RANDOM_BITS))
+        | ((sequence as u64) << SHORT_RANDOM_BITS)
+        | random;
+
+    base64_encode_u48(value)
+}
+
+/// Generate an 8-character random code for people to type, such as a short
+/// URL or coupon code
+///
+/// Characters come from [`UNAMBIGUOUS_ALPHABET`], so a code read off a
+/// screen or receipt has no `0`/`O` or `1`/`l` to mix up. Eight of its 40
+/// characters give about 6.6 trillion codes, roughly 42.6 bits: plenty for
+/// codes nobody should guess, but far too few to rely on chance for
+/// uniqueness, since among 3 million codes a repeat is more likely than
+/// not. Unless something else rules repeats out, use
+/// [`generate_short_code_unique`].
+pub fn generate_short_code() -> String {
+    let radix = UNAMBIGUOUS_ALPHABET.len() as u64;
+    // 40^8 divides 2^64 unevenly, but the bias is below one part in a million
+    let mut rest = generate_random_bits(64) % radix.pow(SHORT_CODE_LEN as u32);
+    let mut code = [0u8; SHORT_CODE_LEN];
+    for slot in code.iter_mut().rev() {
+        *slot = UNAMBIGUOUS_ALPHABET[(rest % radix) as usize];
+        rest /= radix;
+    }
+    String::from_utf8(code.to_vec()).unwrap()
+}
+
+/// Generate a short code that is not in `existing`
+///
+/// Draws codes with [`generate_short_code`] until one is missing from
+/// `existing`, and returns `None` after [`SHORT_CODE_ATTEMPTS`] draws that
+/// all collide. Each draw collides with probability `existing.len()` over
+/// the 6.6 trillion possible codes, so the loop almost always ends on the
+/// first draw and giving up takes a set holding nearly every code. The check
+/// only covers `existing`: codes issued concurrently elsewhere still need a
+/// unique constraint where they are stored.
+pub fn generate_short_code_unique(existing: &HashSet<String>) -> Option<String> {
+    (0..SHORT_CODE_ATTEMPTS)
+        .map(|_| generate_short_code())
+        .find(|code| !existing.contains(code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_id_format() {
+        let id = generate_short_id();
+        assert_eq!(id.len(), SHORT_ENCODED_LEN);
+        for ch in id.chars() {
+            assert!(ch.is_ascii_alphanumeric() || ch == '-' || ch == '_');
+        }
+    }
+
+    #[test]
+    fn test_short_id_ordering() {
+        let ids: Vec<String> = (0..50).map(|_| generate_short_id()).collect();
+        for pair in ids.windows(2) {
+            assert!(pair[0] < pair[1]);
+        }
+    }
+
+    #[test]
+    fn test_short_code() {
+        let code = generate_short_code();
+        assert_eq!(code.len(), SHORT_CODE_LEN);
+        assert!(code.bytes().all(|b| UNAMBIGUOUS_ALPHABET.contains(&b)));
+
+        let mut existing = HashSet::new();
+        for _ in 0..1000 {
+            let code = generate_short_code_unique(&existing).unwrap();
+            assert!(existing.insert(code));
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! Deterministic collision simulation for capacity planning.
+//!
+//! Several independent generators ("nodes") mint IDs over the same simulated
+//! milliseconds, each driven by its own [`Clock`](crate::Clock) and a
+//! [`SeededRandom`], and the results are checked for duplicates. Nodes share
+//! no state, as in a deployment without node IDs, so two nodes hitting the
+//! same timestamp and sequence slot collide whenever their random bits match.
+//! The seeds are fixed, so a given load always reports the same numbers.
+//!
+//! [`measure_collisions`] runs the same experiment live, on the system clock
+//! and the default random source, to check the format on real hardware.
+
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+use crate::clock::ClockGenerator;
+#[cfg(feature = "std")]
+use crate::clock::SystemClock;
+use crate::hash::mix64;
+#[cfg(feature = "std")]
+use crate::random::HashRandom;
+use crate::random::SeededRandom;
+
+// Arbitrary fixed start of the simulated timeline
+const START_MS: u64 = 1_700_000_000_000;
+
+/// Most IDs [`simulate`] will mint in one run, across all nodes
+///
+/// Every ID is held in memory until the duplicates are counted, so this
+/// caps a run at 1 GiB of IDs.
+pub const MAX_SIMULATED_IDS: u64 = 1 << 27;
+
+/// Outcome of [`simulate`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CollisionStats {
+    /// Number of IDs minted across all nodes
+    pub generated: u64,
+    /// Number of distinct IDs among them
+    pub unique: u64,
+    /// IDs that duplicated an earlier one, `generated - unique`
+    pub collisions: u64,
+}
+
+impl CollisionStats {
+    /// Fraction of minted IDs that were duplicates
+    pub fn collision_rate(&self) -> f64 {
+        if self.generated == 0 {
+            0.0
+        } else {
+            self.collisions as f64 / self.generated as f64
+        }
+    }
+}
+
+/// Simulate `nodes` generators each minting `ids_per_ms` IDs per millisecond
+/// for `duration_ms` milliseconds, and count the duplicates
+///
+/// Every node's clock ticks once per `ids_per_ms` reads, so all nodes walk the
+/// same milliseconds in step. Above 4096 IDs/ms a node runs out of sequence
+/// numbers and spins, which moves its clock on early exactly as a real
+/// generator would fall behind; it still mints its full share of IDs.
+///
+/// # Panics
+///
+/// Panics if the run would mint more than [`MAX_SIMULATED_IDS`] IDs.
+pub fn simulate(ids_per_ms: u32, duration_ms: u64, nodes: u32) -> CollisionStats {
+    if ids_per_ms == 0 {
+        return CollisionStats::default();
+    }
+
+    let per_node = (ids_per_ms as u64).saturating_mul(duration_ms);
+    assert!(
+        per_node.saturating_mul(nodes as u64) <= MAX_SIMULATED_IDS,
+        "simulation of {} IDs/ms for {} ms on {} node(s) exceeds {} IDs",
+        ids_per_ms,
+        duration_ms,
+        nodes,
+        MAX_SIMULATED_IDS
+    );
+    let mut ids = Vec::new();
+    for node in 0..nodes {
+        let mut reads = 0u64;
+        let clock = move || {
+            let now = START_MS + reads / ids_per_ms as u64;
+            reads += 1;
+            now
+        };
+        let random = SeededRandom::new(mix64(node as u64));
+
+        let mut generator = ClockGenerator::new(clock, random);
+        ids.extend((0..per_node).map(|_| generator.generate_u64()));
+    }
+
+    count_collisions(ids)
+}
+
+/// Like [`simulate`], but live: `nodes` generators on the system clock and
+/// [`HashRandom`] mint up to `ids_per_ms` IDs each per real millisecond for
+/// `duration_ms` milliseconds
+///
+/// The call takes `duration_ms` of wall time. Generation is paced against the
+/// monotonic clock, so a machine that can't keep up simply mints fewer IDs;
+/// compare [`CollisionStats::generated`] with the target. Unlike
+/// [`simulate`] the numbers vary from run to run. The target saturates at
+/// `u64::MAX` IDs per node and storage grows with the IDs actually minted,
+/// so an unreachable load just runs for `duration_ms`.
+#[cfg(feature = "std")]
+pub fn measure_collisions(ids_per_ms: u32, duration_ms: u64, nodes: u32) -> CollisionStats {
+    let mut generators: Vec<_> = (0..nodes)
+        .map(|_| ClockGenerator::new(SystemClock, HashRandom))
+        .collect();
+    let target = (ids_per_ms as u64).saturating_mul(duration_ms);
+    let mut ids = Vec::new();
+
+    let start = Instant::now();
+    let duration = Duration::from_millis(duration_ms);
+    let mut per_node = 0;
+    loop {
+        let elapsed = start.elapsed();
+        if elapsed >= duration {
+            break;
+        }
+        // Catch up to the share due by the end of the current millisecond
+        let due = (ids_per_ms as u64)
+            .saturating_mul(elapsed.as_millis() as u64 + 1)
+            .min(target);
+        while per_node < due {
+            ids.extend(
+                generators
+                    .iter_mut()
+                    .map(|generator| generator.generate_u64()),
+            );
+            per_node += 1;
+        }
+        core::hint::spin_loop();
+    }
+    count_collisions(ids)
+}
+
+fn count_collisions(mut ids: Vec<u64>) -> CollisionStats {
+    let generated = ids.len() as u64;
+    ids.sort_unstable();
+    ids.dedup();
+    let unique = ids.len() as u64;
+    CollisionStats {
+        generated,
+        unique,
+        collisions: generated - unique,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_node_never_collides() {
+        // Including past the 4096 IDs/ms a node can mint without spinning
+        for ids_per_ms in [1, 4096, 5000] {
+            let stats = simulate(ids_per_ms, 5, 1);
+            assert_eq!(stats.generated, ids_per_ms as u64 * 5);
+            assert_eq!(stats.unique, stats.generated);
+            assert_eq!(stats.collisions, 0);
+        }
+        assert_eq!(simulate(0, 10, 3), CollisionStats::default());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_measure_collisions() {
+        let start = Instant::now();
+        let stats = measure_collisions(10, 20, 1);
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        assert!(stats.generated > 0 && stats.generated <= 200, "{:?}", stats);
+        assert_eq!(stats.collisions, 0);
+        assert_eq!(measure_collisions(0, 5, 2), CollisionStats::default());
+    }
+
+    #[test]
+    fn test_nodes_collide_on_random_bits() {
+        let stats = simulate(1000, 10, 2);
+        assert_eq!(stats, simulate(1000, 10, 2));
+        assert_eq!(stats.generated, 20_000);
+        assert_eq!(stats.unique + stats.collisions, stats.generated);
+
+        // 10,000 shared slots with a 1/1024 chance each: about 10 collisions
+        assert!((1..40).contains(&stats.collisions), "{:?}", stats);
+        assert!(stats.collision_rate() < 0.002);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds 134217728 IDs")]
+    fn test_simulate_rejects_oversized_runs() {
+        simulate(u32::MAX, u64::MAX, u32::MAX);
+    }
+}
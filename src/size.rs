@@ -0,0 +1,58 @@
+use crate::ENCODED_LEN;
+
+/// Size of a Tiny64 ID in bytes when stored as its raw u64
+pub const fn byte_size() -> usize {
+    8
+}
+
+/// Size of a Tiny64 ID in characters when stored as a string
+pub const fn char_size() -> usize {
+    ENCODED_LEN
+}
+
+/// Storage footprint of Tiny64 next to a UUID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeComparison {
+    pub tiny64_bytes: usize,
+    pub tiny64_chars: usize,
+    pub uuid_bytes: usize,
+    /// Canonical hyphenated text form, e.g. `550e8400-e29b-41d4-a716-446655440000`
+    pub uuid_chars: usize,
+}
+
+impl SizeComparison {
+    /// Bytes saved per ID when storing the binary form
+    pub fn bytes_saved(&self) -> usize {
+        self.uuid_bytes - self.tiny64_bytes
+    }
+
+    /// Characters saved per ID when storing the text form
+    pub fn chars_saved(&self) -> usize {
+        self.uuid_chars - self.tiny64_chars
+    }
+}
+
+/// Compare Tiny64's storage footprint against a UUID's
+pub const fn size_comparison() -> SizeComparison {
+    SizeComparison {
+        tiny64_bytes: byte_size(),
+        tiny64_chars: char_size(),
+        uuid_bytes: 16,
+        uuid_chars: 36,
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::generate_tiny64;
+
+    #[test]
+    fn test_size_comparison() {
+        let sizes = size_comparison();
+        assert_eq!(sizes.tiny64_chars, generate_tiny64().len());
+        assert_eq!(sizes.tiny64_bytes, std::mem::size_of::<u64>());
+        assert_eq!(sizes.bytes_saved(), 8);
+        assert_eq!(sizes.chars_saved(), 25);
+    }
+}
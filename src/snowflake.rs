@@ -0,0 +1,119 @@
+//! IDs in the classic Twitter Snowflake layout, for tools that decode it.
+//!
+//! Layout: `[ 1 bit: 0 ] [ 41 bits: ms since the Snowflake epoch ] [ 10 bits: machine_id ] [ 12 bits: sequence ]`.
+//!
+//! This differs from Tiny64 in two ways. The timestamp is one bit narrower
+//! and counts from [`SNOWFLAKE_EPOCH_MS`] (2010-11-04) instead of the Unix
+//! epoch, so it runs out in 2079 rather than 2109. And the machine ID sits
+//! above the sequence where Tiny64 puts random bits below it, so Snowflakes
+//! from one machine are strictly increasing but carry no randomness: distinct
+//! machines must be given distinct machine IDs.
+//!
+//! The value is still a `u64` and its Base64 form still sorts like it, but
+//! decoding either with the Tiny64 functions gives meaningless fields; use
+//! [`decode_snowflake`].
+
+#[cfg(feature = "std")]
+use alloc::string::String;
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+
+#[cfg(feature = "std")]
+use crate::clock::{SequenceState, SystemClock};
+#[cfg(feature = "std")]
+use crate::error::Tiny64Error;
+
+/// The Twitter Snowflake epoch, 2010-11-04T01:42:54.657Z, in ms since the
+/// Unix epoch
+pub const SNOWFLAKE_EPOCH_MS: u64 = 1_288_834_974_657;
+/// Largest machine ID that fits in the 10-bit machine field
+pub const MAX_MACHINE_ID: u16 = (1 << MACHINE_BITS) - 1;
+
+const MACHINE_BITS: u32 = 10;
+const SEQUENCE_BITS: u32 = 12;
+#[cfg(feature = "std")]
+const TIMESTAMP_BITS: u32 = 41;
+
+/// The fields of a Snowflake ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnowflakeParts {
+    /// Milliseconds since the Unix epoch, with the Snowflake epoch added back
+    pub timestamp_ms: u64,
+    pub machine_id: u16,
+    pub sequence: u16,
+}
+
+#[cfg(feature = "std")]
+static STATE: Mutex<SequenceState> = Mutex::new(SequenceState {
+    last_timestamp_ms: 0,
+    sequence: 0,
+});
+
+/// Generate a Snowflake ID for `machine_id`, as both its integer value and
+/// its Base64 encoding
+///
+/// Sequence numbers come from one process-wide counter, so IDs from every
+/// thread are distinct; once a millisecond's 4096 are used up, the call spins
+/// until the clock ticks. Fails if `machine_id` exceeds [`MAX_MACHINE_ID`].
+/// Past 2079 the 41-bit timestamp wraps around, as it does in Snowflake.
+#[cfg(feature = "std")]
+pub fn generate_snowflake(machine_id: u16) -> Result<(u64, String), Tiny64Error> {
+    if machine_id > MAX_MACHINE_ID {
+        return Err(Tiny64Error::MachineIdOutOfRange(machine_id));
+    }
+    let (timestamp_ms, sequence, _) = STATE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .reserve(1, 1 << SEQUENCE_BITS, &mut SystemClock);
+
+    let elapsed_ms = timestamp_ms.saturating_sub(SNOWFLAKE_EPOCH_MS) & ((1 << TIMESTAMP_BITS) - 1);
+    let value = elapsed_ms << (MACHINE_BITS + SEQUENCE_BITS)
+        | (machine_id as u64) << SEQUENCE_BITS
+        | sequence as u64;
+    Ok((value, crate::base64_encode_u64(value)))
+}
+
+/// Split a Snowflake value into its fields
+pub fn decode_snowflake(value: u64) -> SnowflakeParts {
+    SnowflakeParts {
+        timestamp_ms: (value >> (MACHINE_BITS + SEQUENCE_BITS)) + SNOWFLAKE_EPOCH_MS,
+        machine_id: ((value >> SEQUENCE_BITS) & MAX_MACHINE_ID as u64) as u16,
+        sequence: (value & ((1 << SEQUENCE_BITS) - 1)) as u16,
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snowflake_round_trip() {
+        let before = crate::generator::current_time_ms();
+        let ids: Vec<(u64, String)> = (0..5000).map(|_| generate_snowflake(7).unwrap()).collect();
+        for pair in ids.windows(2) {
+            assert!(pair[0].0 < pair[1].0);
+            assert!(pair[0].1 < pair[1].1);
+        }
+
+        let (value, encoded) = &ids[0];
+        assert_eq!(crate::decode_u64(encoded), Ok(*value));
+        assert_eq!(value >> 63, 0);
+        let parts = decode_snowflake(*value);
+        assert_eq!(parts.machine_id, 7);
+        assert!(parts.timestamp_ms >= before);
+        assert!(parts.timestamp_ms <= crate::generator::current_time_ms());
+    }
+
+    #[test]
+    fn test_decode_known_snowflake() {
+        // 2013-07-31T11:03:10.723Z, machine 0x51, sequence 5
+        let parts = decode_snowflake(362_528_861_600_419_845);
+        assert_eq!(parts.timestamp_ms, 1_375_268_590_723);
+        assert_eq!((parts.machine_id, parts.sequence), (0x51, 5));
+
+        assert_eq!(
+            generate_snowflake(MAX_MACHINE_ID + 1),
+            Err(Tiny64Error::MachineIdOutOfRange(1024))
+        );
+    }
+}
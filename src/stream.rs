@@ -0,0 +1,107 @@
+//! Lazily decoding IDs from readers.
+
+use std::fmt;
+use std::io::{self, BufRead};
+
+use crate::decode::{ParseError, decode_tiny64};
+use crate::parts::Tiny64Parts;
+
+/// Error from [`read_ids`]: a failed read, or a line that is not a valid ID
+#[derive(Debug)]
+pub enum ReadIdsError {
+    Io(io::Error),
+    /// `line` is 1-based
+    Parse {
+        line: usize,
+        error: ParseError,
+    },
+}
+
+impl fmt::Display for ReadIdsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadIdsError::Io(e) => write!(f, "read failed: {}", e),
+            ReadIdsError::Parse { line, error } => write!(f, "line {}: {}", line, error),
+        }
+    }
+}
+
+impl std::error::Error for ReadIdsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadIdsError::Io(e) => Some(e),
+            ReadIdsError::Parse { error, .. } => Some(error),
+        }
+    }
+}
+
+/// Decode newline-separated IDs from `r`, one line at a time
+///
+/// Surrounding whitespace is trimmed and blank lines are skipped. A malformed
+/// line yields an error carrying its line number and iteration continues with
+/// the next line; only a read error ends the stream early.
+pub fn read_ids<R: BufRead>(r: R) -> impl Iterator<Item = Result<Tiny64Parts, ReadIdsError>> {
+    let mut lines = r.lines().enumerate();
+    let mut failed = false;
+    std::iter::from_fn(move || {
+        if failed {
+            return None;
+        }
+        loop {
+            let (index, line) = lines.next()?;
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => {
+                    failed = true;
+                    return Some(Err(ReadIdsError::Io(e)));
+                }
+            };
+
+            let id = line.trim();
+            if !id.is_empty() {
+                return Some(decode_tiny64(id).map_err(|error| ReadIdsError::Parse {
+                    line: index + 1,
+                    error,
+                }));
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_tiny64;
+
+    #[test]
+    fn test_read_ids() {
+        let a = generate_tiny64();
+        let b = generate_tiny64();
+        let input = format!("{}\n\n  {}  \nnot-an-id\n", a, b);
+
+        let results: Vec<_> = read_ids(input.as_bytes()).collect();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap(), &decode_tiny64(&a).unwrap());
+        assert_eq!(results[1].as_ref().unwrap(), &decode_tiny64(&b).unwrap());
+        match &results[2] {
+            Err(ReadIdsError::Parse { line, error }) => {
+                assert_eq!(*line, 4);
+                assert_eq!(*error, ParseError::InvalidLength(9));
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+        assert_eq!(
+            results[2].as_ref().unwrap_err().to_string(),
+            "line 4: invalid length 9, expected 11"
+        );
+    }
+
+    #[test]
+    fn test_read_ids_stops_on_io_error() {
+        // Invalid UTF-8 is reported by `lines` as an io::Error
+        let input: &[u8] = b"\xff\xfe\nzzzzzzzzzzw\n";
+        let results: Vec<_> = read_ids(input).collect();
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0], Err(ReadIdsError::Io(_))));
+    }
+}
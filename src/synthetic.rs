@@ -0,0 +1,331 @@
+//! Generation at caller-supplied times rather than the current clock.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::decode::{ParseError, decode_tiny64};
+use crate::encode::base64_encode_u64;
+use crate::error::Tiny64Error;
+use crate::parts::pack_masked;
+use crate::random::generate_random_10bit;
+use crate::{MAX_IDS_PER_MS, MAX_SEQUENCE, MAX_TIMESTAMP_MS};
+
+/// Generate an ID stamped with the given millisecond instead of the clock
+///
+/// The sequence field is 0 and the random field is freshly drawn, so repeated
+/// calls with the same timestamp only differ in their random bits.
+pub fn generate_at(timestamp_ms: u64) -> Result<String, Tiny64Error> {
+    if timestamp_ms > MAX_TIMESTAMP_MS {
+        return Err(Tiny64Error::TimestampOutOfRange(timestamp_ms));
+    }
+    Ok(base64_encode_u64(pack_masked(
+        timestamp_ms,
+        0,
+        generate_random_10bit(),
+    )))
+}
+
+/// Like [`generate_at`], but clamp a timestamp beyond 42 bits to the maximum
+/// instead of failing
+///
+/// For lenient callers that would rather mint an ID than handle an error.
+/// Every clamped ID carries [`MAX_TIMESTAMP_MS`], so it still sorts after
+/// all in-range IDs, but its precise time is lost: decoding it yields the
+/// ceiling, not the timestamp passed in.
+pub fn generate_at_clamped(timestamp_ms: u64) -> String {
+    base64_encode_u64(pack_masked(
+        timestamp_ms.min(MAX_TIMESTAMP_MS),
+        0,
+        generate_random_10bit(),
+    ))
+}
+
+/// Generate an ID stamped with the given `SystemTime`
+///
+/// Sub-millisecond precision is truncated. Times before the Unix epoch fail
+/// with [`Tiny64Error::BeforeEpoch`].
+pub fn generate_at_time(t: SystemTime) -> Result<String, Tiny64Error> {
+    let since_epoch = t
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| Tiny64Error::BeforeEpoch)?;
+    let timestamp_ms = u64::try_from(since_epoch.as_millis()).unwrap_or(u64::MAX);
+    generate_at(timestamp_ms)
+}
+
+/// Generate an ID at `hour:minute` UTC today
+///
+/// Keeps the current UTC date and replaces the time of day with
+/// `hour:minute:00.000`, then mints as [`generate_at`] does. Handy for demo
+/// data at controlled, realistic-looking times without mocking the clock.
+/// The result may lie in the future. Fails with
+/// [`Tiny64Error::InvalidTimeOfDay`] unless `hour < 24` and `minute < 60`.
+pub fn generate_with_time_override(hour: u8, minute: u8) -> Result<String, Tiny64Error> {
+    generate_at(time_override_ms(
+        crate::generator::current_time_ms(),
+        hour,
+        minute,
+    )?)
+}
+
+fn time_override_ms(now_ms: u64, hour: u8, minute: u8) -> Result<u64, Tiny64Error> {
+    if hour >= 24 || minute >= 60 {
+        return Err(Tiny64Error::InvalidTimeOfDay { hour, minute });
+    }
+    let midnight = now_ms - now_ms % 86_400_000;
+    Ok(midnight + hour as u64 * 3_600_000 + minute as u64 * 60_000)
+}
+
+/// Generate `n` IDs at the same millisecond with sequence numbers `0..n`
+///
+/// The result is a dense, sorted block, handy as fixture data for pagination
+/// and range queries. Random bits still vary per ID. Fails if `n` exceeds
+/// [`MAX_IDS_PER_MS`].
+pub fn generate_n_at(timestamp_ms: u64, n: usize) -> Result<Vec<String>, Tiny64Error> {
+    if timestamp_ms > MAX_TIMESTAMP_MS {
+        return Err(Tiny64Error::TimestampOutOfRange(timestamp_ms));
+    }
+    if n > MAX_IDS_PER_MS {
+        return Err(Tiny64Error::TooManyIds(n));
+    }
+
+    Ok((0..n as u16)
+        .map(|sequence| {
+            base64_encode_u64(pack_masked(timestamp_ms, sequence, generate_random_10bit()))
+        })
+        .collect())
+}
+
+/// Generate one ID per timestamp in `timestamps`, numbering repeats the way
+/// the live generator does
+///
+/// For replaying events that carry their own times: a run of equal
+/// timestamps gets sequence numbers 0, 1, 2, ... in order, and the sequence
+/// restarts at 0 whenever the timestamp changes, so the IDs come out strictly
+/// increasing in stream order. Random bits are freshly drawn. Like the live
+/// generator, the stream never goes backwards: a timestamp smaller than the
+/// one before it is treated as a repeat of the larger one. Nothing waits for
+/// a clock, so the 4097th ID of one millisecond fails with
+/// [`Tiny64Error::SequenceExhausted`] instead, as does every further repeat
+/// of it; a timestamp beyond 42 bits fails with
+/// [`Tiny64Error::TimestampOutOfRange`]. Failed items leave the numbering as
+/// it was.
+pub fn generate_ordered_stream(
+    timestamps: impl Iterator<Item = u64>,
+) -> impl Iterator<Item = Result<String, Tiny64Error>> {
+    let mut last: Option<(u64, u16)> = None;
+    timestamps.map(move |timestamp_ms| {
+        if timestamp_ms > MAX_TIMESTAMP_MS {
+            return Err(Tiny64Error::TimestampOutOfRange(timestamp_ms));
+        }
+        let (timestamp_ms, sequence) = match last {
+            Some((last_ms, sequence)) if timestamp_ms <= last_ms => {
+                if sequence == MAX_SEQUENCE {
+                    return Err(Tiny64Error::SequenceExhausted);
+                }
+                (last_ms, sequence + 1)
+            }
+            _ => (timestamp_ms, 0),
+        };
+        last = Some((timestamp_ms, sequence));
+        Ok(base64_encode_u64(pack_masked(
+            timestamp_ms,
+            sequence,
+            generate_random_10bit(),
+        )))
+    })
+}
+
+/// Generate `n` IDs starting now and exactly `gap_ms` apart in time
+///
+/// Each ID comes from [`generate_at`], so nothing waits for the clock: the
+/// later timestamps may lie in the future. Evenly spaced data like this is
+/// handy for testing bucketing and range queries. Fails before generating
+/// anything if the last timestamp would not fit in 42 bits.
+pub fn generate_spaced(n: usize, gap_ms: u64) -> Result<Vec<String>, Tiny64Error> {
+    spaced_from(crate::generator::current_time_ms(), n, gap_ms)
+}
+
+fn spaced_from(start_ms: u64, n: usize, gap_ms: u64) -> Result<Vec<String>, Tiny64Error> {
+    let steps = n.saturating_sub(1) as u64;
+    let last_ms = gap_ms
+        .checked_mul(steps)
+        .and_then(|span| span.checked_add(start_ms))
+        .unwrap_or(u64::MAX);
+    if last_ms > MAX_TIMESTAMP_MS {
+        return Err(Tiny64Error::TimestampOutOfRange(last_ms));
+    }
+    (0..n as u64)
+        .map(|i| generate_at(start_ms + i * gap_ms))
+        .collect()
+}
+
+/// Redraw the random bits of an existing ID, keeping its timestamp and sequence
+///
+/// A cheap retry after an insert collides: the new ID sorts in the same
+/// position relative to every other ID. The new random value always differs
+/// from the old one.
+pub fn reroll_random(id: &str) -> Result<String, ParseError> {
+    let parts = decode_tiny64(id)?;
+    let random = loop {
+        let random = generate_random_10bit();
+        if random != parts.random {
+            break random;
+        }
+    };
+    Ok(base64_encode_u64(pack_masked(
+        parts.timestamp_ms,
+        parts.sequence,
+        random,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode_tiny64, timestamp_of, verify_ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn test_generate_at_time() {
+        let t = UNIX_EPOCH + Duration::from_millis(1_705_314_225_123) + Duration::from_micros(900);
+        let id = generate_at_time(t).unwrap();
+        assert_eq!(timestamp_of(&id), Ok(1_705_314_225_123));
+    }
+
+    #[test]
+    fn test_generate_spaced() {
+        let ids = generate_spaced(5, 250).unwrap();
+        let timestamps: Vec<u64> = ids.iter().map(|id| timestamp_of(id).unwrap()).collect();
+        for pair in timestamps.windows(2) {
+            assert_eq!(pair[1] - pair[0], 250);
+        }
+        assert!(generate_spaced(0, 250).unwrap().is_empty());
+
+        // The whole series has to fit, not just its start
+        assert_eq!(spaced_from(MAX_TIMESTAMP_MS - 20, 3, 10).unwrap().len(), 3);
+        assert_eq!(
+            spaced_from(MAX_TIMESTAMP_MS - 20, 4, 10),
+            Err(Tiny64Error::TimestampOutOfRange(MAX_TIMESTAMP_MS + 10))
+        );
+        assert_eq!(
+            spaced_from(0, 3, u64::MAX),
+            Err(Tiny64Error::TimestampOutOfRange(u64::MAX))
+        );
+    }
+
+    #[test]
+    fn test_generate_n_at() {
+        let ids = generate_n_at(1_000_000, MAX_IDS_PER_MS).unwrap();
+        assert_eq!(ids.len(), MAX_IDS_PER_MS);
+        assert_eq!(verify_ordering(&ids), Ok(()));
+        for (i, id) in ids.iter().enumerate() {
+            let parts = decode_tiny64(id).unwrap();
+            assert_eq!(parts.timestamp_ms, 1_000_000);
+            assert_eq!(parts.sequence as usize, i);
+        }
+
+        assert_eq!(
+            generate_n_at(1_000_000, MAX_IDS_PER_MS + 1),
+            Err(Tiny64Error::TooManyIds(MAX_IDS_PER_MS + 1))
+        );
+    }
+
+    #[test]
+    fn test_generate_ordered_stream() {
+        let timestamps = [1_000, 1_000, 1_000, 1_005, 1_005, 1_009, 1_007, 2_000];
+        let ids: Vec<String> = generate_ordered_stream(timestamps.into_iter())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(verify_ordering(&ids), Ok(()));
+        let fields: Vec<(u64, u16)> = ids
+            .iter()
+            .map(|id| decode_tiny64(id).unwrap())
+            .map(|parts| (parts.timestamp_ms, parts.sequence))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                (1_000, 0),
+                (1_000, 1),
+                (1_000, 2),
+                (1_005, 0),
+                (1_005, 1),
+                (1_009, 0),
+                (1_009, 1),
+                (2_000, 0),
+            ]
+        );
+
+        let repeats = core::iter::repeat_n(7, MAX_IDS_PER_MS + 2).chain([8, MAX_TIMESTAMP_MS + 1]);
+        let results: Vec<_> = generate_ordered_stream(repeats).collect();
+        assert!(results[..MAX_IDS_PER_MS].iter().all(Result::is_ok));
+        assert_eq!(results[MAX_IDS_PER_MS], Err(Tiny64Error::SequenceExhausted));
+        assert_eq!(
+            results[MAX_IDS_PER_MS + 1],
+            Err(Tiny64Error::SequenceExhausted)
+        );
+        let after = decode_tiny64(results[MAX_IDS_PER_MS + 2].as_ref().unwrap()).unwrap();
+        assert_eq!((after.timestamp_ms, after.sequence), (8, 0));
+        assert_eq!(
+            results[MAX_IDS_PER_MS + 3],
+            Err(Tiny64Error::TimestampOutOfRange(MAX_TIMESTAMP_MS + 1))
+        );
+    }
+
+    #[test]
+    fn test_generate_at_time_errors() {
+        let before = UNIX_EPOCH - Duration::from_secs(1);
+        assert_eq!(generate_at_time(before), Err(Tiny64Error::BeforeEpoch));
+        assert_eq!(
+            generate_at(MAX_TIMESTAMP_MS + 1),
+            Err(Tiny64Error::TimestampOutOfRange(MAX_TIMESTAMP_MS + 1))
+        );
+    }
+
+    #[test]
+    fn test_generate_at_clamped() {
+        assert_eq!(timestamp_of(&generate_at_clamped(1_000)), Ok(1_000));
+        for timestamp_ms in [MAX_TIMESTAMP_MS, MAX_TIMESTAMP_MS + 1, u64::MAX] {
+            let id = generate_at_clamped(timestamp_ms);
+            assert_eq!(timestamp_of(&id), Ok(MAX_TIMESTAMP_MS));
+            assert!(generate_at(MAX_TIMESTAMP_MS - 1).unwrap() < id);
+        }
+    }
+
+    #[test]
+    fn test_generate_with_time_override() {
+        // 2024-01-15T10:23:45.123Z becomes 2024-01-15T18:05:00.000Z
+        assert_eq!(
+            time_override_ms(1_705_314_225_123, 18, 5),
+            Ok(1_705_341_900_000)
+        );
+        assert_eq!(
+            time_override_ms(1_705_314_225_123, 0, 0),
+            Ok(1_705_276_800_000)
+        );
+        assert_eq!(
+            time_override_ms(0, 24, 0),
+            Err(Tiny64Error::InvalidTimeOfDay {
+                hour: 24,
+                minute: 0
+            })
+        );
+        assert!(generate_with_time_override(23, 60).is_err());
+
+        let id = generate_with_time_override(23, 59).unwrap();
+        let time = crate::format_iso8601(timestamp_of(&id).unwrap());
+        assert!(time.ends_with("T23:59:00.000Z"), "{}", time);
+    }
+
+    #[test]
+    fn test_reroll_random() {
+        let id = crate::generate_tiny64();
+        let before = decode_tiny64(&id).unwrap();
+        for _ in 0..20 {
+            let after = decode_tiny64(&reroll_random(&id).unwrap()).unwrap();
+            assert_eq!(after.timestamp_ms, before.timestamp_ms);
+            assert_eq!(after.sequence, before.sequence);
+            assert_ne!(after.random, before.random);
+        }
+        assert_eq!(reroll_random("abc"), Err(ParseError::InvalidLength(3)));
+    }
+}
@@ -0,0 +1,83 @@
+//! IDs whose random bits never repeat within a millisecond.
+
+use std::sync::Mutex;
+
+use crate::clock::{SequenceState, SystemClock};
+use crate::random::generate_random_10bit;
+use crate::{MAX_RANDOM, RANDOM_BITS};
+
+/// Random values available per millisecond, and so the cap on IDs per
+/// millisecond
+const SLOTS: usize = 1 << RANDOM_BITS;
+
+struct UniqueRandomState {
+    sequence: SequenceState,
+    /// One bit per random value already used in `sequence.last_timestamp_ms`
+    used: [u64; SLOTS / 64],
+}
+
+static STATE: Mutex<UniqueRandomState> = Mutex::new(UniqueRandomState {
+    sequence: SequenceState {
+        last_timestamp_ms: 0,
+        sequence: 0,
+    },
+    used: [0; SLOTS / 64],
+});
+
+/// Generate an ID whose random bits differ from those of every other ID
+/// minted by this function in the same millisecond
+///
+/// All threads share one sequence, as with
+/// [`SharedGenerator`](crate::SharedGenerator), so the timestamp and sequence
+/// are already unique; on top of that, each ID's random value is drawn from
+/// those not yet used in its millisecond. The timestamp and random field
+/// alone then identify an ID, which keeps IDs distinct even if the sequence
+/// bits are later dropped or overwritten. The extra state is a 1024-bit set of
+/// used values, cleared each millisecond. With only 10 random bits, at most
+/// 1024 IDs fit in a millisecond instead of 4096; once they are used up, the
+/// call waits for the clock. It does nothing for collisions with other
+/// processes, whose random draws are independent.
+pub fn generate_unique_random() -> String {
+    let mut state = STATE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let previous_ms = state.sequence.last_timestamp_ms;
+    let (timestamp_ms, sequence, _) = state.sequence.reserve(1, SLOTS, &mut SystemClock);
+    if timestamp_ms != previous_ms {
+        state.used = [0; SLOTS / 64];
+    }
+
+    // Take the first free value at or after a random starting point; the
+    // capacity check above guarantees one is free
+    let start = generate_random_10bit() as usize;
+    let random = (0..SLOTS)
+        .map(|offset| (start + offset) & MAX_RANDOM as usize)
+        .find(|&value| state.used[value / 64] & (1 << (value % 64)) == 0)
+        .expect("a free random value within the millisecond's capacity");
+    state.used[random / 64] |= 1 << (random % 64);
+
+    crate::base64_encode_u64(crate::parts::pack_masked(
+        timestamp_ms,
+        sequence,
+        random as u16,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_random_values_unique_within_ms() {
+        let ids: Vec<String> = (0..5000).map(|_| generate_unique_random()).collect();
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+
+        let mut seen = HashSet::new();
+        for id in &ids {
+            let parts = crate::decode_tiny64(id).unwrap();
+            assert!(parts.sequence < SLOTS as u16);
+            assert!(seen.insert((parts.timestamp_ms, parts.random)), "{}", id);
+        }
+    }
+}
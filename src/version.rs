@@ -0,0 +1,231 @@
+//! Versioned variant that reserves part of the random field for a format
+//! version.
+//!
+//! Layout: `[ 42 bits: timestamp_ms ] [ 12 bits: sequence ] [ 4 bits: version ] [ 6 bits: random ]`.
+//!
+//! The timestamp and sequence are untouched, so versioned IDs sort and decode
+//! times exactly like plain ones. The cost is randomness: 6 random bits
+//! instead of 10, so two generators minting in the same millisecond slot
+//! collide with probability 1/64 instead of 1/1024.
+//!
+//! A plain ID carries no version field; [`version_of`] on one returns whatever
+//! its top 4 random bits happen to be. Only rely on the version where every
+//! ID was minted with [`generate_versioned`].
+
+use alloc::string::String;
+use core::fmt;
+
+use crate::decode::{ParseError, decode_u64};
+use crate::layout::Layout;
+use crate::{ENCODED_LEN, RANDOM_BITS};
+
+/// Number of bits reserved for the version
+pub const VERSION_BITS: u32 = 4;
+/// Version written by [`generate_versioned`]
+pub const FORMAT_VERSION: u8 = 1;
+
+// The version sits at the top of the random field
+const VERSION_SHIFT: u32 = RANDOM_BITS - VERSION_BITS;
+
+/// Generate an ID carrying [`FORMAT_VERSION`] in its version field
+#[cfg(feature = "std")]
+pub fn generate_versioned() -> String {
+    // Overwrite the top of the freshly drawn random field with the version
+    let version_mask = ((1u64 << VERSION_BITS) - 1) << VERSION_SHIFT;
+    let value = crate::generate_tiny64_u64() & !version_mask;
+    crate::base64_encode_u64(value | (FORMAT_VERSION as u64) << VERSION_SHIFT)
+}
+
+/// Read the version field of an ID minted with [`generate_versioned`]
+pub fn version_of(id: &str) -> Result<u8, ParseError> {
+    let value = decode_u64(id)?;
+    Ok(((value >> VERSION_SHIFT) & ((1 << VERSION_BITS) - 1)) as u8)
+}
+
+/// Best-effort guess at the layout an ID was minted with
+///
+/// Returns [`Layout::DEFAULT`] for an 11-character ID whose version field
+/// reads [`FORMAT_VERSION`], and `None` for everything else. The guess is
+/// weak in both directions. Length only identifies the encoding: every layout
+/// encodes to 11 characters, so a custom split can't be told apart from the
+/// default by its string, and is never detected. And a plain, unversioned ID
+/// has random bits where the version goes, so one in 16 of them reads as v1.
+/// Detection is only dependable where every default-layout ID was minted with
+/// [`generate_versioned`] and custom layouts carry some other marker.
+pub fn detect_layout(id: &str) -> Option<Layout> {
+    if id.chars().count() != ENCODED_LEN {
+        return None;
+    }
+    match version_of(id) {
+        Ok(FORMAT_VERSION) => Some(Layout::DEFAULT),
+        _ => None,
+    }
+}
+
+/// The first ID in a batch that doesn't match the batch's first ID, from
+/// [`validate_homogeneous`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MixedLayoutError {
+    /// The ID has a different number of characters
+    Length {
+        index: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The ID doesn't decode
+    Invalid { index: usize, error: ParseError },
+    /// The ID's version field differs
+    Version {
+        index: usize,
+        expected: u8,
+        found: u8,
+    },
+}
+
+impl MixedLayoutError {
+    /// Position of the offending ID in the batch
+    pub fn index(&self) -> usize {
+        match *self {
+            MixedLayoutError::Length { index, .. }
+            | MixedLayoutError::Invalid { index, .. }
+            | MixedLayoutError::Version { index, .. } => index,
+        }
+    }
+}
+
+impl fmt::Display for MixedLayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MixedLayoutError::Length {
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "ID {} has {} characters, expected {}",
+                index, found, expected
+            ),
+            MixedLayoutError::Invalid { index, error } => {
+                write!(f, "ID {} does not decode: {}", index, error)
+            }
+            MixedLayoutError::Version {
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "ID {} has version {}, expected {}",
+                index, found, expected
+            ),
+        }
+    }
+}
+
+impl core::error::Error for MixedLayoutError {}
+
+/// Check that every ID in a batch has the same length and version field as
+/// the first
+///
+/// Catches batches that mix formats, say IDs from two systems configured
+/// differently. Like [`detect_layout`] it can only go by what the string
+/// shows: layouts differing only in their bit split look alike, and for
+/// unversioned IDs the version field is random, so only batches minted with
+/// [`generate_versioned`] pass. An empty batch passes.
+pub fn validate_homogeneous(ids: &[String]) -> Result<(), MixedLayoutError> {
+    let Some(first) = ids.first() else {
+        return Ok(());
+    };
+    let expected_len = first.chars().count();
+    let mut expected_version = None;
+    for (index, id) in ids.iter().enumerate() {
+        let found = id.chars().count();
+        if found != expected_len {
+            return Err(MixedLayoutError::Length {
+                index,
+                expected: expected_len,
+                found,
+            });
+        }
+        let version = version_of(id).map_err(|error| MixedLayoutError::Invalid { index, error })?;
+        let expected = *expected_version.get_or_insert(version);
+        if version != expected {
+            return Err(MixedLayoutError::Version {
+                index,
+                expected,
+                found: version,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{decode_tiny64, timestamp_of};
+
+    #[test]
+    fn test_versioned_round_trip() {
+        let ids: Vec<String> = (0..100).map(|_| generate_versioned()).collect();
+        for id in &ids {
+            assert_eq!(version_of(id), Ok(FORMAT_VERSION));
+            assert!(timestamp_of(id).is_ok());
+        }
+        for pair in ids.windows(2) {
+            assert!(pair[0] < pair[1]);
+        }
+
+        // Only the low 6 bits of the random field vary
+        let parts = decode_tiny64(&ids[0]).unwrap();
+        assert_eq!(parts.random >> VERSION_SHIFT, FORMAT_VERSION as u16);
+        assert_eq!(version_of("abc"), Err(ParseError::InvalidLength(3)));
+    }
+
+    #[test]
+    fn test_validate_homogeneous() {
+        let mut ids: Vec<String> = (0..5).map(|_| generate_versioned()).collect();
+        assert_eq!(validate_homogeneous(&ids), Ok(()));
+        assert_eq!(validate_homogeneous(&[]), Ok(()));
+
+        let value = decode_u64(&ids[3]).unwrap() ^ (1 << VERSION_SHIFT);
+        ids[3] = crate::base64_encode_u64(value);
+        ids.push("short".to_string());
+        let err = validate_homogeneous(&ids).unwrap_err();
+        assert_eq!(
+            err,
+            MixedLayoutError::Version {
+                index: 3,
+                expected: FORMAT_VERSION,
+                found: FORMAT_VERSION ^ 1
+            }
+        );
+        assert_eq!(err.to_string(), "ID 3 has version 0, expected 1");
+
+        ids.remove(3);
+        assert_eq!(validate_homogeneous(&ids).unwrap_err().index(), 4);
+        let bad_char = vec![ids[0].clone(), "Obrl8O3-+Cw".to_string()];
+        assert!(matches!(
+            validate_homogeneous(&bad_char),
+            Err(MixedLayoutError::Invalid { index: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_detect_layout() {
+        assert_eq!(detect_layout(&generate_versioned()), Some(Layout::DEFAULT));
+
+        // Same value, other version nibble, padded, or another encoding
+        let value = decode_u64(&generate_versioned()).unwrap();
+        let other_version = value ^ (1 << VERSION_SHIFT);
+        assert_eq!(
+            detect_layout(&crate::base64_encode_u64(other_version)),
+            None
+        );
+        assert_eq!(
+            detect_layout(&(crate::base64_encode_u64(value) + "=")),
+            None
+        );
+        assert_eq!(detect_layout(&crate::encode_crockford(value)), None);
+        assert_eq!(detect_layout("not an id!!"), None);
+    }
+}
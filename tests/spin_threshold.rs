@@ -0,0 +1,28 @@
+//! The process-wide spin threshold, in its own test binary so that changing
+//! it can't slow down waits in the unit tests running alongside.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tiny64::{DEFAULT_SPIN_THRESHOLD, align_to_next_ms, set_spin_threshold, spin_threshold};
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+#[test]
+fn spin_threshold_round_trips_and_waits_either_way() {
+    assert_eq!(spin_threshold(), DEFAULT_SPIN_THRESHOLD);
+
+    set_spin_threshold(Duration::ZERO);
+    assert_eq!(spin_threshold(), Duration::ZERO);
+    let now = now_ms();
+    assert!(align_to_next_ms() > now);
+
+    set_spin_threshold(Duration::MAX);
+    assert_eq!(spin_threshold(), Duration::from_nanos(u64::MAX));
+    let now = now_ms();
+    assert!(align_to_next_ms() > now);
+}
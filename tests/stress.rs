@@ -0,0 +1,50 @@
+//! Multi-threaded uniqueness stress tests.
+//!
+//! Both are ignored by default; run them with `cargo test --release -- --ignored`.
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::thread;
+
+const THREADS: usize = 16;
+const IDS_PER_THREAD: usize = 100_000;
+
+/// Generate IDs on `THREADS` threads and return (total, unique) counts
+fn run(generate: fn() -> String) -> (usize, usize) {
+    let seen = Mutex::new(HashSet::with_capacity(THREADS * IDS_PER_THREAD));
+
+    thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                let ids: Vec<String> = (0..IDS_PER_THREAD).map(|_| generate()).collect();
+                seen.lock().unwrap().extend(ids);
+            });
+        }
+    });
+
+    (THREADS * IDS_PER_THREAD, seen.into_inner().unwrap().len())
+}
+
+#[test]
+#[ignore = "slow; generates 1.6M IDs"]
+fn shared_generator_is_unique_across_threads() {
+    let (total, unique) = run(|| tiny64::shared_generator().generate());
+    assert_eq!(unique, total, "{} collisions", total - unique);
+}
+
+#[test]
+#[ignore = "slow; generates 1.6M IDs"]
+fn thread_local_collisions_match_random_bits() {
+    // Each thread keeps its own sequence, so threads minting in the same
+    // millisecond reuse the same timestamp/sequence slots and only the 10
+    // random bits keep them apart. Even if all 16 threads shared every slot,
+    // 16 draws from 1024 values would leave about 0.73% duplicates; anything
+    // above 1% means the random bits are not doing their job.
+    let (total, unique) = run(tiny64::generate_tiny64);
+    let rate = (total - unique) as f64 / total as f64;
+    assert!(
+        rate < 0.01,
+        "collision rate {:.4}% is too high",
+        rate * 100.0
+    );
+}